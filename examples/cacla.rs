@@ -4,13 +4,13 @@ extern crate slog;
 
 use rsrl::{
     control::actor_critic::CACLA,
-    core::{make_shared, run, Evaluation, SerialExperiment, Parameter},
+    core::{make_shared, run, Evaluation, SerialExperiment},
     domains::{Domain, ContinuousMountainCar},
     fa::{basis::fixed::Fourier, LFA},
     geometry::Space,
     logging,
     prediction::td::TD,
-    policies::parameterised::Dirac,
+    policies::{parameterised::Dirac, PerturbedPolicy},
 };
 
 fn main() {
@@ -22,9 +22,11 @@ fn main() {
         // appropriate eligibility trace.
         let fa = LFA::scalar_output(bases.clone());
 
-        // Build a stochastic behaviour policy with exponential epsilon.
+        // Build a deterministic target policy.
         Dirac::new(fa)
     });
+    // Explore by perturbing the target policy with Gaussian noise.
+    let behaviour = make_shared(PerturbedPolicy::normal(policy.clone(), 0.1));
     let critic = make_shared({
         // Build the linear value function using a fourier basis projection and the
         // appropriate eligibility trace.
@@ -33,7 +35,7 @@ fn main() {
         TD::new(v_func, 0.01, 1.0)
     });
 
-    let mut agent = CACLA::new(critic, policy, 0.005, 1.0);
+    let mut agent = CACLA::new(critic, policy, behaviour, 0.005, 1.0);
 
     let logger = logging::root(logging::stdout());
     let domain_builder = Box::new(ContinuousMountainCar::default);
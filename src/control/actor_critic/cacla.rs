@@ -105,6 +105,15 @@ where
     }
 }
 
+impl<S, C, PT, PB> ContinuousActionValuePredictor<S> for CACLA<C, PT, PB>
+where
+    C: ContinuousActionValuePredictor<S>,
+{
+    fn predict_qsa_continuous(&mut self, s: &S, a: f64) -> f64 {
+        self.critic.borrow_mut().predict_qsa_continuous(s, a)
+    }
+}
+
 impl<S, C, PT, PB> Controller<S, PT::Action> for CACLA<C, PT, PB>
 where
     PT: ParameterisedPolicy<S>,
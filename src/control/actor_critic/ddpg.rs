@@ -0,0 +1,274 @@
+use crate::core::*;
+use crate::domains::Transition;
+use crate::fa::{Approximator, Projector, ScalarLFA, VectorLFA};
+use ndarray::Axis;
+use rand::{
+    distributions::{Distribution, Normal},
+    rngs::ThreadRng,
+    thread_rng,
+    Rng,
+};
+
+/// Features of the (scalar) action used by the critic: `[1, a, a^2]`.
+fn action_features(a: f64) -> Vector<f64> { Vector::from_vec(vec![1.0, a, a * a]) }
+
+/// Deep deterministic policy gradient, using linear function approximation.
+///
+/// The deterministic actor is given by `mu(s) = theta . phi(s)`, and the
+/// critic by `Q(s, a) = w_0(s) + w_1(s) * a + w_2(s) * a^2`, where each
+/// coefficient is linear in the critic's features of the state. The critic
+/// must therefore have exactly three outputs. At every step the transition is
+/// stored in the replay buffer and a uniformly sampled batch is used to
+/// update:
+///
+/// - the critic towards the TD target computed with the target networks;
+/// - the actor along the deterministic policy gradient,
+/// `grad_a Q(s, mu(s)) * grad_theta mu(s)`.
+///
+/// The target networks are hard copies of the online weights, refreshed after
+/// every `target_sync` updates. The behaviour policy perturbs the actor's
+/// output with zero-mean Gaussian noise.
+///
+/// # References
+/// - Silver, D., Lever, G., Heess, N., Degris, T., Wierstra, D., & Riedmiller,
+/// M. (2014). Deterministic policy gradient algorithms. In Proceedings of the
+/// 31st International Conference on Machine Learning, pp. 387-395.
+/// - Lillicrap, T. P., Hunt, J. J., Pritzel, A., Heess, N., Erez, T., Tassa,
+/// Y., Silver, D., & Wierstra, D. (2015). Continuous control with deep
+/// reinforcement learning. arXiv preprint arXiv:1509.02971.
+pub struct DDPG<S, MA, MC, R = ThreadRng> {
+    pub actor: Shared<ScalarLFA<MA>>,
+    pub critic: Shared<VectorLFA<MC>>,
+
+    pub replay: ReplayBuffer<S, f64>,
+
+    pub alpha_actor: Parameter,
+    pub alpha_critic: Parameter,
+    pub gamma: Parameter,
+    pub noise: Parameter,
+
+    batch_size: usize,
+
+    target_sync: usize,
+    target_actor: Vector<f64>,
+    target_critic: Matrix<f64>,
+
+    n_updates: usize,
+    rng: R,
+}
+
+impl<S, MA, MC> DDPG<S, MA, MC> {
+    pub fn new<T1, T2, T3>(
+        actor: Shared<ScalarLFA<MA>>,
+        critic: Shared<VectorLFA<MC>>,
+        replay: ReplayBuffer<S, f64>,
+        alpha_actor: T1,
+        alpha_critic: T2,
+        gamma: T3,
+        target_sync: usize,
+    ) -> Self
+    where
+        T1: Into<Parameter>,
+        T2: Into<Parameter>,
+        T3: Into<Parameter>,
+    {
+        assert!(target_sync > 0, "Target synchronisation interval must be positive.");
+        assert_eq!(critic.approximator.weights.cols(), 3, "The critic must have exactly three outputs.");
+
+        let target_actor = actor.approximator.weights.clone();
+        let target_critic = critic.approximator.weights.clone();
+
+        DDPG {
+            actor,
+            critic,
+
+            replay,
+
+            alpha_actor: alpha_actor.into(),
+            alpha_critic: alpha_critic.into(),
+            gamma: gamma.into(),
+            noise: Parameter::fixed(0.1),
+
+            batch_size: 32,
+
+            target_sync,
+            target_actor,
+            target_critic,
+
+            n_updates: 0,
+            rng: thread_rng(),
+        }
+    }
+
+    /// Use a given random number generator for sampling replay batches and
+    /// exploration noise.
+    pub fn with_rng<R: Rng>(self, rng: R) -> DDPG<S, MA, MC, R> {
+        DDPG {
+            actor: self.actor,
+            critic: self.critic,
+
+            replay: self.replay,
+
+            alpha_actor: self.alpha_actor,
+            alpha_critic: self.alpha_critic,
+            gamma: self.gamma,
+            noise: self.noise,
+
+            batch_size: self.batch_size,
+
+            target_sync: self.target_sync,
+            target_actor: self.target_actor,
+            target_critic: self.target_critic,
+
+            n_updates: self.n_updates,
+            rng,
+        }
+    }
+}
+
+impl<S, MA, MC, R> DDPG<S, MA, MC, R> {
+    /// Set the standard deviation of the exploration noise.
+    pub fn with_noise<T: Into<Parameter>>(mut self, noise: T) -> Self {
+        self.noise = noise.into();
+
+        self
+    }
+
+    /// Set the number of transitions sampled from the replay buffer per update.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+
+        self
+    }
+
+    fn sync_targets(&mut self) {
+        self.target_actor.assign(&self.actor.approximator.weights);
+        self.target_critic.assign(&self.critic.approximator.weights);
+    }
+}
+
+impl<S, MA, MC, R> Algorithm for DDPG<S, MA, MC, R> {
+    fn handle_terminal(&mut self) {
+        self.alpha_actor = self.alpha_actor.step();
+        self.alpha_critic = self.alpha_critic.step();
+        self.gamma = self.gamma.step();
+        self.noise = self.noise.step();
+    }
+}
+
+impl<S, MA, MC, R> OnlineLearner<S, f64> for DDPG<S, MA, MC, R>
+where
+    S: Clone,
+    MA: Projector<S>,
+    MC: Projector<S>,
+    R: Rng,
+{
+    fn handle_transition(&mut self, t: &Transition<S, f64>) {
+        self.replay.push(t.clone());
+
+        let batch = self.replay.sample(self.batch_size, &mut self.rng);
+        let n = batch.len() as f64;
+
+        let mut critic_update = Matrix::zeros(self.target_critic.dim());
+        let mut actor_update = Vector::zeros(self.target_actor.len());
+
+        for t in batch.iter() {
+            let s = t.from.state();
+            let phi_a = self.actor.projector.project_expanded(s);
+            let phi_c = self.critic.projector.project_expanded(s);
+            let coefs = phi_c.dot(&self.critic.approximator.weights);
+
+            let target = if t.terminated() {
+                t.reward
+            } else {
+                let ns = t.to.state();
+                let nmu = self.actor.projector.project_expanded(ns).dot(&self.target_actor);
+                let ncoefs = self.critic.projector.project_expanded(ns).dot(&self.target_critic);
+
+                t.reward + self.gamma * ncoefs.dot(&action_features(nmu))
+            };
+            let td_error = target - coefs.dot(&action_features(t.action));
+
+            critic_update += &phi_c
+                .insert_axis(Axis(1))
+                .dot(&(action_features(t.action) * td_error).insert_axis(Axis(0)));
+
+            let mu = phi_a.dot(&self.actor.approximator.weights);
+            let dq_da = coefs[1] + 2.0 * coefs[2] * mu;
+
+            actor_update.scaled_add(dq_da, &phi_a);
+        }
+
+        self.critic.borrow_mut().approximator.weights.scaled_add(self.alpha_critic / n, &critic_update);
+        self.actor.borrow_mut().approximator.weights.scaled_add(self.alpha_actor / n, &actor_update);
+
+        self.n_updates += 1;
+
+        if self.n_updates % self.target_sync == 0 {
+            self.sync_targets();
+        }
+    }
+}
+
+impl<S, MA: Projector<S>, MC, R: Rng> Controller<S, f64> for DDPG<S, MA, MC, R> {
+    fn sample_target(&mut self, s: &S) -> f64 { self.actor.evaluate(s).unwrap() }
+
+    fn sample_behaviour(&mut self, s: &S) -> f64 {
+        let mu = self.sample_target(s);
+
+        mu + Normal::new(0.0, self.noise.value()).sample(&mut self.rng)
+    }
+}
+
+impl<S, MA: Projector<S>, MC: Projector<S>, R: Rng> ValuePredictor<S> for DDPG<S, MA, MC, R> {
+    fn predict_v(&mut self, s: &S) -> f64 {
+        let mu = self.sample_target(s);
+
+        self.predict_qsa_continuous(s, mu)
+    }
+}
+
+impl<S, MA: Projector<S>, MC: Projector<S>, R: Rng> ContinuousActionValuePredictor<S> for DDPG<S, MA, MC, R> {
+    fn predict_qsa_continuous(&mut self, s: &S, a: f64) -> f64 {
+        self.critic.evaluate(s).unwrap().dot(&action_features(a))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::Observation;
+    use crate::fa::{basis::fixed::bias, LFA};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_tracking() {
+        // Single-step episodes in which the reward is maximised by the action 0.5:
+        let reward = |a: f64| -(a - 0.5) * (a - 0.5);
+
+        let actor = make_shared(LFA::scalar_output(bias()));
+        let critic = make_shared(LFA::vector_output(bias(), 3));
+
+        let mut agent = DDPG::new(actor, critic, ReplayBuffer::new(1000), 0.05, 0.1, 0.9, 10)
+            .with_noise(0.3)
+            .with_batch_size(16)
+            .with_rng(StdRng::seed_from_u64(0));
+
+        assert_eq!(agent.sample_target(&0), 0.0);
+
+        for _ in 0..2000 {
+            let a = agent.sample_behaviour(&0);
+
+            agent.handle_transition(&Transition {
+                from: Observation::Full(0),
+                action: a,
+                reward: reward(a),
+                to: Observation::Terminal(0),
+                truncated: false,
+            });
+        }
+
+        assert!((agent.sample_target(&0) - 0.5).abs() < 0.05);
+        assert!((agent.predict_v(&0) - 0.0).abs() < 0.05);
+    }
+}
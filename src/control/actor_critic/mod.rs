@@ -4,3 +4,4 @@ import_all!(qac);
 import_all!(tdac);
 import_all!(a2c);
 import_all!(nac);
+import_all!(ddpg);
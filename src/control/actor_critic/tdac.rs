@@ -1,9 +1,16 @@
 use crate::core::*;
 use crate::domains::Transition;
-use crate::policies::{Policy, ParameterisedPolicy};
+use crate::fa::VFunction;
+use crate::policies::{parameterised::Gibbs, Policy, ParameterisedPolicy};
 use std::marker::PhantomData;
 
 /// TD-error actor-critic.
+///
+/// The critic is updated online and its TD error, an unbiased estimate of the
+/// advantage of the action taken, is used to scale the policy gradient step of
+/// the actor. For a `Gibbs` actor with linear features the gradient of the log
+/// probability is the feature vector of the action minus its expectation under
+/// the policy.
 pub struct TDAC<C, P> {
     pub critic: Shared<C>,
     pub policy: Shared<P>,
@@ -97,3 +104,148 @@ where
         self.policy.borrow_mut().sample(s)
     }
 }
+
+/// Actor-critic with a state-value critic and a `Gibbs` actor.
+///
+/// A self-contained form of `TDAC`: the critic is a value function updated
+/// directly with the TD error, using its own step size, and the same error
+/// scales the policy gradient step of a softmax actor built over `actor_fa`.
+pub struct ActorCritic<V, F> {
+    pub critic: Shared<V>,
+    pub policy: Shared<Gibbs<F>>,
+
+    pub alpha_critic: Parameter,
+    pub alpha_actor: Parameter,
+    pub gamma: Parameter,
+}
+
+impl<V, F> ActorCritic<V, F> {
+    pub fn new<T1, T2, T3>(
+        critic: Shared<V>,
+        actor_fa: F,
+        alpha_critic: T1,
+        alpha_actor: T2,
+        gamma: T3,
+    ) -> Self
+    where
+        T1: Into<Parameter>,
+        T2: Into<Parameter>,
+        T3: Into<Parameter>,
+    {
+        ActorCritic {
+            critic,
+            policy: make_shared(Gibbs::new(actor_fa)),
+
+            alpha_critic: alpha_critic.into(),
+            alpha_actor: alpha_actor.into(),
+            gamma: gamma.into(),
+        }
+    }
+}
+
+impl<V, F> Algorithm for ActorCritic<V, F> {
+    fn handle_terminal(&mut self) {
+        self.alpha_critic = self.alpha_critic.step();
+        self.alpha_actor = self.alpha_actor.step();
+        self.gamma = self.gamma.step();
+
+        self.policy.borrow_mut().handle_terminal();
+    }
+}
+
+impl<S, V, F> OnlineLearner<S, usize> for ActorCritic<V, F>
+where
+    V: VFunction<S>,
+    Gibbs<F>: ParameterisedPolicy<S, Action = usize>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, usize>) {
+        let s = t.from.state();
+        let v = self.predict_v(s);
+        let td_error = if t.terminated() {
+            t.reward - v
+        } else {
+            t.reward + self.gamma * self.predict_v(t.to.state()) - v
+        };
+
+        self.critic.borrow_mut().update(s, self.alpha_critic * td_error).ok();
+        self.policy.borrow_mut().update(s, t.action, self.alpha_actor * td_error);
+    }
+}
+
+impl<S, V: VFunction<S>, F> ValuePredictor<S> for ActorCritic<V, F> {
+    fn predict_v(&mut self, s: &S) -> f64 { self.critic.evaluate(s).unwrap() }
+}
+
+impl<S, V: VFunction<S>, F> ActionValuePredictor<S, usize> for ActorCritic<V, F> {}
+
+impl<S, V, F> Controller<S, usize> for ActorCritic<V, F>
+where
+    Gibbs<F>: Policy<S, Action = usize>,
+{
+    fn sample_target(&mut self, s: &S) -> usize {
+        self.policy.borrow_mut().sample(s)
+    }
+
+    fn sample_behaviour(&mut self, s: &S) -> usize {
+        self.policy.borrow_mut().sample(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::Observation;
+    use crate::fa::{mocking::OneHot, LFA};
+    use crate::policies::{parameterised::Gibbs, FinitePolicy};
+    use crate::prediction::td::TD;
+
+    #[test]
+    fn test_two_armed_bandit() {
+        let critic = make_shared(TD::new(make_shared(LFA::scalar_output(OneHot::new(1))), 0.1, 1.0));
+        let policy = make_shared(Gibbs::new(LFA::vector_output(OneHot::new(1), 2)));
+
+        let mut agent = TDAC::new(critic, policy.clone(), 0.1, 1.0);
+
+        for _ in 0..1000 {
+            let a = agent.sample_behaviour(&0);
+
+            agent.handle_transition(&Transition {
+                from: Observation::Full(0),
+                action: a,
+                reward: a as f64,
+                to: Observation::Terminal(0),
+                truncated: false,
+            });
+        }
+
+        assert!(policy.borrow_mut().probabilities(&0)[1] > 0.9);
+    }
+
+    #[test]
+    fn test_actor_critic_bandit() {
+        let mut agent = ActorCritic::new(
+            make_shared(LFA::scalar_output(OneHot::new(1))),
+            LFA::vector_output(OneHot::new(1), 2),
+            0.1,
+            0.1,
+            1.0,
+        );
+
+        for _ in 0..1000 {
+            let a = agent.sample_behaviour(&0);
+
+            agent.handle_transition(&Transition {
+                from: Observation::Full(0),
+                action: a,
+                reward: a as f64,
+                to: Observation::Terminal(0),
+                truncated: false,
+            });
+        }
+
+        assert!(agent.policy.borrow_mut().probabilities(&0)[1] > 0.9);
+
+        // The critic tracks the expected reward of the improving policy:
+        assert!(agent.predict_v(&0) > 0.5);
+    }
+}
@@ -59,7 +59,8 @@ where
             let s = t.from.state();
             let baseline = self.baseline.borrow_mut().predict_qsa(s, t.action.clone());
 
-            ret = t.reward + self.gamma * ret;
+            // Returns do not carry across the episodes of a batch:
+            ret = if t.ended() { t.reward } else { t.reward + self.gamma * ret };
 
             self.policy.borrow_mut().update(s, t.action.clone(), self.alpha * (ret - baseline));
         }
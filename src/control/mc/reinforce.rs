@@ -5,11 +5,24 @@ use crate::fa::Parameterised;
 use crate::policies::{Policy, ParameterisedPolicy};
 use std::marker::PhantomData;
 
+/// Monte-Carlo policy gradient.
+///
+/// Each batch is split into its constituent episodes: the discounted return
+/// is computed for every time step and the policy is moved along
+/// `grad log pi(a | s) * G_t`. If a baseline is enabled, the mean return over
+/// the episode is subtracted from each `G_t` to reduce variance.
+///
+/// # References
+/// - Williams, R. J. (1992). Simple statistical gradient-following algorithms
+/// for connectionist reinforcement learning. Machine Learning, 8(3-4),
+/// 229-256.
 pub struct REINFORCE<P> {
     pub policy: Shared<P>,
 
     pub alpha: Parameter,
     pub gamma: Parameter,
+
+    baseline: bool,
 }
 
 impl<P> REINFORCE<P> {
@@ -23,8 +36,17 @@ impl<P> REINFORCE<P> {
 
             alpha: alpha.into(),
             gamma: gamma.into(),
+
+            baseline: false,
         }
     }
+
+    /// Toggle subtraction of the mean episode return from each update.
+    pub fn with_baseline(mut self, baseline: bool) -> Self {
+        self.baseline = baseline;
+
+        self
+    }
 }
 
 impl<P: Algorithm> Algorithm for REINFORCE<P> {
@@ -42,16 +64,33 @@ where
     P::Action: Clone,
 {
     fn handle_batch(&mut self, batch: &[Transition<S, P::Action>]) {
+        for episode in split_episodes(batch) {
+            self.update_episode(episode);
+        }
+    }
+}
+
+impl<P> REINFORCE<P> {
+    fn update_episode<S>(&mut self, batch: &[Transition<S, P::Action>])
+    where
+        P: ParameterisedPolicy<S>,
+        P::Action: Clone,
+    {
         let z = batch.len() as f64;
-        let mut ret = 0.0;
+        let rewards: Vec<f64> = batch.iter().map(|t| t.reward).collect();
+        let returns = discounted_returns(&rewards, self.gamma.value());
 
-        for t in batch.into_iter().rev() {
-            ret = t.reward + self.gamma * ret;
+        let baseline = if self.baseline {
+            returns.iter().sum::<f64>() / z
+        } else {
+            0.0
+        };
 
+        for (t, ret) in batch.into_iter().zip(returns.into_iter()) {
             self.policy.borrow_mut().update(
                 t.from.state(),
                 t.action.clone(),
-                self.alpha * ret / z
+                self.alpha * (ret - baseline) / z
             );
         }
     }
@@ -68,3 +107,62 @@ impl<P: Parameterised> Parameterised for REINFORCE<P> {
         self.policy.weights()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::Observation;
+    use crate::fa::{mocking::OneHot, LFA};
+    use crate::policies::{parameterised::Gibbs, FinitePolicy};
+
+    /// Three-step episode in which every action is chosen in the same state and
+    /// earns a reward equal to its index; all-ones is the optimal trajectory.
+    fn episode<C: Controller<usize, usize>>(agent: &mut C) -> Vec<Transition<usize, usize>> {
+        (0..3)
+            .map(|i| {
+                let a = agent.sample_behaviour(&0);
+
+                Transition {
+                    from: Observation::Full(0),
+                    action: a,
+                    reward: a as f64,
+                    to: if i == 2 { Observation::Terminal(0) } else { Observation::Full(0) },
+                    truncated: false,
+                }
+            })
+            .collect()
+    }
+
+    fn trajectory_probability(baseline: bool) -> (f64, f64) {
+        let policy = make_shared(Gibbs::new(LFA::vector_output(OneHot::new(1), 2)));
+        let mut agent = REINFORCE::new(policy.clone(), 0.5, 1.0).with_baseline(baseline);
+
+        let before = policy.borrow_mut().probabilities(&0)[1].powi(3);
+
+        for _ in 0..200 {
+            let batch = episode(&mut agent);
+
+            agent.handle_batch(&batch);
+        }
+
+        let after = policy.borrow_mut().probabilities(&0)[1].powi(3);
+
+        (before, after)
+    }
+
+    #[test]
+    fn test_improvement() {
+        let (before, after) = trajectory_probability(false);
+
+        assert!((before - 0.125).abs() < 1e-7);
+        assert!(after > 0.7);
+    }
+
+    #[test]
+    fn test_improvement_with_baseline() {
+        let (before, after) = trajectory_probability(true);
+
+        assert!((before - 0.125).abs() < 1e-7);
+        assert!(after > 0.7);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::core::*;
+use crate::domains::Transition;
+use crate::fa::{Parameterised, QFunction, delegate_weights};
+use crate::policies::{Policy, FinitePolicy};
+
+/// Differential semi-gradient SARSA for average-reward, continuing tasks.
+///
+/// Rather than discounting, the agent maintains an estimate `rho` of the
+/// long-run average reward and learns differential action-values using the
+/// TD error `r - rho + Q(s', a') - Q(s, a)`. The same error is used to update
+/// `rho` with step size `beta`.
+///
+/// # References
+/// - Sutton, R. S. and Barto, A. G. (2018). Reinforcement Learning: An
+/// Introduction (2nd ed.). MIT Press, Section 10.3.
+/// - Schwartz, A. (1993). A Reinforcement Learning Method for Maximizing
+/// Undiscounted Rewards. In Proceedings of the Tenth International Conference
+/// on Machine Learning, pp. 298-305.
+pub struct DifferentialSARSA<Q, P> {
+    pub q_func: Shared<Q>,
+    pub policy: Shared<P>,
+
+    pub alpha: Parameter,
+    pub beta: Parameter,
+
+    rho: f64,
+}
+
+impl<Q, P> DifferentialSARSA<Q, P> {
+    pub fn new<T1, T2>(q_func: Shared<Q>, policy: Shared<P>, alpha: T1, beta: T2) -> Self
+    where
+        T1: Into<Parameter>,
+        T2: Into<Parameter>,
+    {
+        DifferentialSARSA {
+            q_func,
+            policy,
+
+            alpha: alpha.into(),
+            beta: beta.into(),
+
+            rho: 0.0,
+        }
+    }
+
+    /// Return the current estimate of the average reward per step.
+    pub fn average_reward(&self) -> f64 { self.rho }
+}
+
+impl<Q, P: Algorithm> Algorithm for DifferentialSARSA<Q, P> {
+    fn handle_terminal(&mut self) {
+        self.alpha = self.alpha.step();
+        self.beta = self.beta.step();
+
+        self.policy.borrow_mut().handle_terminal();
+    }
+
+    fn handle_episode(&mut self, episode: usize) {
+        self.policy.borrow_mut().handle_episode(episode);
+    }
+}
+
+impl<S, Q, P> OnlineLearner<S, P::Action> for DifferentialSARSA<Q, P>
+where
+    Q: QFunction<S>,
+    P: FinitePolicy<S>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, P::Action>) {
+        let s = t.from.state();
+        let qsa = self.q_func.evaluate_action(s, t.action);
+        let td_error = if t.terminated() {
+            t.reward - self.rho - qsa
+        } else {
+            let ns = t.to.state();
+            let na = self.policy.borrow_mut().sample(ns);
+            let nqsna = self.q_func.evaluate_action(ns, na);
+
+            t.reward - self.rho + nqsna - qsa
+        };
+
+        self.rho += self.beta * td_error;
+        self.q_func.borrow_mut().update_action(s, t.action, self.alpha * td_error);
+    }
+}
+
+impl<S, Q, P: Policy<S>> Controller<S, P::Action> for DifferentialSARSA<Q, P> {
+    fn sample_target(&mut self, s: &S) -> P::Action {
+        self.policy.borrow_mut().sample(s)
+    }
+
+    fn sample_behaviour(&mut self, s: &S) -> P::Action {
+        self.policy.borrow_mut().sample(s)
+    }
+}
+
+impl<S, Q, P> ValuePredictor<S> for DifferentialSARSA<Q, P>
+where
+    Q: QFunction<S>,
+    P: FinitePolicy<S>,
+{
+    fn predict_v(&mut self, s: &S) -> f64 {
+        self.predict_qs(s).dot(&self.policy.borrow_mut().probabilities(s))
+    }
+}
+
+impl<S, Q, P> ActionValuePredictor<S, P::Action> for DifferentialSARSA<Q, P>
+where
+    Q: QFunction<S>,
+    P: FinitePolicy<S>,
+{
+    fn predict_qs(&mut self, s: &S) -> Vector<f64> {
+        self.q_func.evaluate(s).unwrap()
+    }
+
+    fn predict_qsa(&mut self, s: &S, a: P::Action) -> f64 {
+        self.q_func.evaluate_action(&s, a)
+    }
+}
+
+delegate_weights!(DifferentialSARSA<Q, P>, q_func: Q);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::Observation;
+    use crate::fa::{mocking::OneHot, LFA};
+    use crate::policies::fixed::Random;
+
+    #[test]
+    fn test_cyclic_average_reward() {
+        // Deterministic three-state cycle with rewards 1, 0 and 2; the long-run
+        // reward rate is therefore 1.
+        let rewards = [1.0, 0.0, 2.0];
+
+        let q_func = make_shared(LFA::vector_output(OneHot::new(3), 1));
+        let mut agent = DifferentialSARSA::new(q_func, make_shared(Random::new(1)), 0.1, 0.01);
+
+        for i in 0..3000 {
+            let s = i % 3;
+
+            agent.handle_transition(&Transition {
+                from: Observation::Full(s),
+                action: 0,
+                reward: rewards[s],
+                to: Observation::Full((s + 1) % 3),
+                truncated: false,
+            });
+        }
+
+        assert!((agent.average_reward() - 1.0).abs() < 1e-3);
+
+        // Differential values are only defined up to a constant:
+        let q0 = agent.predict_qsa(&0, 0);
+
+        assert!((agent.predict_qsa(&1, 0) - q0).abs() < 1e-3);
+        assert!((agent.predict_qsa(&2, 0) - q0 - 1.0).abs() < 1e-3);
+    }
+}
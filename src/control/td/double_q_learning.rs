@@ -0,0 +1,221 @@
+use crate::core::*;
+use crate::domains::Transition;
+use crate::fa::{Approximator, Parameterised, QFunction};
+use crate::policies::Policy;
+use crate::utils::argmax_choose;
+use rand::{rngs::ThreadRng, thread_rng, Rng};
+
+/// Double Q-learning.
+///
+/// Two independent estimators are maintained and, for each sample, one is
+/// chosen at random to be updated using the other to evaluate its greedy
+/// action. This decoupling of action selection and evaluation removes the
+/// maximisation bias of standard Q-learning.
+///
+/// # References
+/// - van Hasselt, H. (2010). Double Q-learning. In Advances in Neural
+/// Information Processing Systems, pp. 2613–2621.
+pub struct DoubleQLearning<Q, P, R = ThreadRng> {
+    pub fa_a: Shared<Q>,
+    pub fa_b: Shared<Q>,
+
+    pub policy: Shared<P>,
+
+    pub alpha: Parameter,
+    pub gamma: Parameter,
+
+    rng: R,
+}
+
+impl<Q, P> DoubleQLearning<Q, P> {
+    pub fn new<T1, T2>(
+        fa_a: Shared<Q>,
+        fa_b: Shared<Q>,
+        policy: Shared<P>,
+        alpha: T1,
+        gamma: T2,
+    ) -> Self
+    where
+        T1: Into<Parameter>,
+        T2: Into<Parameter>,
+    {
+        DoubleQLearning {
+            fa_a,
+            fa_b,
+
+            policy,
+
+            alpha: alpha.into(),
+            gamma: gamma.into(),
+
+            rng: thread_rng(),
+        }
+    }
+
+    /// Use a given random number generator for choosing the estimator to be
+    /// updated and breaking ties between greedy actions.
+    pub fn with_rng<R: Rng>(self, rng: R) -> DoubleQLearning<Q, P, R> {
+        DoubleQLearning {
+            fa_a: self.fa_a,
+            fa_b: self.fa_b,
+
+            policy: self.policy,
+
+            alpha: self.alpha,
+            gamma: self.gamma,
+
+            rng,
+        }
+    }
+}
+
+impl<Q, P, R: Rng> DoubleQLearning<Q, P, R> {
+    /// Return the target for `fa_select` at the next state of `t`, evaluating
+    /// its greedy action with `fa_eval`.
+    fn double_target<S>(
+        &mut self,
+        t: &Transition<S, usize>,
+        fa_select: &Shared<Q>,
+        fa_eval: &Shared<Q>,
+    ) -> f64
+    where
+        Q: QFunction<S>,
+    {
+        if t.terminated() {
+            t.reward
+        } else {
+            let ns = t.to.state();
+            let nqs = fa_select.evaluate(ns).unwrap();
+            let na = argmax_choose(&mut self.rng, nqs.as_slice().unwrap()).1;
+
+            t.reward + self.gamma * fa_eval.evaluate_action(ns, na)
+        }
+    }
+}
+
+impl<Q, P: Algorithm, R> Algorithm for DoubleQLearning<Q, P, R> {
+    fn handle_terminal(&mut self) {
+        self.alpha = self.alpha.step();
+        self.gamma = self.gamma.step();
+
+        self.policy.borrow_mut().handle_terminal();
+    }
+
+    fn handle_episode(&mut self, episode: usize) {
+        self.policy.borrow_mut().handle_episode(episode);
+    }
+}
+
+impl<S, Q, P, R> OnlineLearner<S, P::Action> for DoubleQLearning<Q, P, R>
+where
+    Q: QFunction<S>,
+    P: Policy<S, Action = usize>,
+    R: Rng,
+{
+    fn handle_transition(&mut self, t: &Transition<S, P::Action>) {
+        let s = t.from.state();
+        let (fa_a, fa_b) = (self.fa_a.clone(), self.fa_b.clone());
+
+        let (fa_update, fa_target) = if self.rng.gen_bool(0.5) {
+            (fa_a, fa_b)
+        } else {
+            (fa_b, fa_a)
+        };
+
+        let qsa = fa_update.evaluate_action(s, t.action);
+        let residual = self.double_target(t, &fa_update, &fa_target) - qsa;
+
+        fa_update.borrow_mut().update_action(s, t.action, self.alpha * residual);
+    }
+}
+
+impl<S, Q, P, R> Controller<S, P::Action> for DoubleQLearning<Q, P, R>
+where
+    Q: QFunction<S>,
+    P: Policy<S, Action = usize>,
+    R: Rng,
+{
+    fn sample_target(&mut self, s: &S) -> P::Action {
+        let qs = self.predict_qs(s);
+
+        argmax_choose(&mut self.rng, qs.as_slice().unwrap()).1
+    }
+
+    fn sample_behaviour(&mut self, s: &S) -> P::Action { self.policy.borrow_mut().sample(s) }
+}
+
+impl<S, Q, P, R> ValuePredictor<S> for DoubleQLearning<Q, P, R>
+where
+    Q: QFunction<S>,
+    P: Policy<S, Action = usize>,
+    R: Rng,
+{
+    fn predict_v(&mut self, s: &S) -> f64 {
+        let a = self.sample_target(s);
+
+        self.predict_qsa(s, a)
+    }
+}
+
+impl<S, Q, P, R> ActionValuePredictor<S, P::Action> for DoubleQLearning<Q, P, R>
+where
+    Q: QFunction<S>,
+    P: Policy<S, Action = usize>,
+    R: Rng,
+{
+    fn predict_qs(&mut self, s: &S) -> Vector<f64> {
+        (self.fa_a.evaluate(s).unwrap() + self.fa_b.evaluate(s).unwrap()) / 2.0
+    }
+
+    fn predict_qsa(&mut self, s: &S, a: P::Action) -> f64 {
+        (self.fa_a.evaluate_action(s, a) + self.fa_b.evaluate_action(s, a)) / 2.0
+    }
+}
+
+impl<Q: Parameterised, P, R> Parameterised for DoubleQLearning<Q, P, R> {
+    fn weights(&self) -> Matrix<f64> {
+        (self.fa_a.weights() + self.fa_b.weights()) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::Observation;
+    use crate::fa::{mocking::OneHot, LFA};
+    use crate::policies::fixed::Random;
+
+    #[test]
+    fn test_single_update() {
+        let mut agent = DoubleQLearning::new(
+            make_shared(LFA::vector_output(OneHot::new(2), 2)),
+            make_shared(LFA::vector_output(OneHot::new(2), 2)),
+            make_shared(Random::new(2)),
+            0.5,
+            0.9,
+        );
+        let t = Transition {
+            from: Observation::Full(0),
+            action: 1,
+            reward: 1.0,
+            to: Observation::Full(1),
+            truncated: false,
+        };
+
+        for _ in 0..20 {
+            let (a, b) = (agent.fa_a.weights(), agent.fa_b.weights());
+
+            agent.handle_transition(&t);
+
+            let a_changed = agent.fa_a.weights() != a;
+            let b_changed = agent.fa_b.weights() != b;
+
+            assert!(a_changed != b_changed);
+        }
+
+        // Predictions average the two estimators:
+        let mean = (agent.fa_a.evaluate_action(&0, 1) + agent.fa_b.evaluate_action(&0, 1)) / 2.0;
+
+        assert_eq!(agent.predict_qsa(&0, 1), mean);
+    }
+}
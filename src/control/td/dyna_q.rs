@@ -0,0 +1,218 @@
+use crate::core::*;
+use crate::domains::{Observation, Transition};
+use crate::fa::{Parameterised, QFunction, delegate_weights};
+use crate::policies::{fixed::Greedy, Policy};
+use rand::{rngs::ThreadRng, seq::SliceRandom, thread_rng, Rng};
+
+/// Dyna-Q integrated planning, acting and learning.
+///
+/// Each real transition is used for a standard Q-learning update and recorded
+/// in a `Model` of the environment, such as a `TabularModel`. A further
+/// `planning_steps` Q-learning updates are then performed on simulated
+/// transitions from previously observed state-action pairs, chosen uniformly
+/// at random.
+///
+/// # References
+/// - Sutton, R. S. (1990). Integrated architectures for learning, planning,
+/// and reacting based on approximating dynamic programming. In Proceedings of
+/// the 7th International Conference on Machine Learning, pp. 216-224.
+/// - Sutton, R. S. and Barto, A. G. (2017). Reinforcement Learning: An
+/// Introduction (2nd ed.). Manuscript in preparation.
+pub struct DynaQ<M, Q, P, R = ThreadRng> {
+    pub fa_theta: Shared<Q>,
+
+    pub policy: Shared<P>,
+    pub target: Greedy<Q>,
+
+    pub model: M,
+
+    pub alpha: Parameter,
+    pub gamma: Parameter,
+    pub planning_steps: usize,
+
+    rng: R,
+}
+
+impl<M, Q, P> DynaQ<M, Q, P> {
+    pub fn new<T1, T2>(
+        fa_theta: Shared<Q>,
+        policy: Shared<P>,
+        model: M,
+        alpha: T1,
+        gamma: T2,
+        planning_steps: usize,
+    ) -> Self
+    where
+        T1: Into<Parameter>,
+        T2: Into<Parameter>,
+    {
+        DynaQ {
+            fa_theta: fa_theta.clone(),
+
+            policy,
+            target: Greedy::new(fa_theta),
+
+            model,
+
+            alpha: alpha.into(),
+            gamma: gamma.into(),
+            planning_steps,
+
+            rng: thread_rng(),
+        }
+    }
+
+    /// Use a given random number generator for sampling the state-action
+    /// pairs from which simulated transitions are generated.
+    pub fn with_rng<R: Rng>(self, rng: R) -> DynaQ<M, Q, P, R> {
+        DynaQ {
+            fa_theta: self.fa_theta,
+
+            policy: self.policy,
+            target: self.target,
+
+            model: self.model,
+
+            alpha: self.alpha,
+            gamma: self.gamma,
+            planning_steps: self.planning_steps,
+
+            rng,
+        }
+    }
+}
+
+impl<M, Q, P, R> DynaQ<M, Q, P, R> {
+    fn backup<S>(&mut self, t: &Transition<S, usize>) where Q: QFunction<S> {
+        let s = t.from.state();
+        let qsa = self.fa_theta.evaluate_action(s, t.action);
+        let residual = if t.terminated() {
+            t.reward - qsa
+        } else {
+            let nqs = self.fa_theta.evaluate(t.to.state()).unwrap();
+
+            t.reward + self.gamma * nqs[self.target.argmax_qs(&nqs)] - qsa
+        };
+
+        self.fa_theta.borrow_mut().update_action(s, t.action, self.alpha * residual);
+    }
+}
+
+impl<M, Q, P: Algorithm, R> Algorithm for DynaQ<M, Q, P, R> {
+    fn handle_terminal(&mut self) {
+        self.alpha = self.alpha.step();
+        self.gamma = self.gamma.step();
+
+        self.policy.borrow_mut().handle_terminal();
+        self.target.handle_terminal();
+    }
+
+    fn handle_episode(&mut self, episode: usize) {
+        self.policy.borrow_mut().handle_episode(episode);
+    }
+}
+
+impl<S, M, Q, P, R> OnlineLearner<S, P::Action> for DynaQ<M, Q, P, R>
+where
+    S: Clone,
+    M: Model<S, usize>,
+    Q: QFunction<S>,
+    P: Policy<S, Action = usize>,
+    R: Rng,
+{
+    fn handle_transition(&mut self, t: &Transition<S, P::Action>) {
+        self.backup(t);
+        self.model.record(t);
+
+        for _ in 0..self.planning_steps {
+            let (s, a) = match self.model.observed_pairs().choose(&mut self.rng) {
+                Some(pair) => pair.clone(),
+                None => break,
+            };
+            let (reward, to) = self.model.predict(&s, &a).unwrap();
+
+            self.backup(&Transition {
+                from: Observation::Full(s),
+                action: a,
+                reward,
+                to,
+                truncated: false,
+            });
+        }
+    }
+}
+
+impl<S, M, Q, P, R> Controller<S, P::Action> for DynaQ<M, Q, P, R>
+where
+    Q: QFunction<S>,
+    P: Policy<S, Action = usize>,
+{
+    fn sample_target(&mut self, s: &S) -> P::Action { self.target.sample(s) }
+
+    fn sample_behaviour(&mut self, s: &S) -> P::Action { self.policy.borrow_mut().sample(s) }
+}
+
+impl<S, M, Q: QFunction<S>, P, R> ValuePredictor<S> for DynaQ<M, Q, P, R> {
+    fn predict_v(&mut self, s: &S) -> f64 {
+        let a = self.target.sample(s);
+
+        self.predict_qsa(s, a)
+    }
+}
+
+impl<S, M, Q, P, R, A> ActionValuePredictor<S, A> for DynaQ<M, Q, P, R>
+where
+    Q: QFunction<S>,
+    A: DiscreteAction,
+{
+    fn predict_qs(&mut self, s: &S) -> Vector<f64> {
+        self.fa_theta.evaluate(s).unwrap()
+    }
+
+    fn predict_qsa(&mut self, s: &S, a: A) -> f64 {
+        self.fa_theta.evaluate_action(&s, a.index())
+    }
+}
+
+delegate_weights!(DynaQ<M, Q, P, R>, fa_theta: Q);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::td::QLearning;
+    use crate::domains::GridWorld;
+    use crate::fa::{mocking::OneHot, LFA};
+    use crate::policies::fixed::EpsilonGreedy;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_planning_speedup() {
+        let domain_factory = || Box::new(|| GridWorld::new(8, 8).with_goal((7, 7), 1.0));
+        let late_steps = |episodes: Vec<Episode>| -> u64 {
+            episodes[1..].iter().map(|e| e.steps).sum()
+        };
+
+        let (mut dyna_steps, mut q_steps) = (0, 0);
+
+        for seed in 0..5 {
+            let q_func = make_shared(LFA::vector_output(OneHot::new(64), 4));
+            let policy = make_shared(EpsilonGreedy::from_Q_with_rng(
+                q_func.clone(), 0.1, StdRng::seed_from_u64(seed),
+            ));
+            let mut agent = DynaQ::new(q_func, policy, TabularModel::deterministic(), 0.5, 0.95, 50)
+                .with_rng(StdRng::seed_from_u64(seed));
+
+            dyna_steps += late_steps(run(SerialExperiment::new(&mut agent, domain_factory(), 1000), 10, None));
+
+            let q_func = make_shared(LFA::vector_output(OneHot::new(64), 4));
+            let policy = make_shared(EpsilonGreedy::from_Q_with_rng(
+                q_func.clone(), 0.1, StdRng::seed_from_u64(seed),
+            ));
+            let mut agent = QLearning::new(q_func, policy, 0.5, 0.95);
+
+            q_steps += late_steps(run(SerialExperiment::new(&mut agent, domain_factory(), 1000), 10, None));
+        }
+
+        assert!(dyna_steps < q_steps);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::core::*;
 use crate::domains::Transition;
-use crate::fa::{Parameterised, QFunction};
-use crate::policies::{Policy, FinitePolicy};
+use crate::fa::{Parameterised, QFunction, WeightedQFunction, delegate_weights};
+use crate::policies::{fixed::Greedy, Policy, FinitePolicy};
 use std::marker::PhantomData;
 
 /// Action probability-weighted variant of SARSA (aka "summation Q-learning").
@@ -13,12 +13,15 @@ use std::marker::PhantomData;
 /// theoretical and empirical analysis of Expected Sarsa. In Proceedings of the
 /// IEEE Symposium on Adaptive Dynamic Programming and Reinforcement Learning,
 /// pp. 177–184.
-pub struct ExpectedSARSA<Q, P> {
+pub struct ExpectedSARSA<Q, P, A = ()> {
     pub q_func: Shared<Q>,
     pub policy: Shared<P>,
+    pub target: Greedy<Q>,
 
     pub alpha: Parameter,
     pub gamma: Parameter,
+
+    averaging: A,
 }
 
 impl<Q, P> ExpectedSARSA<Q, P> {
@@ -28,42 +31,76 @@ impl<Q, P> ExpectedSARSA<Q, P> {
         T2: Into<Parameter>,
     {
         ExpectedSARSA {
-            q_func,
+            q_func: q_func.clone(),
             policy,
+            target: Greedy::new(q_func),
 
             alpha: alpha.into(),
             gamma: gamma.into(),
+
+            averaging: (),
+        }
+    }
+}
+
+impl<Q: Parameterised + 'static, P> ExpectedSARSA<Q, P> {
+    /// Maintain a Polyak average of the weights, updated after every
+    /// transition, through which all predictions are routed. The target policy
+    /// becomes greedy with respect to the averaged values, while learning and
+    /// behaviour continue on the raw weights.
+    pub fn enable_averaging(self, tau: f64) -> ExpectedSARSA<Q, P, PolyakAverage> {
+        ExpectedSARSA {
+            averaging: PolyakAverage::new(self.q_func.clone(), tau),
+
+            q_func: self.q_func,
+            policy: self.policy,
+            target: self.target,
+
+            alpha: self.alpha,
+            gamma: self.gamma,
         }
     }
 }
 
-impl<Q, P: Algorithm> Algorithm for ExpectedSARSA<Q, P> {
+impl<Q, P> ExpectedSARSA<Q, P, PolyakAverage> {
+    /// Return the averaged weights.
+    pub fn averaged_weights(&self) -> &Matrix<f64> { self.averaging.weights() }
+}
+
+impl<Q, P: Algorithm, A> Algorithm for ExpectedSARSA<Q, P, A> {
     fn handle_terminal(&mut self) {
         self.alpha = self.alpha.step();
         self.gamma = self.gamma.step();
 
         self.policy.borrow_mut().handle_terminal();
     }
+
+    fn handle_episode(&mut self, episode: usize) {
+        self.policy.borrow_mut().handle_episode(episode);
+    }
 }
 
-impl<S, Q, P> OnlineLearner<S, P::Action> for ExpectedSARSA<Q, P>
+impl<S, Q, P, A> OnlineLearner<S, P::Action> for ExpectedSARSA<Q, P, A>
 where
     Q: QFunction<S>,
     P: FinitePolicy<S>,
+    A: Averaging<S, Q>,
 {
     fn handle_transition(&mut self, t: &Transition<S, P::Action>) {
         let s = t.from.state();
-        let qsa = self.predict_qsa(s, t.action);
+        let qsa = self.q_func.evaluate_action(s, t.action);
         let residual = if t.terminated() {
             t.reward - qsa
         } else {
             let ns = t.to.state();
-            let exp_nv = self.predict_v(ns);
+            let exp_nv = self.q_func.evaluate(ns).unwrap()
+                .dot(&self.policy.borrow_mut().probabilities(ns));
 
             t.reward + self.gamma * exp_nv - qsa
         };
 
         self.q_func.borrow_mut().update_action(s, t.action, self.alpha * residual);
+        self.averaging.update();
     }
 }
 
@@ -73,32 +110,95 @@ impl<S, Q, P: Policy<S>> Controller<S, P::Action> for ExpectedSARSA<Q, P> {
     fn sample_behaviour(&mut self, s: &S) -> P::Action { self.policy.borrow_mut().sample(s) }
 }
 
-impl<S, Q, P> ValuePredictor<S> for ExpectedSARSA<Q, P>
+impl<S, Q, P> Controller<S, usize> for ExpectedSARSA<Q, P, PolyakAverage>
+where
+    Q: WeightedQFunction<S>,
+    P: Policy<S, Action = usize>,
+{
+    fn sample_target(&mut self, s: &S) -> usize {
+        let qs = Averaging::<S, Q>::evaluate(&self.averaging, &self.q_func, s).unwrap();
+
+        self.target.argmax_qs(&qs)
+    }
+
+    fn sample_behaviour(&mut self, s: &S) -> usize { self.policy.borrow_mut().sample(s) }
+}
+
+impl<S, Q, P, A> ValuePredictor<S> for ExpectedSARSA<Q, P, A>
 where
     Q: QFunction<S>,
     P: FinitePolicy<S>,
+    A: Averaging<S, Q>,
 {
     fn predict_v(&mut self, s: &S) -> f64 {
         self.predict_qs(s).dot(&self.policy.borrow_mut().probabilities(s))
     }
 }
 
-impl<S, Q, P> ActionValuePredictor<S, P::Action> for ExpectedSARSA<Q, P>
+impl<S, Q, P, A> ActionValuePredictor<S, P::Action> for ExpectedSARSA<Q, P, A>
 where
     Q: QFunction<S>,
     P: FinitePolicy<S>,
+    A: Averaging<S, Q>,
 {
     fn predict_qs(&mut self, s: &S) -> Vector<f64> {
-        self.q_func.evaluate(s).unwrap()
+        match self.averaging.evaluate(&self.q_func, s) {
+            Some(qs) => qs,
+            None => self.q_func.evaluate(s).unwrap(),
+        }
     }
 
     fn predict_qsa(&mut self, s: &S, a: P::Action) -> f64 {
-        self.q_func.evaluate_action(&s, a)
+        match self.averaging.evaluate(&self.q_func, s) {
+            Some(qs) => qs[a],
+            None => self.q_func.evaluate_action(&s, a),
+        }
     }
 }
 
-impl<Q: Parameterised, P> Parameterised for ExpectedSARSA<Q, P> {
-    fn weights(&self) -> Matrix<f64> {
-        self.q_func.weights()
+delegate_weights!(ExpectedSARSA<Q, P, A>, q_func: Q);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{Domain, GridWorld};
+    use crate::fa::{mocking::OneHot, LFA};
+    use crate::policies::fixed::Random;
+
+    #[test]
+    fn test_convergence() {
+        let q_func = make_shared(LFA::vector_output(OneHot::new(3), 4));
+        let mut agent = ExpectedSARSA::new(q_func, make_shared(Random::new(4)), 0.5, 0.9);
+
+        // Sweep every state-action pair of the corridor until convergence:
+        for _ in 0..1000 {
+            for s in 0..2 {
+                for a in 0..4 {
+                    let mut domain = GridWorld::new(3, 1)
+                        .with_start((s, 0))
+                        .with_goal((2, 0), 1.0);
+
+                    agent.handle_transition(&domain.step(a));
+                }
+            }
+        }
+
+        // Solving the Bellman equations of the uniform random policy, with only
+        // the right move (3) leaving the start cell, yields V = (18/41, 26/41):
+        let v = [18.0 / 41.0, 26.0 / 41.0];
+
+        assert!((agent.predict_v(&0) - v[0]).abs() < 1e-10);
+        assert!((agent.predict_v(&1) - v[1]).abs() < 1e-10);
+
+        let expected = [
+            [0.9 * v[0], 0.9 * v[0], 0.9 * v[0], 0.9 * v[1]],
+            [0.9 * v[1], 0.9 * v[1], 0.9 * v[0], 1.0],
+        ];
+
+        for s in 0..2 {
+            for a in 0..4 {
+                assert!((agent.predict_qsa(&s, a) - expected[s][a]).abs() < 1e-10);
+            }
+        }
     }
 }
@@ -1,5 +1,8 @@
 // Off-policy:
 import_all!(q_learning);
+import_all!(double_q_learning);
+import_all!(dyna_q);
+import_all!(prioritized_sweeping);
 import_all!(q_lambda);
 import_all!(q_sigma);
 import_all!(pal);
@@ -7,7 +10,11 @@ import_all!(pal);
 // On-policy:
 import_all!(sarsa);
 import_all!(sarsa_lambda);
+import_all!(n_step_sarsa);
 import_all!(expected_sarsa);
 
+// Average reward:
+import_all!(differential_sarsa);
+
 // TODO:
 // PQ(lambda) - http://proceedings.mlr.press/v32/sutton14.pdf
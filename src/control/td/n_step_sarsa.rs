@@ -0,0 +1,211 @@
+use crate::core::*;
+use crate::domains::Transition;
+use crate::fa::{Parameterised, QFunction, delegate_weights};
+use crate::policies::{Policy, FinitePolicy};
+use std::collections::VecDeque;
+
+/// On-policy n-step SARSA.
+///
+/// Transitions are buffered until `n` have been observed, at which point the
+/// oldest state-action pair is updated towards the n-step return, bootstrapped
+/// from the value of the newest. On termination, all remaining transitions are
+/// updated using their (shorter) Monte-Carlo returns.
+///
+/// # References
+/// - Sutton, R. S., & Barto, A. G. (2018). Reinforcement learning: An
+/// introduction (2nd ed.), Section 7.2. MIT Press.
+pub struct NStepSARSA<S, Q, P> {
+    pub q_func: Shared<Q>,
+    pub policy: Shared<P>,
+
+    pub alpha: Parameter,
+    pub gamma: Parameter,
+
+    n_steps: usize,
+    buffer: VecDeque<Transition<S, usize>>,
+}
+
+impl<S, Q, P> NStepSARSA<S, Q, P> {
+    pub fn new<T1, T2>(
+        n_steps: usize,
+        q_func: Shared<Q>,
+        policy: Shared<P>,
+        alpha: T1,
+        gamma: T2,
+    ) -> Self
+    where
+        T1: Into<Parameter>,
+        T2: Into<Parameter>,
+    {
+        assert!(n_steps > 0, "NStepSARSA requires a horizon of at least one step.");
+
+        NStepSARSA {
+            q_func,
+            policy,
+
+            alpha: alpha.into(),
+            gamma: gamma.into(),
+
+            n_steps,
+            buffer: VecDeque::with_capacity(n_steps),
+        }
+    }
+}
+
+impl<S, Q: QFunction<S>, P> NStepSARSA<S, Q, P> {
+    /// Update the oldest buffered state-action pair towards the discounted sum
+    /// of buffered rewards plus a bootstrapped `tail` value.
+    fn update_head(&mut self, tail: f64) {
+        let rewards: Vec<f64> = self.buffer.iter().map(|t| t.reward).collect();
+        let ret = n_step_return(&rewards, tail, self.gamma.value());
+
+        if let Some(t) = self.buffer.pop_front() {
+            let s = t.from.state();
+            let qsa = self.q_func.evaluate_action(s, t.action);
+
+            self.q_func.borrow_mut().update_action(s, t.action, self.alpha * (ret - qsa));
+        }
+    }
+}
+
+impl<S, Q, P: Algorithm> Algorithm for NStepSARSA<S, Q, P> {
+    fn handle_terminal(&mut self) {
+        self.alpha = self.alpha.step();
+        self.gamma = self.gamma.step();
+
+        self.buffer.clear();
+        self.policy.borrow_mut().handle_terminal();
+    }
+
+    fn handle_episode(&mut self, episode: usize) {
+        self.policy.borrow_mut().handle_episode(episode);
+    }
+}
+
+impl<S: Clone, Q, P> OnlineLearner<S, P::Action> for NStepSARSA<S, Q, P>
+where
+    Q: QFunction<S>,
+    P: FinitePolicy<S>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, P::Action>) {
+        self.buffer.push_back(t.clone());
+
+        if t.terminated() {
+            while !self.buffer.is_empty() {
+                self.update_head(0.0);
+            }
+        } else if t.truncated || self.buffer.len() >= self.n_steps {
+            let ns = t.to.state();
+            let na = self.policy.borrow_mut().sample(ns);
+            let nqsna = self.q_func.evaluate_action(ns, na);
+
+            // On truncation the whole buffer is flushed, bootstrapping from `ns`:
+            let n_updates = if t.truncated { self.buffer.len() } else { 1 };
+
+            for _ in 0..n_updates {
+                self.update_head(nqsna);
+            }
+        }
+    }
+}
+
+impl<S, Q, P: Policy<S>> Controller<S, P::Action> for NStepSARSA<S, Q, P> {
+    fn sample_target(&mut self, s: &S) -> P::Action {
+        self.policy.borrow_mut().sample(s)
+    }
+
+    fn sample_behaviour(&mut self, s: &S) -> P::Action {
+        self.policy.borrow_mut().sample(s)
+    }
+}
+
+impl<S, Q, P> ValuePredictor<S> for NStepSARSA<S, Q, P>
+where
+    Q: QFunction<S>,
+    P: FinitePolicy<S>,
+{
+    fn predict_v(&mut self, s: &S) -> f64 {
+        self.predict_qs(s).dot(&self.policy.borrow_mut().probabilities(s))
+    }
+}
+
+impl<S, Q, P> ActionValuePredictor<S, P::Action> for NStepSARSA<S, Q, P>
+where
+    Q: QFunction<S>,
+    P: FinitePolicy<S>,
+{
+    fn predict_qs(&mut self, s: &S) -> Vector<f64> {
+        self.q_func.evaluate(s).unwrap()
+    }
+
+    fn predict_qsa(&mut self, s: &S, a: P::Action) -> f64 {
+        self.q_func.evaluate_action(&s, a)
+    }
+}
+
+delegate_weights!(NStepSARSA<S, Q, P>, q_func: Q);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::td::SARSA;
+    use crate::domains::tests::corridor;
+    use crate::fa::{mocking::OneHot, VectorLFA, LFA};
+    use crate::policies::fixed::Random;
+
+    const LENGTH: usize = 5;
+
+    fn values<L: ActionValuePredictor<usize, usize>>(agent: &mut L) -> Vec<f64> {
+        (0..LENGTH - 1).map(|s| agent.predict_qsa(&s, 0)).collect()
+    }
+
+    fn train<L: OnlineLearner<usize, usize>>(agent: &mut L, n_episodes: usize) {
+        for _ in 0..n_episodes {
+            corridor(LENGTH).iter().for_each(|t| agent.handle_transition(t));
+
+            agent.handle_terminal();
+        }
+    }
+
+    fn n_step_sarsa(n: usize) -> NStepSARSA<usize, VectorLFA<OneHot>, Random> {
+        NStepSARSA::new(
+            n,
+            make_shared(LFA::vector_output(OneHot::new(LENGTH), 1)),
+            make_shared(Random::new(1)),
+            0.5,
+            1.0,
+        )
+    }
+
+    #[test]
+    fn test_one_step_matches_sarsa() {
+        let mut sarsa = SARSA::new(
+            make_shared(LFA::vector_output(OneHot::new(LENGTH), 1)),
+            make_shared(Random::new(1)),
+            0.5,
+            1.0,
+        );
+        let mut n_step = n_step_sarsa(1);
+
+        for _ in 0..5 {
+            train(&mut sarsa, 1);
+            train(&mut n_step, 1);
+
+            for (q1, q2) in values(&mut sarsa).into_iter().zip(values(&mut n_step)) {
+                assert!((q1 - q2).abs() < 1e-7);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reward_propagation() {
+        let mut one_step = n_step_sarsa(1);
+        let mut three_step = n_step_sarsa(3);
+
+        train(&mut one_step, 1);
+        train(&mut three_step, 1);
+
+        assert_eq!(values(&mut one_step), vec![0.0, 0.0, 0.0, 0.5]);
+        assert_eq!(values(&mut three_step), vec![0.0, 0.5, 0.5, 0.5]);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::core::*;
 use crate::domains::Transition;
-use crate::fa::{Parameterised, QFunction};
+use crate::fa::{Parameterised, QFunction, delegate_weights};
 use crate::policies::{fixed::Greedy, Policy};
 
 /// Persistent Advantage Learning
@@ -44,6 +44,10 @@ impl<Q, P: Algorithm> Algorithm for PAL<Q, P> {
         self.policy.borrow_mut().handle_terminal();
         self.target.handle_terminal();
     }
+
+    fn handle_episode(&mut self, episode: usize) {
+        self.policy.borrow_mut().handle_episode(episode);
+    }
 }
 
 impl<S, Q, P> OnlineLearner<S, P::Action> for PAL<Q, P>
@@ -109,8 +113,4 @@ where
     }
 }
 
-impl<Q: Parameterised, P> Parameterised for PAL<Q, P> {
-    fn weights(&self) -> Matrix<f64> {
-        self.q_func.weights()
-    }
-}
+delegate_weights!(PAL<Q, P>, q_func: Q);
@@ -0,0 +1,317 @@
+use crate::core::*;
+use crate::domains::Transition;
+use crate::fa::{Parameterised, QFunction, delegate_weights};
+use crate::policies::{fixed::Greedy, Policy};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+/// Max-heap entry for a state-action pair, ordered by priority alone.
+struct Entry<S> {
+    priority: f64,
+    key: (S, usize),
+}
+
+impl<S> PartialEq for Entry<S> {
+    fn eq(&self, other: &Entry<S>) -> bool { self.priority == other.priority }
+}
+
+impl<S> Eq for Entry<S> {}
+
+impl<S> PartialOrd for Entry<S> {
+    fn partial_cmp(&self, other: &Entry<S>) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl<S> Ord for Entry<S> {
+    fn cmp(&self, other: &Entry<S>) -> Ordering {
+        self.priority.partial_cmp(&other.priority).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Prioritized sweeping.
+///
+/// Rather than updating from real experience directly, each transition is
+/// recorded in a `Model` and its state-action pair queued with priority given
+/// by the magnitude of its temporal-difference error. Up to `budget` of the
+/// highest priority pairs are then updated from the model after every step,
+/// and the predecessors of each updated state are queued in turn if their
+/// priority exceeds the threshold `theta`. Value changes thus propagate
+/// backwards from where they occur.
+///
+/// The queue holds at most one entry per pair, retaining the highest
+/// priority seen since it was last updated. It is backed by a binary heap in
+/// which superseded entries are invalidated lazily, and skipped when popped.
+///
+/// # References
+/// - Moore, A. W., & Atkeson, C. G. (1993). Prioritized sweeping:
+/// Reinforcement learning with less data and less time. Machine Learning,
+/// 13(1), 103-130.
+/// - Sutton, R. S. and Barto, A. G. (2017). Reinforcement Learning: An
+/// Introduction (2nd ed.). Manuscript in preparation.
+pub struct PrioritizedSweeping<S, M, Q, P> {
+    pub fa_theta: Shared<Q>,
+    pub model: M,
+
+    pub policy: Shared<P>,
+    pub target: Greedy<Q>,
+
+    pub alpha: Parameter,
+    pub gamma: Parameter,
+
+    pub theta: f64,
+    pub budget: usize,
+
+    queue: HashMap<(S, usize), f64>,
+    heap: BinaryHeap<Entry<S>>,
+}
+
+impl<S: Hash + Eq + Clone, M, Q, P> PrioritizedSweeping<S, M, Q, P> {
+    pub fn new<T1, T2>(
+        fa_theta: Shared<Q>,
+        model: M,
+        policy: Shared<P>,
+        alpha: T1,
+        gamma: T2,
+        theta: f64,
+        budget: usize,
+    ) -> Self
+    where
+        T1: Into<Parameter>,
+        T2: Into<Parameter>,
+    {
+        PrioritizedSweeping {
+            fa_theta: fa_theta.clone(),
+            model,
+
+            policy,
+            target: Greedy::new(fa_theta),
+
+            alpha: alpha.into(),
+            gamma: gamma.into(),
+
+            theta,
+            budget,
+
+            queue: HashMap::new(),
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    /// Number of state-action pairs awaiting an update.
+    pub fn queue_len(&self) -> usize { self.queue.len() }
+
+    fn enqueue(&mut self, key: (S, usize), priority: f64) {
+        if priority <= self.theta {
+            return;
+        }
+
+        let current = self.queue.entry(key.clone()).or_insert(0.0);
+
+        if priority > *current {
+            *current = priority;
+
+            self.heap.push(Entry { priority, key });
+        }
+    }
+
+    fn pop(&mut self) -> Option<(S, usize)> {
+        while let Some(Entry { priority, key }) = self.heap.pop() {
+            if self.queue.get(&key) == Some(&priority) {
+                self.queue.remove(&key);
+
+                return Some(key);
+            }
+        }
+
+        None
+    }
+}
+
+impl<S, M, Q, P> PrioritizedSweeping<S, M, Q, P>
+where
+    S: Hash + Eq + Clone,
+    M: Model<S, usize>,
+    Q: QFunction<S>,
+{
+    /// Compute the modelled temporal-difference error of the pair `(s, a)`.
+    fn td_error(&mut self, s: &S, a: usize) -> f64 {
+        let (reward, to) = self.model.predict(s, &a).unwrap();
+        let qsa = self.fa_theta.evaluate_action(s, a);
+
+        if to.is_terminal() {
+            reward - qsa
+        } else {
+            let nqs = self.fa_theta.evaluate(to.state()).unwrap();
+
+            reward + self.gamma * nqs[self.target.argmax_qs(&nqs)] - qsa
+        }
+    }
+
+    fn sweep(&mut self) {
+        for _ in 0..self.budget {
+            let (s, a) = match self.pop() {
+                Some(key) => key,
+                None => break,
+            };
+
+            let td_error = self.td_error(&s, a);
+
+            self.fa_theta.borrow_mut().update_action(&s, a, self.alpha * td_error);
+
+            for (ps, pa) in self.model.predecessors(&s) {
+                let priority = self.td_error(&ps, pa).abs();
+
+                self.enqueue((ps, pa), priority);
+            }
+        }
+    }
+}
+
+impl<S, M, Q, P: Algorithm> Algorithm for PrioritizedSweeping<S, M, Q, P> {
+    fn handle_terminal(&mut self) {
+        self.alpha = self.alpha.step();
+        self.gamma = self.gamma.step();
+
+        self.policy.borrow_mut().handle_terminal();
+        self.target.handle_terminal();
+    }
+
+    fn handle_episode(&mut self, episode: usize) {
+        self.policy.borrow_mut().handle_episode(episode);
+    }
+}
+
+impl<S, M, Q, P> OnlineLearner<S, P::Action> for PrioritizedSweeping<S, M, Q, P>
+where
+    S: Hash + Eq + Clone,
+    M: Model<S, usize>,
+    Q: QFunction<S>,
+    P: Policy<S, Action = usize>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, P::Action>) {
+        let s = t.from.state().clone();
+
+        self.model.record(t);
+
+        let priority = self.td_error(&s, t.action).abs();
+
+        self.enqueue((s, t.action), priority);
+        self.sweep();
+    }
+}
+
+impl<S, M, Q, P> Controller<S, P::Action> for PrioritizedSweeping<S, M, Q, P>
+where
+    Q: QFunction<S>,
+    P: Policy<S, Action = usize>,
+{
+    fn sample_target(&mut self, s: &S) -> P::Action { self.target.sample(s) }
+
+    fn sample_behaviour(&mut self, s: &S) -> P::Action { self.policy.borrow_mut().sample(s) }
+}
+
+impl<S, M, Q: QFunction<S>, P> ValuePredictor<S> for PrioritizedSweeping<S, M, Q, P> {
+    fn predict_v(&mut self, s: &S) -> f64 {
+        let a = self.target.sample(s);
+
+        self.predict_qsa(s, a)
+    }
+}
+
+impl<S, M, Q, P, A> ActionValuePredictor<S, A> for PrioritizedSweeping<S, M, Q, P>
+where
+    Q: QFunction<S>,
+    A: DiscreteAction,
+{
+    fn predict_qs(&mut self, s: &S) -> Vector<f64> {
+        self.fa_theta.evaluate(s).unwrap()
+    }
+
+    fn predict_qsa(&mut self, s: &S, a: A) -> f64 {
+        self.fa_theta.evaluate_action(&s, a.index())
+    }
+}
+
+delegate_weights!(PrioritizedSweeping<S, M, Q, P>, fa_theta: Q);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{Domain, GridWorld};
+    use crate::fa::{mocking::OneHot, VectorLFA, LFA};
+    use crate::policies::fixed::Random;
+
+    const RIGHT: usize = 3;
+
+    fn agent(budget: usize) -> PrioritizedSweeping<usize, TabularModel<usize>, VectorLFA<OneHot>, Random> {
+        let q_func = make_shared(LFA::vector_output(OneHot::new(5), 4));
+
+        PrioritizedSweeping::new(
+            q_func, TabularModel::deterministic(), make_shared(Random::new(4)),
+            1.0, 0.9, 1e-6, budget,
+        )
+    }
+
+    fn walk_right(agent: &mut PrioritizedSweeping<usize, TabularModel<usize>, VectorLFA<OneHot>, Random>) {
+        let mut domain = GridWorld::new(5, 1).with_goal((4, 0), 1.0);
+
+        for _ in 0..4 {
+            let t = domain.step(RIGHT);
+
+            agent.handle_transition(&t);
+        }
+
+        assert!(domain.is_terminal());
+    }
+
+    #[test]
+    fn test_backward_propagation() {
+        let mut agent = agent(4);
+
+        walk_right(&mut agent);
+
+        // A single episode suffices to propagate the goal reward along the path:
+        for (s, v) in [0.729, 0.81, 0.9, 1.0].iter().enumerate() {
+            assert!((agent.predict_qsa(&s, RIGHT) - v).abs() < 1e-10);
+        }
+
+        // Any discrete action type may be used to query the values:
+        assert_eq!(agent.predict_qsa(&3, RIGHT as u8), agent.predict_qsa(&3, RIGHT));
+
+        assert_eq!(agent.queue_len(), 0);
+    }
+
+    #[test]
+    fn test_budget() {
+        let mut agent = agent(2);
+
+        walk_right(&mut agent);
+
+        assert!((agent.predict_qsa(&3, RIGHT) - 1.0).abs() < 1e-10);
+        assert!((agent.predict_qsa(&2, RIGHT) - 0.9).abs() < 1e-10);
+        assert_eq!(agent.predict_qsa(&1, RIGHT), 0.0);
+
+        // The remaining change stays queued for the next step:
+        assert_eq!(agent.queue_len(), 1);
+    }
+
+    #[test]
+    fn test_queue_order() {
+        let mut agent = agent(0);
+
+        agent.enqueue((0, RIGHT), 0.5);
+        agent.enqueue((1, RIGHT), 1.0);
+        agent.enqueue((2, RIGHT), 0.25);
+
+        // Raising a priority supersedes the stale heap entry:
+        agent.enqueue((2, RIGHT), 2.0);
+        agent.enqueue((1, RIGHT), 0.1);
+
+        assert_eq!(agent.queue_len(), 3);
+
+        assert_eq!(agent.pop(), Some((2, RIGHT)));
+        assert_eq!(agent.pop(), Some((1, RIGHT)));
+        assert_eq!(agent.pop(), Some((0, RIGHT)));
+        assert_eq!(agent.pop(), None);
+    }
+}
@@ -1,16 +1,88 @@
 use crate::core::*;
 use crate::domains::Transition;
-use crate::fa::{Approximator, Parameterised, VectorLFA, Projection, Projector, QFunction};
+use crate::fa::{Approximator, Parameterised, VectorLFA, Projection, Projector, QFunction, delegate_weights};
 use crate::policies::{fixed::Greedy, Policy};
 
-/// Watkins' Q-learning with eligibility traces.
+/// Strategy for decaying the eligibility trace of a `QLambda` learner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QLambdaVariant {
+    /// Cut the trace whenever an exploratory (non-greedy) action is taken, such
+    /// that only greedy sub-trajectories are credited.
+    Watkins,
+
+    /// Never cut the trace; previously visited pairs are updated towards the
+    /// greedy value of each subsequent state, blending the on- and off-policy
+    /// returns.
+    Peng,
+
+    /// Never cut the trace, applying the one-step Q-learning error to every
+    /// visited pair.
+    Naive,
+}
+
+impl Default for QLambdaVariant {
+    fn default() -> QLambdaVariant { QLambdaVariant::Watkins }
+}
+
+/// Storage for a projection reused across calls to `handle_transition`.
+///
+/// The unit type caches nothing, whereas a `StateProjectionCache` holds the
+/// most recent next-state projection.
+pub trait ProjectionCache<S> {
+    /// Remove and return the cached projection, if it belongs to `s`.
+    fn take(&mut self, s: &S) -> Option<Projection>;
+
+    /// Cache the projection `phi` of `s`.
+    fn store(&mut self, s: &S, phi: Projection);
+}
+
+impl<S> ProjectionCache<S> for () {
+    fn take(&mut self, _: &S) -> Option<Projection> { None }
+
+    fn store(&mut self, _: &S, _: Projection) {}
+}
+
+/// Cache of a single projection, keyed by the state from which it was
+/// computed.
+pub struct StateProjectionCache<S>(Option<(S, Projection)>);
+
+impl<S: Clone + PartialEq> ProjectionCache<S> for StateProjectionCache<S> {
+    fn take(&mut self, s: &S) -> Option<Projection> {
+        match self.0.take() {
+            Some((key, phi)) => if key == *s { Some(phi) } else { None },
+            None => None,
+        }
+    }
+
+    fn store(&mut self, s: &S, phi: Projection) { self.0 = Some((s.clone(), phi)); }
+}
+
+/// Q-learning with eligibility traces.
+///
+/// By default this is Watkins' Q(lambda); see `QLambdaVariant` for the
+/// alternatives selected by `with_variant`.
+///
+/// Note that the eligibility trace is kept over features alone, and each
+/// update is applied to the weights of the action just taken. Credit for
+/// earlier pairs is therefore assigned to that action's values, rather than to
+/// those of the actions actually taken in them.
+///
+/// # Projection caching
+/// When enabled via `with_projection_cache`, the projection of `t.to` is kept
+/// and reused as the projection of `t.from` in the next call to
+/// `handle_transition`, provided that `t.from` equals the cached state. This
+/// saves one projection per step when transitions are fed in the order they
+/// were experienced, as in `SerialExperiment`, and falls back to projecting
+/// the state otherwise.
 ///
 /// # References
 /// - Watkins, C. J. C. H. (1989). Learning from Delayed Rewards. Ph.D. thesis,
 /// Cambridge University.
 /// - Watkins, C. J. C. H., Dayan, P. (1992). Q-learning. Machine Learning,
 /// 8:279–292.
-pub struct QLambda<F, P> {
+/// - Peng, J., Williams, R. J. (1996). Incremental multi-step Q-learning.
+/// Machine Learning, 22:283–290.
+pub struct QLambda<F, P, C = ()> {
     pub fa_theta: Shared<F>,
 
     pub policy: Shared<P>,
@@ -20,6 +92,12 @@ pub struct QLambda<F, P> {
     pub gamma: Parameter,
 
     trace: Trace,
+    variant: QLambdaVariant,
+
+    clip_td_error: Option<f64>,
+    loss: LossKind,
+
+    projection_cache: C,
 }
 
 impl<F, P> QLambda<F, P> {
@@ -44,63 +122,165 @@ impl<F, P> QLambda<F, P> {
             gamma: gamma.into(),
 
             trace,
+            variant: QLambdaVariant::default(),
+
+            clip_td_error: None,
+            loss: LossKind::default(),
+
+            projection_cache: (),
         }
     }
+
+    /// Reuse the next-state projection across contiguous transitions.
+    pub fn with_projection_cache<S>(self) -> QLambda<F, P, StateProjectionCache<S>> {
+        QLambda {
+            fa_theta: self.fa_theta,
+
+            policy: self.policy,
+            target: self.target,
+
+            alpha: self.alpha,
+            gamma: self.gamma,
+
+            trace: self.trace,
+            variant: self.variant,
+
+            clip_td_error: self.clip_td_error,
+            loss: self.loss,
+
+            projection_cache: StateProjectionCache(None),
+        }
+    }
+}
+
+impl<F, P, C> QLambda<F, P, C> {
+    /// Clear the eligibility trace without ending the current episode.
+    pub fn reset_trace(&mut self) { self.trace.clear(); }
+
+    /// Set the strategy by which the eligibility trace is decayed.
+    pub fn with_variant(mut self, variant: QLambdaVariant) -> Self {
+        self.variant = variant;
+
+        self
+    }
+
+    /// Clamp the TD error to `[-clip, clip]` before each update.
+    pub fn with_clip_td_error(mut self, clip: f64) -> Self {
+        self.clip_td_error = Some(clip);
+
+        self
+    }
+
+    /// Set the loss from which the update magnitude is derived.
+    pub fn with_loss(mut self, loss: LossKind) -> Self {
+        self.loss = loss;
+
+        self
+    }
+
+    fn shape_residual(&self, residual: f64) -> f64 {
+        let residual = match self.clip_td_error {
+            Some(c) => clip!(-c, residual, c),
+            None => residual,
+        };
+
+        self.loss.gradient(residual)
+    }
 }
 
-impl<F, P: Algorithm> Algorithm for QLambda<F, P> {
+impl<F, P: Algorithm, C> Algorithm for QLambda<F, P, C> {
     fn handle_terminal(&mut self) {
         self.alpha = self.alpha.step();
         self.gamma = self.gamma.step();
 
         self.policy.borrow_mut().handle_terminal();
         self.target.handle_terminal();
+
+        self.reset_trace();
+    }
+
+    fn handle_episode(&mut self, episode: usize) {
+        self.policy.borrow_mut().handle_episode(episode);
     }
 }
 
-impl<S, M, P> OnlineLearner<S, P::Action> for QLambda<VectorLFA<M>, P>
+impl<S, M, P, C> OnlineLearner<S, P::Action> for QLambda<VectorLFA<M>, P, C>
 where
     M: Projector<S>,
     P: Policy<S, Action = <Greedy<VectorLFA<M>> as Policy<S>>::Action>,
+    C: ProjectionCache<S>,
 {
     fn handle_transition(&mut self, t: &Transition<S, P::Action>) {
         let s = t.from.state();
-        let phi_s = self.fa_theta.projector.project(s);
-        let qsa = self.fa_theta.evaluate_action_phi(&phi_s, t.action);
-
-        // Update trace:
-        let n_bases = self.fa_theta.projector.dim();
-        let decay_rate = if t.action == self.target.sample(s) {
-            self.trace.lambda.value() * self.gamma.value()
-        } else {
-            0.0
+        let phi_s = match self.projection_cache.take(s) {
+            Some(phi) => phi,
+            None => self.fa_theta.projector.project(s),
         };
+        let qs = self.fa_theta.evaluate_phi(&phi_s);
+        let qsa = qs[t.action];
 
-        self.trace.decay(decay_rate);
-        self.trace.update(&phi_s.expanded(n_bases));
+        let greedy_action = self.target.argmax_qs(&qs);
+        let qsm = qs[greedy_action];
 
-        // Update weight vectors:
-        let z = self.trace.get();
-        let residual = if t.terminated() {
-            self.trace.decay(0.0);
+        let n_bases = self.fa_theta.projector.dim();
+        let phi_s = phi_s.expanded(n_bases);
 
+        let residual = if t.terminated() {
             t.reward - qsa
         } else {
             let ns = t.to.state();
-            let na = self.target.sample(&ns);
-            let nqsna = self.fa_theta.evaluate_action(ns, na);
+            let phi_ns = self.fa_theta.projector.project(ns);
+            let nqs = self.fa_theta.evaluate_phi(&phi_ns);
+            let nqsna = nqs[self.target.argmax_qs(&nqs)];
+
+            self.projection_cache.store(ns, phi_ns);
 
             t.reward + self.gamma * nqsna - qsa
         };
 
-        self.fa_theta.borrow_mut().update_action_phi(
-            &Projection::Dense(z), t.action,
-            self.alpha * residual,
-        );
+        // Update trace and weight vectors:
+        let decay_rate = self.trace.lambda.value() * self.gamma.value();
+
+        match self.variant {
+            QLambdaVariant::Peng => {
+                // Preceding pairs are updated towards the greedy value of `s`
+                // before the current pair is added to the trace:
+                let trace_residual = self.shape_residual(residual + qsa - qsm);
+                let residual = self.shape_residual(residual);
+
+                self.trace.decay(decay_rate);
+                self.trace.scaled_add_to(
+                    self.alpha * trace_residual,
+                    self.fa_theta.borrow_mut().approximator.weights.column_mut(t.action),
+                );
+                self.fa_theta.borrow_mut().approximator.weights
+                    .column_mut(t.action)
+                    .scaled_add(self.alpha * residual, &phi_s);
+                self.trace.update(&phi_s);
+            },
+            QLambdaVariant::Watkins | QLambdaVariant::Naive => {
+                let residual = self.shape_residual(residual);
+                if self.variant == QLambdaVariant::Watkins && t.action != greedy_action {
+                    self.trace.decay(0.0);
+                } else {
+                    self.trace.decay(decay_rate);
+                }
+
+                self.trace.update(&phi_s);
+                self.trace.scaled_add_to(
+                    self.alpha * residual,
+                    self.fa_theta.borrow_mut().approximator.weights.column_mut(t.action),
+                );
+            },
+        }
+
+        if t.terminated() {
+            self.trace.decay(0.0);
+        }
     }
 }
 
-impl<S, F, P> Controller<S, P::Action> for QLambda<F, P>
+impl<S, F, P, C> Controller<S, P::Action> for QLambda<F, P, C>
 where
     F: QFunction<S>,
     P: Policy<S, Action = <Greedy<F> as Policy<S>>::Action>,
@@ -110,7 +290,7 @@ where
     fn sample_behaviour(&mut self, s: &S) -> P::Action { self.policy.borrow_mut().sample(s) }
 }
 
-impl<S, F, P> ValuePredictor<S> for QLambda<F, P>
+impl<S, F, P, C> ValuePredictor<S> for QLambda<F, P, C>
 where
     F: QFunction<S>,
     P: Policy<S, Action = <Greedy<F> as Policy<S>>::Action>,
@@ -122,7 +302,7 @@ where
     }
 }
 
-impl<S, F, P> ActionValuePredictor<S, P::Action> for QLambda<F, P>
+impl<S, F, P, C> ActionValuePredictor<S, P::Action> for QLambda<F, P, C>
 where
     F: QFunction<S>,
     P: Policy<S, Action = <Greedy<F> as Policy<S>>::Action>,
@@ -136,8 +316,122 @@ where
     }
 }
 
-impl<F: Parameterised, P> Parameterised for QLambda<F, P> {
-    fn weights(&self) -> Matrix<f64> {
-        self.fa_theta.weights()
+delegate_weights!(QLambda<F, P, C>, fa_theta: F);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{
+        tests::{chain_transitions, transition},
+        Observation,
+    };
+    use crate::fa::{mocking::OneHot, LFA};
+    use crate::policies::fixed::{Random, TieBreak};
+
+    fn q_lambda() -> QLambda<VectorLFA<OneHot>, Random> {
+        let q_func = make_shared(LFA::vector_output(OneHot::new(4), 2));
+        let mut agent = QLambda::new(
+            q_func.clone(),
+            make_shared(Random::new(2)),
+            Trace::accumulating(0.8, 4),
+            0.5,
+            0.9,
+        );
+
+        agent.target = Greedy::with_tie_break(q_func, TieBreak::First);
+        agent
+    }
+
+    #[test]
+    fn test_projection_cache() {
+        let run = |ts: Vec<Transition<usize, usize>>| {
+            let mut naive = q_lambda();
+            let mut cached = q_lambda().with_projection_cache();
+
+            for t in ts {
+                naive.handle_transition(&t);
+                cached.handle_transition(&t);
+
+                if t.terminated() {
+                    naive.handle_terminal();
+                    cached.handle_terminal();
+                }
+
+                assert!(naive.weights().all_close(&cached.weights(), 1e-10));
+            }
+        };
+
+        run(chain_transitions());
+
+        // A stale projection must not be reused for out-of-order transitions:
+        run(chain_transitions().into_iter().rev().collect());
+    }
+
+    #[test]
+    fn test_huber_loss() {
+        let update = |loss: LossKind, reward: f64| {
+            let fa_theta = make_shared(LFA::vector_output(OneHot::new(2), 2));
+            let mut agent = QLambda::new(
+                fa_theta,
+                make_shared(Random::new(2)),
+                Trace::accumulating(0.0, 2),
+                0.5,
+                0.9,
+            ).with_loss(loss);
+
+            agent.handle_transition(&Transition {
+                from: Observation::Full(0),
+                action: 1,
+                reward,
+                to: Observation::Terminal(1),
+                truncated: false,
+            });
+
+            agent.predict_qsa(&0, 1)
+        };
+
+        // Within delta the update matches the squared loss:
+        assert!((update(LossKind::Huber(1.0), 0.5) - update(LossKind::Squared, 0.5)).abs() < 1e-10);
+        assert!((update(LossKind::Huber(1.0), 0.5) - 0.25).abs() < 1e-10);
+
+        // Beyond delta it is linear, with slope delta:
+        assert!((update(LossKind::Squared, 10.0) - 5.0).abs() < 1e-10);
+        assert!((update(LossKind::Huber(1.0), 10.0) - 0.5).abs() < 1e-10);
+        assert!((update(LossKind::Huber(1.0), -10.0) + 0.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_variants() {
+        // A corridor traversed with an exploratory action in the middle and a
+        // reward only on reaching the end:
+        let episode = vec![
+            transition(0, 0, 0.0, Observation::Full(1)),
+            transition(1, 1, 0.0, Observation::Full(2)),
+            transition(2, 0, 1.0, Observation::Terminal(3)),
+        ];
+
+        let run = |variant| {
+            let mut agent = q_lambda().with_variant(variant);
+
+            episode.iter().for_each(|t| agent.handle_transition(t));
+
+            agent
+        };
+
+        // Watkins' variant cuts the trace at the exploratory step, so the reward
+        // never reaches the first state:
+        let mut watkins = run(QLambdaVariant::Watkins);
+
+        assert_eq!(watkins.predict_qsa(&0, 0), 0.0);
+        assert!((watkins.predict_qsa(&1, 0) - 0.5 * 0.72).abs() < 1e-10);
+
+        // Peng's and the naive variant propagate through it:
+        for &variant in [QLambdaVariant::Peng, QLambdaVariant::Naive].iter() {
+            let mut agent = run(variant);
+
+            assert!((agent.predict_qsa(&0, 0) - 0.5 * 0.72 * 0.72).abs() < 1e-10);
+            assert!((agent.predict_qsa(&1, 0) - 0.5 * 0.72).abs() < 1e-10);
+            assert!((agent.predict_qsa(&2, 0) - 0.5).abs() < 1e-10);
+        }
     }
 }
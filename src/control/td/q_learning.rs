@@ -1,17 +1,22 @@
 use crate::core::*;
 use crate::domains::Transition;
-use crate::fa::{Parameterised, QFunction};
+use crate::fa::{Parameterised, QFunction, delegate_weights};
 use crate::policies::{fixed::Greedy, Policy, FinitePolicy};
 use std::marker::PhantomData;
 
 /// Watkins' Q-learning.
 ///
+/// # Target networks
+/// When enabled via `with_target_network`, the greedy value of the next state
+/// is taken from a copy of the action-value weights that is only synchronised
+/// with the online weights every `period` updates, as in DQN.
+///
 /// # References
 /// - Watkins, C. J. C. H. (1989). Learning from Delayed Rewards. Ph.D. thesis,
 /// Cambridge University.
 /// - Watkins, C. J. C. H., Dayan, P. (1992). Q-learning. Machine Learning,
 /// 8:279–292.
-pub struct QLearning<Q, P> {
+pub struct QLearning<Q, P, G = (), A = (), T = ()> {
     pub q_func: Shared<Q>,
 
     pub policy: Shared<P>,
@@ -19,6 +24,14 @@ pub struct QLearning<Q, P> {
 
     pub alpha: Parameter,
     pub gamma: Parameter,
+
+    action_alphas: Option<Vector<f64>>,
+    clip_td_error: Option<f64>,
+    loss: LossKind,
+    gamma_fn: G,
+
+    averaging: A,
+    target_network: T,
 }
 
 impl<Q, P> QLearning<Q, P> {
@@ -35,79 +48,498 @@ impl<Q, P> QLearning<Q, P> {
 
             alpha: alpha.into(),
             gamma: gamma.into(),
+
+            action_alphas: None,
+            clip_td_error: None,
+            loss: LossKind::default(),
+            gamma_fn: (),
+
+            averaging: (),
+            target_network: (),
+        }
+    }
+}
+
+impl<Q, P, G, A, T> QLearning<Q, P, G, A, T> {
+    /// Use a separate step size for each action in place of `alpha`.
+    ///
+    /// The update of `Q(s, a)` is scaled by `alphas[a]`, which allows rarely
+    /// taken actions to be updated more conservatively (or aggressively) than
+    /// common ones. The `alpha` parameter is still stepped on each terminal,
+    /// but is ignored when updating.
+    pub fn with_action_step_sizes(mut self, alphas: Vector<f64>) -> Self {
+        self.action_alphas = Some(alphas);
+
+        self
+    }
+
+    fn step_size(&self, action: usize) -> f64 {
+        match self.action_alphas {
+            Some(ref alphas) => alphas[action],
+            None => self.alpha.value(),
+        }
+    }
+
+    /// Clamp the TD error to `[-clip, clip]` before each update.
+    pub fn with_clip_td_error(mut self, clip: f64) -> Self {
+        self.clip_td_error = Some(clip);
+
+        self
+    }
+
+    /// Set the loss from which the update magnitude is derived.
+    pub fn with_loss(mut self, loss: LossKind) -> Self {
+        self.loss = loss;
+
+        self
+    }
+
+    /// Replace the constant discount factor with a function of the next state.
+    ///
+    /// The `gamma` parameter is still stepped on each terminal, but is ignored
+    /// when computing the TD target.
+    pub fn with_gamma_fn<S, F: Fn(&S) -> f64>(self, gamma_fn: F) -> QLearning<Q, P, F, A, T> {
+        QLearning {
+            q_func: self.q_func,
+
+            policy: self.policy,
+            target: self.target,
+
+            alpha: self.alpha,
+            gamma: self.gamma,
+
+            action_alphas: self.action_alphas,
+            clip_td_error: self.clip_td_error,
+            loss: self.loss,
+            gamma_fn,
+
+            averaging: self.averaging,
+            target_network: self.target_network,
+        }
+    }
+}
+
+impl<Q: Parameterised + 'static, P, G, T> QLearning<Q, P, G, (), T> {
+    /// Maintain a Polyak average of the weights, updated after every
+    /// transition, through which `sample_target` and all predictions are
+    /// routed. Learning itself continues on the raw weights.
+    pub fn enable_averaging(self, tau: f64) -> QLearning<Q, P, G, PolyakAverage, T> {
+        QLearning {
+            averaging: PolyakAverage::new(self.q_func.clone(), tau),
+            target_network: self.target_network,
+
+            q_func: self.q_func,
+
+            policy: self.policy,
+            target: self.target,
+
+            alpha: self.alpha,
+            gamma: self.gamma,
+
+            action_alphas: self.action_alphas,
+            clip_td_error: self.clip_td_error,
+            loss: self.loss,
+            gamma_fn: self.gamma_fn,
         }
     }
 }
 
-impl<Q, P: Algorithm> Algorithm for QLearning<Q, P> {
+impl<Q, P, G, T> QLearning<Q, P, G, PolyakAverage, T> {
+    /// Return the averaged weights.
+    pub fn averaged_weights(&self) -> &Matrix<f64> { self.averaging.weights() }
+}
+
+impl<Q: Parameterised, P, G, A> QLearning<Q, P, G, A> {
+    /// Bootstrap from a target network that is synchronised with the online
+    /// weights after every `period` updates.
+    pub fn with_target_network(self, period: usize) -> QLearning<Q, P, G, A, PeriodicTarget<Q>> {
+        QLearning {
+            target_network: PeriodicTarget::new(self.q_func.clone(), period),
+
+            q_func: self.q_func,
+
+            policy: self.policy,
+            target: self.target,
+
+            alpha: self.alpha,
+            gamma: self.gamma,
+
+            action_alphas: self.action_alphas,
+            clip_td_error: self.clip_td_error,
+            loss: self.loss,
+            gamma_fn: self.gamma_fn,
+
+            averaging: self.averaging,
+        }
+    }
+}
+
+impl<Q: Parameterised, P, G, A> QLearning<Q, P, G, A, PeriodicTarget<Q>> {
+    /// Return the target network weights.
+    pub fn target_weights(&self) -> &Matrix<f64> { self.target_network.network.weights() }
+}
+
+impl<Q, P: Algorithm, G, A, T> Algorithm for QLearning<Q, P, G, A, T> {
     fn handle_terminal(&mut self) {
         self.alpha = self.alpha.step();
         self.gamma = self.gamma.step();
 
         self.policy.borrow_mut().handle_terminal();
     }
+
+    fn handle_episode(&mut self, episode: usize) {
+        self.policy.borrow_mut().handle_episode(episode);
+    }
 }
 
-impl<S, Q, P> OnlineLearner<S, P::Action> for QLearning<Q, P>
+impl<S, Q, P, G, A, T> OnlineLearner<S, P::Action> for QLearning<Q, P, G, A, T>
 where
     Q: QFunction<S>,
     P: Policy<S, Action = <Greedy<Q> as Policy<S>>::Action>,
+    G: Discount<S>,
+    A: Averaging<S, Q>,
+    T: BootstrapTarget<S, Q>,
 {
     fn handle_transition(&mut self, t: &Transition<S, P::Action>) {
         let s = t.from.state();
-        let qsa = self.predict_qsa(&s, t.action);
+        let qsa = self.q_func.evaluate_action(s, t.action);
         let residual = if t.terminated() {
             t.reward - qsa
         } else {
             let ns = t.to.state();
-            let na = self.sample_target(&ns);
-            let nqsna = self.predict_qsa(&ns, na);
+            let nqs = match self.target_network.evaluate(&self.q_func, ns) {
+                Some(nqs) => nqs,
+                None => self.q_func.evaluate(ns).unwrap(),
+            };
+            let nqsna = nqs[self.target.argmax_qs(&nqs)];
+
+            t.reward + self.gamma_fn.discount(self.gamma.value(), ns) * nqsna - qsa
+        };
 
-            t.reward + self.gamma * nqsna - qsa
+        let residual = match self.clip_td_error {
+            Some(c) => clip!(-c, residual, c),
+            None => residual,
         };
+        let residual = self.loss.gradient(residual);
+
+        let alpha = self.step_size(t.action);
 
-        self.q_func.borrow_mut().update_action(s, t.action, self.alpha * residual);
+        self.q_func.borrow_mut().update_action(s, t.action, alpha * residual);
+
+        self.averaging.update();
+        self.target_network.step();
     }
 }
 
-impl<S, Q, P> Controller<S, P::Action> for QLearning<Q, P>
+impl<S, Q, P, G, A, T> Controller<S, P::Action> for QLearning<Q, P, G, A, T>
 where
     Q: QFunction<S>,
     P: Policy<S, Action = <Greedy<Q> as Policy<S>>::Action>,
+    A: Averaging<S, Q>,
 {
-    fn sample_target(&mut self, s: &S) -> P::Action { self.target.sample(s) }
+    fn sample_target(&mut self, s: &S) -> P::Action {
+        match self.averaging.evaluate(&self.q_func, s) {
+            Some(qs) => self.target.argmax_qs(&qs),
+            None => self.target.sample(s),
+        }
+    }
 
     fn sample_behaviour(&mut self, s: &S) -> P::Action { self.policy.borrow_mut().sample(s) }
 }
 
-impl<S, Q, P> ValuePredictor<S> for QLearning<Q, P>
+impl<S, Q, P, G, A, T> ValuePredictor<S> for QLearning<Q, P, G, A, T>
 where
     Q: QFunction<S>,
     P: Policy<S, Action = <Greedy<Q> as Policy<S>>::Action>,
+    A: Averaging<S, Q>,
 {
     fn predict_v(&mut self, s: &S) -> f64 {
-        let a = self.target.sample(s);
+        let a = self.sample_target(s);
 
         self.predict_qsa(s, a)
     }
 }
 
-impl<S, Q, P> ActionValuePredictor<S, P::Action> for QLearning<Q, P>
+impl<S, Q, P, G, A, T> ActionValuePredictor<S, P::Action> for QLearning<Q, P, G, A, T>
 where
     Q: QFunction<S>,
     P: Policy<S, Action = <Greedy<Q> as Policy<S>>::Action>,
+    A: Averaging<S, Q>,
 {
     fn predict_qs(&mut self, s: &S) -> Vector<f64> {
-        self.q_func.evaluate(s).unwrap()
+        match self.averaging.evaluate(&self.q_func, s) {
+            Some(qs) => qs,
+            None => self.q_func.evaluate(s).unwrap(),
+        }
     }
 
     fn predict_qsa(&mut self, s: &S, a: P::Action) -> f64 {
-        self.q_func.evaluate_action(&s, a)
+        match self.averaging.evaluate(&self.q_func, s) {
+            Some(qs) => qs[a],
+            None => self.q_func.evaluate_action(&s, a),
+        }
     }
 }
 
-impl<Q: Parameterised, P> Parameterised for QLearning<Q, P> {
-    fn weights(&self) -> Matrix<f64> {
-        self.q_func.weights()
+delegate_weights!(QLearning<Q, P, G, A, T>, q_func: Q);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::td::{QLambda, SARSA};
+    use crate::domains::{
+        tests::{chain_transitions, transition},
+        GridWorld,
+        Observation,
+    };
+    use crate::fa::{mocking::OneHot, Approximator, LFA};
+    use crate::policies::fixed::Random;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_matches_q_lambda() {
+        let q_func = make_shared(LFA::vector_output(OneHot::new(4), 2));
+        let mut q_learning = QLearning::new(q_func, make_shared(Random::new(2)), 0.5, 0.9);
+
+        let q_func = make_shared(LFA::vector_output(OneHot::new(4), 2));
+        let mut q_lambda = QLambda::new(
+            q_func,
+            make_shared(Random::new(2)),
+            Trace::accumulating(0.0, 4),
+            0.5,
+            0.9,
+        );
+
+        for t in chain_transitions() {
+            q_learning.handle_transition(&t);
+            q_lambda.handle_transition(&t);
+
+            if t.terminated() {
+                q_learning.handle_terminal();
+                q_lambda.handle_terminal();
+            }
+
+            assert!(q_learning.weights().all_close(&q_lambda.weights(), 1e-10));
+        }
+    }
+
+    #[test]
+    fn test_greedy_policy_table() {
+        let q_func = make_shared(LFA::vector_output(OneHot::new(9), 4));
+        let policy = Random::with_rng(4, StdRng::seed_from_u64(0));
+        let mut agent = QLearning::new(q_func, make_shared(policy), 0.5, 0.9);
+
+        {
+            let domain_factory = Box::new(|| GridWorld::new(3, 3).with_goal((2, 2), 1.0));
+
+            run(SerialExperiment::new(&mut agent, domain_factory, 100), 300, None);
+        }
+
+        let states: Vec<usize> = (0..8).collect();
+        let table = agent.greedy_policy_table(&states);
+
+        assert_eq!(table.len(), 8);
+
+        // Every non-terminal cell should move down (1) or right (3) towards the
+        // goal, without trying to leave the grid:
+        for (s, a) in states.into_iter().zip(table.into_iter()) {
+            let (x, y) = (s % 3, s / 3);
+
+            match a {
+                1 => assert!(y < 2),
+                3 => assert!(x < 2),
+                _ => panic!("Action {} in state {} does not lie on an optimal path.", a, s),
+            }
+        }
+    }
+
+    #[test]
+    fn test_clip_td_error() {
+        let t = Transition {
+            from: Observation::Full(0),
+            action: 1,
+            reward: 1e6,
+            to: Observation::Full(1),
+            truncated: false,
+        };
+
+        // With one-hot features, ||phi|| = 1 so each weight moves by at most
+        // alpha * c = 0.25:
+        let bounded = |w: Matrix<f64>| w.iter().all(|x| x.abs() <= 0.25 + 1e-10);
+
+        let q_func = make_shared(LFA::vector_output(OneHot::new(2), 2));
+        let mut agent = QLearning::new(q_func, make_shared(Random::new(2)), 0.5, 0.9)
+            .with_clip_td_error(0.5);
+
+        agent.handle_transition(&t);
+        assert!(bounded(agent.weights()));
+        assert!((agent.predict_qsa(&0, 1) - 0.25).abs() < 1e-10);
+
+        let q_func = make_shared(LFA::vector_output(OneHot::new(2), 2));
+        let mut agent = SARSA::new(q_func, make_shared(Random::new(2)), 0.5, 0.9)
+            .with_clip_td_error(0.5);
+
+        agent.handle_transition(&t);
+        assert!(bounded(agent.weights()));
+
+        let q_func = make_shared(LFA::vector_output(OneHot::new(2), 2));
+        let mut agent = QLambda::new(
+            q_func,
+            make_shared(Random::new(2)),
+            Trace::accumulating(0.0, 2),
+            0.5,
+            0.9,
+        ).with_clip_td_error(0.5);
+
+        agent.handle_transition(&t);
+        assert!(bounded(agent.weights()));
+    }
+
+    #[test]
+    fn test_action_step_sizes() {
+        let t = |action| Transition {
+            from: Observation::Full(0),
+            action,
+            reward: 1.0,
+            to: Observation::Terminal(1),
+            truncated: false,
+        };
+        let alphas = Vector::from_vec(vec![0.1, 0.4]);
+
+        let q_func = make_shared(LFA::vector_output(OneHot::new(2), 2));
+        let mut agent = QLearning::new(q_func, make_shared(Random::new(2)), 0.5, 0.9)
+            .with_action_step_sizes(alphas.clone());
+
+        agent.handle_transition(&t(0));
+        agent.handle_transition(&t(1));
+
+        // Both actions see a TD error of 1, so the changes are the step sizes:
+        assert!((agent.predict_qsa(&0, 0) - 0.1).abs() < 1e-10);
+        assert!((agent.predict_qsa(&0, 1) - 0.4).abs() < 1e-10);
+
+        let q_func = make_shared(LFA::vector_output(OneHot::new(2), 2));
+        let mut agent = SARSA::new(q_func, make_shared(Random::new(2)), 0.5, 0.9)
+            .with_action_step_sizes(alphas);
+
+        agent.handle_transition(&t(0));
+        agent.handle_transition(&t(1));
+
+        assert!((agent.predict_qsa(&0, 0) - 0.1).abs() < 1e-10);
+        assert!((agent.predict_qsa(&0, 1) - 0.4).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_gamma_fn() {
+        let t = Transition {
+            from: Observation::Full(0),
+            action: 0,
+            reward: 0.0,
+            to: Observation::Full(1),
+            truncated: false,
+        };
+        let q_func = || {
+            let q_func = make_shared(LFA::vector_output(OneHot::new(3), 2));
+
+            q_func.borrow_mut().approximator.weights[[1, 0]] = 1.0;
+            q_func.borrow_mut().approximator.weights[[1, 1]] = 1.0;
+
+            q_func
+        };
+
+        let mut agent = QLearning::new(q_func(), make_shared(Random::new(2)), 0.5, 0.9);
+
+        agent.handle_transition(&t);
+        assert!((agent.predict_qsa(&0, 0) - 0.45).abs() < 1e-10);
+
+        // The bootstrap term vanishes on entering state 1:
+        let mut agent = QLearning::new(q_func(), make_shared(Random::new(2)), 0.5, 0.9)
+            .with_gamma_fn(|s: &usize| if *s == 1 { 0.0 } else { 0.9 });
+
+        agent.handle_transition(&t);
+        assert_eq!(agent.predict_qsa(&0, 0), 0.0);
+
+        // ...but not elsewhere:
+        let mut agent = QLearning::new(q_func(), make_shared(Random::new(2)), 0.5, 0.0)
+            .with_gamma_fn(|s: &usize| if *s == 2 { 0.0 } else { 0.9 });
+
+        agent.handle_transition(&t);
+        assert!((agent.predict_qsa(&0, 0) - 0.45).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_averaging() {
+        let q_func = make_shared(LFA::vector_output(OneHot::new(4), 2));
+        let mut frozen = QLearning::new(q_func, make_shared(Random::new(2)), 0.5, 0.9)
+            .enable_averaging(0.0);
+
+        let q_func = make_shared(LFA::vector_output(OneHot::new(4), 2));
+        let mut tracking = QLearning::new(q_func, make_shared(Random::new(2)), 0.5, 0.9)
+            .enable_averaging(1.0);
+
+        for t in chain_transitions() {
+            frozen.handle_transition(&t);
+            tracking.handle_transition(&t);
+
+            assert!(frozen.averaged_weights().iter().all(|&w| w == 0.0));
+            assert_eq!(tracking.averaged_weights(), &tracking.weights());
+        }
+
+        // Training proceeds on the raw weights regardless:
+        assert!(frozen.weights().all_close(&tracking.weights(), 1e-10));
+        assert!(frozen.weights().iter().any(|&w| w != 0.0));
+
+        for s in 0..3 {
+            assert_eq!(frozen.predict_qs(&s), Vector::zeros(2));
+            assert!(tracking.predict_qs(&s).all_close(&tracking.q_func.evaluate(&s).unwrap(), 1e-10));
+        }
+    }
+
+    #[test]
+    fn test_truncated_bootstrap() {
+        let target = |truncated| {
+            let q_func = make_shared(LFA::vector_output(OneHot::new(2), 2));
+
+            q_func.borrow_mut().approximator.weights.row_mut(1).fill(2.0);
+
+            let mut agent = QLearning::new(q_func, make_shared(Random::new(2)), 1.0, 0.9);
+
+            agent.handle_transition(&Transition {
+                from: Observation::Full(0),
+                action: 0,
+                reward: 1.0,
+                to: Observation::Terminal(1),
+                truncated,
+            });
+
+            agent.predict_qsa(&0, 0)
+        };
+
+        // Truncation still bootstraps from the next state, termination does not:
+        assert!((target(true) - 2.8).abs() < 1e-10);
+        assert!((target(false) - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_target_network() {
+        let q_func = make_shared(LFA::vector_output(OneHot::new(3), 1));
+        let mut agent = QLearning::new(q_func, make_shared(Random::new(1)), 0.5, 0.9)
+            .with_target_network(2);
+
+        let terminal = transition(1, 0, 1.0, Observation::Terminal(2));
+        let bootstrap = transition(0, 0, 0.0, Observation::Full(1));
+
+        agent.handle_transition(&terminal);
+        agent.handle_transition(&bootstrap);
+
+        // The target weights were still zero when bootstrapping from state 1:
+        assert_eq!(agent.predict_qsa(&0, 0), 0.0);
+        assert_eq!(agent.predict_qsa(&1, 0), 0.5);
+
+        // ...but have since been synchronised:
+        assert_eq!(agent.target_weights(), &agent.weights());
+
+        agent.handle_transition(&bootstrap);
+
+        assert!((agent.predict_qsa(&0, 0) - 0.5 * 0.9 * 0.5).abs() < 1e-10);
     }
 }
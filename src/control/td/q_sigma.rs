@@ -1,6 +1,6 @@
 use crate::core::*;
 use crate::domains::Transition;
-use crate::fa::{Parameterised, QFunction};
+use crate::fa::{Parameterised, QFunction, delegate_weights};
 use crate::policies::{fixed::Greedy, Policy, FinitePolicy};
 use std::collections::VecDeque;
 
@@ -24,6 +24,14 @@ struct BackupEntry<S> {
 ///     * `0` - `ExpectedSARSA` | `TreeBackup`
 ///     * `1` - `SARSA`
 ///
+/// # Backups
+/// Each transition is stored in a buffer of length `n_steps` along with the
+/// value of `sigma` at that time. Once the buffer is full, the oldest entry is
+/// updated towards the n-step return, in which every subsequent step is either
+/// sampled or taken in expectation under the target policy according to its
+/// own `sigma`. On termination, the remaining entries are updated using their
+/// truncated returns before the buffer is cleared.
+///
 /// # References
 /// - Sutton, R. S. and Barto, A. G. (2017). Reinforcement Learning: An
 /// Introduction (2nd ed.). Manuscript in preparation.
@@ -76,17 +84,21 @@ impl<S, Q, P> QSigma<S, Q, P> {
 
 impl<S, Q: QFunction<S>, P> QSigma<S, Q, P> {
     fn consume_backup(&mut self) {
+        let n = self.backup.len().min(self.n_steps);
+
         let mut g = self.backup[0].q;
         let mut z = 1.0;
         let mut rho = 1.0;
 
-        for k in 0..(self.n_steps - 1) {
-            let b1 = &self.backup[k];
-            let b2 = &self.backup[k + 1];
+        for k in 0..n {
+            let b = &self.backup[k];
+
+            g += z * b.residual;
 
-            g += z * b1.residual;
-            z *= self.gamma * ((1.0 - b2.sigma) * b2.pi + b2.sigma);
-            rho *= 1.0 - b1.sigma + b1.sigma * b1.pi / b1.mu;
+            if k + 1 < n {
+                z *= self.gamma * ((1.0 - b.sigma) * b.pi + b.sigma);
+                rho *= 1.0 - b.sigma + b.sigma * b.pi / b.mu;
+            }
         }
 
         let qsa = self.q_func.evaluate_action(&self.backup[0].s, self.backup[0].a);
@@ -118,6 +130,10 @@ impl<S, Q, P: Algorithm> Algorithm for QSigma<S, Q, P> {
         self.policy.borrow_mut().handle_terminal();
         self.target.handle_terminal();
     }
+
+    fn handle_episode(&mut self, episode: usize) {
+        self.policy.borrow_mut().handle_episode(episode);
+    }
 }
 
 impl<S, Q, P> OnlineLearner<S, P::Action> for QSigma<S, Q, P>
@@ -147,7 +163,9 @@ where
                 mu: 1.0,
             });
 
-            self.backup.clear();
+            while !self.backup.is_empty() {
+                self.consume_backup();
+            }
 
         } else {
             let ns = t.to.state();
@@ -173,6 +191,12 @@ where
                 pi: pi[na],
                 mu: mu,
             });
+
+            if t.truncated {
+                while !self.backup.is_empty() {
+                    self.consume_backup();
+                }
+            }
         };
     }
 }
@@ -213,8 +237,98 @@ where
     }
 }
 
-impl<S, Q: Parameterised, P> Parameterised for QSigma<S, Q, P> {
-    fn weights(&self) -> Matrix<f64> {
-        self.q_func.weights()
+delegate_weights!(QSigma<S, Q, P>, q_func: Q);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::Observation;
+    use crate::fa::{mocking::OneHot, VectorLFA, LFA};
+
+    /// Behaviour policy that reports uniform probabilities over two actions,
+    /// but always samples the first for the sake of determinism.
+    struct FirstAction;
+
+    impl Algorithm for FirstAction {}
+
+    impl Policy<usize> for FirstAction {
+        type Action = usize;
+
+        fn sample(&mut self, _: &usize) -> usize { 0 }
+
+        fn probability(&mut self, _: &usize, _: usize) -> f64 { 0.5 }
+    }
+
+    fn q_sigma(sigma: f64, n_steps: usize, q1: [f64; 2]) -> QSigma<usize, VectorLFA<OneHot>, FirstAction> {
+        let q_func = make_shared(LFA::vector_output(OneHot::new(3), 2));
+
+        q_func.borrow_mut().approximator.weights[[1, 0]] = q1[0];
+        q_func.borrow_mut().approximator.weights[[1, 1]] = q1[1];
+
+        QSigma::new(q_func, make_shared(FirstAction), 1.0, 0.9, sigma, n_steps)
+    }
+
+    fn transitions() -> Vec<Transition<usize, usize>> {
+        vec![
+            Transition {
+                from: Observation::Full(0),
+                action: 0,
+                reward: 1.0,
+                to: Observation::Full(1),
+                truncated: false,
+            },
+            Transition {
+                from: Observation::Full(1),
+                action: 0,
+                reward: 0.0,
+                to: Observation::Terminal(2),
+                truncated: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_sarsa_target() {
+        // sigma = 1 samples the next action: 1 + 0.9 * Q(1, 0)
+        let mut agent = q_sigma(1.0, 1, [1.0, 3.0]);
+
+        agent.handle_transition(&transitions()[0]);
+
+        assert!((agent.predict_qsa(&0, 0) - 1.9).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_expected_sarsa_target() {
+        // sigma = 0 takes the expectation under the greedy target: 1 + 0.9 * Q(1, 1)
+        let mut agent = q_sigma(0.0, 1, [1.0, 3.0]);
+
+        agent.handle_transition(&transitions()[0]);
+
+        assert!((agent.predict_qsa(&0, 0) - 3.7).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_tree_backup_target() {
+        // The sampled action at state 1 has zero probability under the target, so
+        // the tree backup is cut after the expected value of state 1:
+        let mut agent = q_sigma(0.0, 2, [1.0, 3.0]);
+
+        transitions().iter().for_each(|t| agent.handle_transition(t));
+
+        assert!((agent.predict_qsa(&0, 0) - 3.7).abs() < 1e-7);
+
+        // The tail of the buffer is flushed on termination:
+        assert!(agent.predict_qsa(&1, 0).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_n_step_sarsa_target() {
+        // With sigma = 1 the two-step return, 1 + 0.9 * 0, is weighted by the
+        // importance sampling ratio pi(0 | 1) / mu(0 | 1) = 2:
+        let mut agent = q_sigma(1.0, 2, [3.0, 1.0]);
+
+        transitions().iter().for_each(|t| agent.handle_transition(t));
+
+        assert!((agent.predict_qsa(&0, 0) - 2.0).abs() < 1e-7);
     }
 }
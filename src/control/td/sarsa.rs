@@ -1,7 +1,7 @@
 use crate::core::*;
 use crate::domains::Transition;
-use crate::fa::{Parameterised, QFunction};
-use crate::policies::{Policy, FinitePolicy};
+use crate::fa::{Parameterised, QFunction, WeightedQFunction, delegate_weights};
+use crate::policies::{fixed::Greedy, Policy, FinitePolicy};
 use std::marker::PhantomData;
 
 /// On-policy variant of Watkins' Q-learning (aka "modified Q-learning").
@@ -11,12 +11,20 @@ use std::marker::PhantomData;
 /// thesis, Cambridge University.
 /// - Singh, S. P., Sutton, R. S. (1996). Reinforcement learning with replacing
 /// eligibility traces. Machine Learning 22:123–158.
-pub struct SARSA<Q, P> {
+pub struct SARSA<Q, P, G = (), A = ()> {
     pub q_func: Shared<Q>,
     pub policy: Shared<P>,
+    pub target: Greedy<Q>,
 
     pub alpha: Parameter,
     pub gamma: Parameter,
+
+    action_alphas: Option<Vector<f64>>,
+    clip_td_error: Option<f64>,
+    loss: LossKind,
+    gamma_fn: G,
+
+    averaging: A,
 }
 
 impl<Q, P> SARSA<Q, P> {
@@ -26,28 +34,128 @@ impl<Q, P> SARSA<Q, P> {
         T2: Into<Parameter>,
     {
         SARSA {
-            q_func,
+            q_func: q_func.clone(),
             policy,
+            target: Greedy::new(q_func),
 
             alpha: alpha.into(),
             gamma: gamma.into(),
+
+            action_alphas: None,
+            clip_td_error: None,
+            loss: LossKind::default(),
+            gamma_fn: (),
+
+            averaging: (),
         }
     }
 }
 
-impl<Q, P: Algorithm> Algorithm for SARSA<Q, P> {
+impl<Q, P, G, A> SARSA<Q, P, G, A> {
+    /// Use a separate step size for each action in place of `alpha`.
+    ///
+    /// The update of `Q(s, a)` is scaled by `alphas[a]`, which allows rarely
+    /// taken actions to be updated more conservatively (or aggressively) than
+    /// common ones. The `alpha` parameter is still stepped on each terminal,
+    /// but is ignored when updating.
+    pub fn with_action_step_sizes(mut self, alphas: Vector<f64>) -> Self {
+        self.action_alphas = Some(alphas);
+
+        self
+    }
+
+    fn step_size(&self, action: usize) -> f64 {
+        match self.action_alphas {
+            Some(ref alphas) => alphas[action],
+            None => self.alpha.value(),
+        }
+    }
+
+    /// Clamp the TD error to `[-clip, clip]` before each update.
+    pub fn with_clip_td_error(mut self, clip: f64) -> Self {
+        self.clip_td_error = Some(clip);
+
+        self
+    }
+
+    /// Set the loss from which the update magnitude is derived.
+    pub fn with_loss(mut self, loss: LossKind) -> Self {
+        self.loss = loss;
+
+        self
+    }
+
+    /// Replace the constant discount factor with a function of the next state.
+    ///
+    /// The `gamma` parameter is still stepped on each terminal, but is ignored
+    /// when computing the TD target.
+    pub fn with_gamma_fn<S, F: Fn(&S) -> f64>(self, gamma_fn: F) -> SARSA<Q, P, F, A> {
+        SARSA {
+            q_func: self.q_func,
+            policy: self.policy,
+            target: self.target,
+
+            alpha: self.alpha,
+            gamma: self.gamma,
+
+            action_alphas: self.action_alphas,
+            clip_td_error: self.clip_td_error,
+            loss: self.loss,
+            gamma_fn,
+
+            averaging: self.averaging,
+        }
+    }
+}
+
+impl<Q: Parameterised + 'static, P, G> SARSA<Q, P, G> {
+    /// Maintain a Polyak average of the weights, updated after every
+    /// transition, through which all predictions are routed. The target policy
+    /// becomes greedy with respect to the averaged values, while learning and
+    /// behaviour continue on the raw weights.
+    pub fn enable_averaging(self, tau: f64) -> SARSA<Q, P, G, PolyakAverage> {
+        SARSA {
+            averaging: PolyakAverage::new(self.q_func.clone(), tau),
+
+            q_func: self.q_func,
+            policy: self.policy,
+            target: self.target,
+
+            alpha: self.alpha,
+            gamma: self.gamma,
+
+            action_alphas: self.action_alphas,
+            clip_td_error: self.clip_td_error,
+            loss: self.loss,
+            gamma_fn: self.gamma_fn,
+        }
+    }
+}
+
+impl<Q, P, G> SARSA<Q, P, G, PolyakAverage> {
+    /// Return the averaged weights.
+    pub fn averaged_weights(&self) -> &Matrix<f64> { self.averaging.weights() }
+}
+
+impl<Q, P: Algorithm, G, A> Algorithm for SARSA<Q, P, G, A> {
     fn handle_terminal(&mut self) {
         self.alpha = self.alpha.step();
         self.gamma = self.gamma.step();
 
         self.policy.borrow_mut().handle_terminal();
     }
+
+    fn handle_episode(&mut self, episode: usize) {
+        self.policy.borrow_mut().handle_episode(episode);
+    }
 }
 
-impl<S, Q, P> OnlineLearner<S, P::Action> for SARSA<Q, P>
+impl<S, Q, P, G, A> OnlineLearner<S, P::Action> for SARSA<Q, P, G, A>
 where
     Q: QFunction<S>,
     P: FinitePolicy<S>,
+    G: Discount<S>,
+    A: Averaging<S, Q>,
 {
     fn handle_transition(&mut self, t: &Transition<S, P::Action>) {
         let s = t.from.state();
@@ -59,49 +167,114 @@ where
             let na = self.policy.borrow_mut().sample(ns);
             let nqsna = self.q_func.evaluate_action(ns, na);
 
-            t.reward + self.gamma * nqsna - qsa
+            t.reward + self.gamma_fn.discount(self.gamma.value(), ns) * nqsna - qsa
         };
 
-        self.q_func.borrow_mut().update_action(s, t.action, self.alpha * residual);
+        let residual = match self.clip_td_error {
+            Some(c) => clip!(-c, residual, c),
+            None => residual,
+        };
+        let residual = self.loss.gradient(residual);
+
+        let alpha = self.step_size(t.action);
+
+        self.q_func.borrow_mut().update_action(s, t.action, alpha * residual);
+        self.averaging.update();
     }
 }
 
-impl<S, Q, P: Policy<S>> Controller<S, P::Action> for SARSA<Q, P> {
-    fn sample_target(&mut self, s: &S) -> P::Action {
-        self.policy.borrow_mut().sample(s)
-    }
+impl<S, Q, P: Policy<S>, G> Controller<S, P::Action> for SARSA<Q, P, G> {
+    fn sample_target(&mut self, s: &S) -> P::Action { self.policy.borrow_mut().sample(s) }
+
+    fn sample_behaviour(&mut self, s: &S) -> P::Action { self.policy.borrow_mut().sample(s) }
+}
+
+impl<S, Q, P, G> Controller<S, usize> for SARSA<Q, P, G, PolyakAverage>
+where
+    Q: WeightedQFunction<S>,
+    P: Policy<S, Action = usize>,
+{
+    fn sample_target(&mut self, s: &S) -> usize {
+        let qs = Averaging::<S, Q>::evaluate(&self.averaging, &self.q_func, s).unwrap();
 
-    fn sample_behaviour(&mut self, s: &S) -> P::Action {
-        self.policy.borrow_mut().sample(s)
+        self.target.argmax_qs(&qs)
     }
+
+    fn sample_behaviour(&mut self, s: &S) -> usize { self.policy.borrow_mut().sample(s) }
 }
 
-impl<S, Q, P> ValuePredictor<S> for SARSA<Q, P>
+impl<S, Q, P, G, A> ValuePredictor<S> for SARSA<Q, P, G, A>
 where
     Q: QFunction<S>,
     P: FinitePolicy<S>,
+    A: Averaging<S, Q>,
 {
     fn predict_v(&mut self, s: &S) -> f64 {
         self.predict_qs(s).dot(&self.policy.borrow_mut().probabilities(s))
     }
 }
 
-impl<S, Q, P> ActionValuePredictor<S, P::Action> for SARSA<Q, P>
+impl<S, Q, P, G, A> ActionValuePredictor<S, P::Action> for SARSA<Q, P, G, A>
 where
     Q: QFunction<S>,
     P: FinitePolicy<S>,
+    A: Averaging<S, Q>,
 {
     fn predict_qs(&mut self, s: &S) -> Vector<f64> {
-        self.q_func.evaluate(s).unwrap()
+        match self.averaging.evaluate(&self.q_func, s) {
+            Some(qs) => qs,
+            None => self.q_func.evaluate(s).unwrap(),
+        }
     }
 
     fn predict_qsa(&mut self, s: &S, a: P::Action) -> f64 {
-        self.q_func.evaluate_action(&s, a)
+        match self.averaging.evaluate(&self.q_func, s) {
+            Some(qs) => qs[a],
+            None => self.q_func.evaluate_action(&s, a),
+        }
     }
 }
 
-impl<Q: Parameterised, P> Parameterised for SARSA<Q, P> {
-    fn weights(&self) -> Matrix<f64> {
-        self.q_func.weights()
+delegate_weights!(SARSA<Q, P, G, A>, q_func: Q);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::Observation;
+    use crate::fa::{mocking::OneHot, Approximator, LFA};
+    use crate::policies::fixed::Random;
+
+    #[test]
+    fn test_averaging() {
+        let q_func = make_shared(LFA::vector_output(OneHot::new(3), 2));
+        let mut frozen = SARSA::new(q_func, make_shared(Random::new(2)), 0.5, 0.9)
+            .enable_averaging(0.0);
+
+        let q_func = make_shared(LFA::vector_output(OneHot::new(3), 2));
+        let mut tracking = SARSA::new(q_func, make_shared(Random::new(2)), 0.5, 0.9)
+            .enable_averaging(1.0);
+
+        for &(from, action, reward) in [(0, 1, 1.0), (1, 0, -1.0), (0, 0, 0.5)].iter() {
+            let t = Transition {
+                from: Observation::Full(from),
+                action,
+                reward,
+                to: Observation::Terminal(2),
+                truncated: false,
+            };
+
+            frozen.handle_transition(&t);
+            tracking.handle_transition(&t);
+
+            assert!(frozen.averaged_weights().iter().all(|&w| w == 0.0));
+            assert_eq!(tracking.averaged_weights(), &tracking.weights());
+        }
+
+        assert_eq!(frozen.predict_qs(&0), Vector::zeros(2));
+        assert_eq!(tracking.predict_qs(&0), tracking.q_func.evaluate(&0).unwrap());
+
+        // The target policy is greedy with respect to the averaged values:
+        assert_eq!(tracking.sample_target(&0), 1);
+        assert_eq!(tracking.sample_target(&1), 1);
     }
 }
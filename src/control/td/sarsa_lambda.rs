@@ -1,6 +1,6 @@
 use crate::core::*;
 use crate::domains::Transition;
-use crate::fa::{Approximator, Parameterised, VectorLFA, Projection, Projector, QFunction};
+use crate::fa::{Approximator, Parameterised, VectorLFA, Projector, QFunction, delegate_weights};
 use crate::policies::{Policy, FinitePolicy};
 
 /// On-policy variant of Watkins' Q-learning with eligibility traces (aka
@@ -44,6 +44,9 @@ impl<F, P> SARSALambda<F, P> {
         }
     }
 
+    /// Clear the eligibility trace without ending the current episode.
+    pub fn reset_trace(&mut self) { self.trace.clear(); }
+
     #[inline(always)]
     fn update_trace(&mut self, phi: Vector<f64>) {
         let decay_rate = self.trace.lambda.value() * self.gamma.value();
@@ -59,6 +62,11 @@ impl<F, P: Algorithm> Algorithm for SARSALambda<F, P> {
         self.gamma = self.gamma.step();
 
         self.policy.borrow_mut().handle_terminal();
+        self.reset_trace();
+    }
+
+    fn handle_episode(&mut self, episode: usize) {
+        self.policy.borrow_mut().handle_episode(episode);
     }
 }
 
@@ -78,10 +86,7 @@ where
         self.update_trace(phi_s.expanded(n_bases));
 
         // Update weight vectors:
-        let z = self.trace.get();
         let residual = if t.terminated() {
-            self.trace.decay(0.0);
-
             t.reward - qsa
         } else {
             let ns = t.to.state();
@@ -91,11 +96,14 @@ where
             t.reward + self.gamma * nqsna - qsa
         };
 
-        self.fa_theta.borrow_mut().update_action_phi(
-            &Projection::Dense(z),
-            t.action,
+        self.trace.scaled_add_to(
             self.alpha * residual,
+            self.fa_theta.borrow_mut().approximator.weights.column_mut(t.action),
         );
+
+        if t.terminated() {
+            self.trace.decay(0.0);
+        }
     }
 }
 
@@ -133,8 +141,111 @@ where
     }
 }
 
-impl<F: Parameterised, P> Parameterised for SARSALambda<F, P> {
-    fn weights(&self) -> Matrix<f64> {
-        self.fa_theta.weights()
+delegate_weights!(SARSALambda<F, P>, fa_theta: F);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::td::SARSA;
+    use crate::domains::{tests::corridor, Observation};
+    use crate::fa::{mocking::OneHot, LFA};
+    use crate::policies::fixed::Random;
+
+    const LENGTH: usize = 5;
+
+    #[test]
+    fn test_reward_propagation() {
+        let mut sarsa = SARSA::new(
+            make_shared(LFA::vector_output(OneHot::new(LENGTH), 1)),
+            make_shared(Random::new(1)),
+            0.5,
+            1.0,
+        );
+        let mut sarsa_lambda = SARSALambda::new(
+            make_shared(LFA::vector_output(OneHot::new(LENGTH), 1)),
+            make_shared(Random::new(1)),
+            Trace::accumulating(0.9, LENGTH),
+            0.5,
+            1.0,
+        );
+
+        for t in corridor(LENGTH) {
+            sarsa.handle_transition(&t);
+            sarsa_lambda.handle_transition(&t);
+        }
+
+        // One-step SARSA only credits the final state-action pair, whereas the
+        // trace propagates the reward all the way back to the start:
+        assert_eq!(sarsa.predict_qsa(&0, 0), 0.0);
+        assert!((sarsa.predict_qsa(&3, 0) - 0.5).abs() < 1e-7);
+
+        for s in 0..LENGTH - 1 {
+            let expected = 0.5 * 0.9f64.powi((LENGTH - 2 - s) as i32);
+
+            assert!((sarsa_lambda.predict_qsa(&s, 0) - expected).abs() < 1e-7);
+        }
+    }
+
+    #[test]
+    fn test_reset_trace() {
+        let run = |reset: bool| {
+            let mut agent = SARSALambda::new(
+                make_shared(LFA::vector_output(OneHot::new(LENGTH), 1)),
+                make_shared(Random::new(1)),
+                Trace::accumulating(1.0, LENGTH),
+                0.5,
+                1.0,
+            );
+            let t = |from, reward| Transition {
+                from: Observation::Full(from),
+                action: 0,
+                reward,
+                to: Observation::Full(from + 1),
+                truncated: false,
+            };
+
+            agent.handle_transition(&t(0, 0.0));
+            agent.handle_transition(&t(1, 0.0));
+
+            if reset {
+                agent.reset_trace();
+            }
+
+            agent.handle_transition(&t(2, 1.0));
+
+            (0..LENGTH).map(|s| agent.predict_qsa(&s, 0)).collect::<Vec<_>>()
+        };
+
+        assert_eq!(run(false), vec![0.5, 0.5, 0.5, 0.0, 0.0]);
+        assert_eq!(run(true), vec![0.0, 0.0, 0.5, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_truncation_clears_trace() {
+        let mut agent = SARSALambda::new(
+            make_shared(LFA::vector_output(OneHot::new(LENGTH), 1)),
+            make_shared(Random::new(1)),
+            Trace::accumulating(1.0, LENGTH),
+            0.5,
+            1.0,
+        );
+        let t = |from, reward, truncated| Transition {
+            from: Observation::Full(from),
+            action: 0,
+            reward,
+            to: Observation::Full(from + 1),
+            truncated,
+        };
+
+        // The first episode is cut short by a time limit:
+        agent.handle_transition(&t(0, 0.0, false));
+        agent.handle_transition(&t(1, 0.0, true));
+        agent.handle_terminal();
+
+        agent.handle_transition(&t(2, 1.0, false));
+
+        let qs: Vec<f64> = (0..LENGTH).map(|s| agent.predict_qsa(&s, 0)).collect();
+
+        assert_eq!(qs, vec![0.0, 0.0, 0.5, 0.0, 0.0]);
     }
 }
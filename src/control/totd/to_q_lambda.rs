@@ -48,6 +48,12 @@ impl<M, P> TOQLambda<M, P> {
         }
     }
 
+    /// Clear the eligibility trace without ending the current episode.
+    pub fn reset_trace(&mut self) {
+        self.trace.clear();
+        self.q_old = 0.0;
+    }
+
     #[inline(always)]
     fn update_traces(&mut self, phi: Vector<f64>, decay_rate: f64, update_rate: f64) {
         let trace_update = (
@@ -64,6 +70,8 @@ impl<M, P> Algorithm for TOQLambda<M, P> {
     fn handle_terminal(&mut self) {
         self.alpha = self.alpha.step();
         self.gamma = self.gamma.step();
+
+        self.reset_trace();
     }
 }
 
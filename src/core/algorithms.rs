@@ -5,12 +5,28 @@ use crate::geometry::Vector;
 pub trait Algorithm {
     /// Perform housekeeping after terminal state observation.
     fn handle_terminal(&mut self) {}
+
+    /// Inform the algorithm of the (zero-based) index of the episode that is
+    /// about to begin.
+    ///
+    /// This is invoked by experiment runners such as `SerialExperiment`, and
+    /// allows schedules to be driven by global progress rather than only by
+    /// the number of calls to `handle_terminal`.
+    fn handle_episode(&mut self, episode: usize) {}
 }
 
 pub trait OnlineLearner<S, A>: Algorithm {
     /// Handle a single transition collected from the problem environment.
     fn handle_transition(&mut self, transition: &Transition<S, A>);
 
+    /// Handle a single transition in which either state was only partially
+    /// observed.
+    ///
+    /// By default, partial observations are treated as if they were full.
+    fn handle_partial_transition(&mut self, transition: &Transition<S, A>) {
+        self.handle_transition(transition);
+    }
+
     /// Handle an arbitrary sequence of transitions collected from the problem environment.
     fn handle_sequence(&mut self, sequence: &[Transition<S, A>]) {
         sequence.into_iter().for_each(|ref t| self.handle_transition(t));
@@ -28,6 +44,14 @@ pub trait Controller<S, A> {
 
     /// Sample the behaviour policy for a given state `s`.
     fn sample_behaviour(&mut self, s: &S) -> A;
+
+    /// Sample the target policy at each of the given states.
+    ///
+    /// For tabular domains, enumerating the full state space yields the learnt
+    /// (greedy) policy as a lookup table.
+    fn greedy_policy_table(&mut self, states: &[S]) -> Vec<A> {
+        states.iter().map(|s| self.sample_target(s)).collect()
+    }
 }
 
 pub trait ValuePredictor<S> {
@@ -35,6 +59,33 @@ pub trait ValuePredictor<S> {
     fn predict_v(&mut self, s: &S) -> f64;
 }
 
+/// Trait for action types drawn from a finite, indexable set.
+pub trait DiscreteAction: Copy {
+    /// Return the index of the action in the action set.
+    fn index(self) -> usize;
+}
+
+impl DiscreteAction for usize {
+    fn index(self) -> usize { self }
+}
+
+impl DiscreteAction for u8 {
+    fn index(self) -> usize { self as usize }
+}
+
+impl DiscreteAction for u16 {
+    fn index(self) -> usize { self as usize }
+}
+
+impl DiscreteAction for u32 {
+    fn index(self) -> usize { self as usize }
+}
+
+/// Action-value predictor for discrete action spaces.
+///
+/// Estimates for every action in a state are only well-defined when the
+/// action set is finite; see `ContinuousActionValuePredictor` for continuous
+/// action spaces.
 pub trait ActionValuePredictor<S, A>: ValuePredictor<S> {
     /// Compute the estimated value of Q(s, a).
     fn predict_qsa(&mut self, s: &S, a: A) -> f64 {
@@ -46,3 +97,78 @@ pub trait ActionValuePredictor<S, A>: ValuePredictor<S> {
         unimplemented!()
     }
 }
+
+/// Action-value predictor for one-dimensional, continuous action spaces.
+pub trait ContinuousActionValuePredictor<S>: ValuePredictor<S> {
+    /// Compute the estimated value of Q(s, a) for a real-valued action.
+    fn predict_qsa_continuous(&mut self, s: &S, a: f64) -> f64;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DiscreteAgent;
+
+    impl Controller<usize, usize> for DiscreteAgent {
+        fn sample_target(&mut self, s: &usize) -> usize { *s % 2 }
+
+        fn sample_behaviour(&mut self, s: &usize) -> usize { *s % 2 }
+    }
+
+    impl ValuePredictor<usize> for DiscreteAgent {
+        fn predict_v(&mut self, s: &usize) -> f64 { *s as f64 }
+    }
+
+    impl ActionValuePredictor<usize, usize> for DiscreteAgent {
+        fn predict_qsa(&mut self, s: &usize, a: usize) -> f64 { (*s + a) as f64 }
+
+        fn predict_qs(&mut self, s: &usize) -> Vector<f64> {
+            Vector::from_vec(vec![*s as f64, *s as f64 + 1.0])
+        }
+    }
+
+    struct ContinuousAgent;
+
+    impl Controller<f64, f64> for ContinuousAgent {
+        fn sample_target(&mut self, s: &f64) -> f64 { -s }
+
+        fn sample_behaviour(&mut self, s: &f64) -> f64 { -s }
+    }
+
+    impl ValuePredictor<f64> for ContinuousAgent {
+        fn predict_v(&mut self, _: &f64) -> f64 { 0.0 }
+    }
+
+    impl ContinuousActionValuePredictor<f64> for ContinuousAgent {
+        fn predict_qsa_continuous(&mut self, s: &f64, a: f64) -> f64 { -(s + a).powi(2) }
+    }
+
+    fn greedy_value<S, A, C>(agent: &mut C, s: &S) -> f64
+    where
+        A: DiscreteAction,
+        C: Controller<S, A> + ActionValuePredictor<S, A>,
+    {
+        let a = agent.sample_target(s);
+
+        agent.predict_qs(s)[a.index()]
+    }
+
+    fn target_value<S, C>(agent: &mut C, s: &S) -> f64
+    where
+        C: Controller<S, f64> + ContinuousActionValuePredictor<S>,
+    {
+        let a = agent.sample_target(s);
+
+        agent.predict_qsa_continuous(s, a)
+    }
+
+    #[test]
+    fn test_action_bounds() {
+        assert_eq!(greedy_value(&mut DiscreteAgent, &3), 4.0);
+        assert_eq!(greedy_value(&mut DiscreteAgent, &4), 4.0);
+
+        assert_eq!(target_value(&mut ContinuousAgent, &1.5), 0.0);
+        assert_eq!(ContinuousAgent.predict_qsa_continuous(&1.0, 1.0), -4.0);
+    }
+}
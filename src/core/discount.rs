@@ -0,0 +1,35 @@
+/// State-dependent discounting applied to the bootstrap term of a TD target.
+///
+/// The unit type yields the controller's constant `gamma` parameter, whereas
+/// any closure `Fn(&S) -> f64` is evaluated on the next state in its place.
+pub trait Discount<S> {
+    /// Return the discount factor for a transition into state `s`, given the
+    /// current value of the constant `gamma` parameter.
+    fn discount(&self, gamma: f64, s: &S) -> f64;
+}
+
+impl<S> Discount<S> for () {
+    fn discount(&self, gamma: f64, _: &S) -> f64 { gamma }
+}
+
+impl<S, F: Fn(&S) -> f64> Discount<S> for F {
+    fn discount(&self, _: f64, s: &S) -> f64 { self(s) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Discount;
+
+    #[test]
+    fn test_constant() {
+        assert_eq!(Discount::<usize>::discount(&(), 0.9, &3), 0.9);
+    }
+
+    #[test]
+    fn test_closure() {
+        let gamma_fn = |s: &usize| if *s == 3 { 0.0 } else { 0.5 };
+
+        assert_eq!(gamma_fn.discount(0.9, &3), 0.0);
+        assert_eq!(gamma_fn.discount(0.9, &2), 0.5);
+    }
+}
@@ -0,0 +1,155 @@
+use crate::core::*;
+use crate::domains::Transition;
+use crate::fa::Parameterised;
+use crate::policies::Policy;
+
+/// Adapter exposing a `BatchLearner` as an `OnlineLearner`.
+///
+/// Transitions are buffered as they arrive and the full episode is passed to
+/// the wrapped learner's `handle_batch` in `handle_terminal`, after which the
+/// buffer is cleared. This allows episodic learners, such as `GradientMC`, to
+/// be run through the usual online loop, e.g. with `SerialExperiment`.
+///
+/// Actions are delegated to the learner itself if it is a `Controller`, or
+/// to a behaviour policy supplied via `with_policy` otherwise.
+pub struct EpisodicAdapter<L, S, A, P = ()> {
+    pub learner: L,
+    policy: P,
+
+    buffer: Vec<Transition<S, A>>,
+}
+
+impl<L, S, A> EpisodicAdapter<L, S, A> {
+    pub fn new(learner: L) -> Self {
+        EpisodicAdapter {
+            learner,
+            policy: (),
+
+            buffer: vec![],
+        }
+    }
+}
+
+impl<L, S, A, P> EpisodicAdapter<L, S, A, P> {
+    /// Sample actions from `policy` rather than the wrapped learner.
+    pub fn with_policy<Q>(self, policy: Shared<Q>) -> EpisodicAdapter<L, S, A, Shared<Q>> {
+        EpisodicAdapter {
+            learner: self.learner,
+            policy,
+
+            buffer: self.buffer,
+        }
+    }
+
+    /// Return the transitions buffered since the last terminal.
+    pub fn buffered(&self) -> &[Transition<S, A>] { &self.buffer }
+
+    pub fn into_inner(self) -> L { self.learner }
+}
+
+impl<L: BatchLearner<S, A>, S, A, P> Algorithm for EpisodicAdapter<L, S, A, P> {
+    fn handle_terminal(&mut self) {
+        if !self.buffer.is_empty() {
+            self.learner.handle_batch(&self.buffer);
+            self.buffer.clear();
+        }
+
+        self.learner.handle_terminal();
+    }
+
+    fn handle_episode(&mut self, episode: usize) { self.learner.handle_episode(episode); }
+}
+
+impl<L, S, A, P> OnlineLearner<S, A> for EpisodicAdapter<L, S, A, P>
+where
+    L: BatchLearner<S, A>,
+    S: Clone,
+    A: Clone,
+{
+    fn handle_transition(&mut self, t: &Transition<S, A>) { self.buffer.push(t.clone()); }
+}
+
+impl<L: Controller<S, A>, S, A> Controller<S, A> for EpisodicAdapter<L, S, A> {
+    fn sample_target(&mut self, s: &S) -> A { self.learner.sample_target(s) }
+
+    fn sample_behaviour(&mut self, s: &S) -> A { self.learner.sample_behaviour(s) }
+}
+
+impl<L, S, Q: Policy<S>> Controller<S, Q::Action> for EpisodicAdapter<L, S, Q::Action, Shared<Q>> {
+    fn sample_target(&mut self, s: &S) -> Q::Action { self.policy.borrow_mut().mpa(s) }
+
+    fn sample_behaviour(&mut self, s: &S) -> Q::Action { self.policy.borrow_mut().sample(s) }
+}
+
+impl<L: ValuePredictor<S>, S, A, P> ValuePredictor<S> for EpisodicAdapter<L, S, A, P> {
+    fn predict_v(&mut self, s: &S) -> f64 { self.learner.predict_v(s) }
+}
+
+impl<L, S, A, P> ActionValuePredictor<S, A> for EpisodicAdapter<L, S, A, P>
+where L: ActionValuePredictor<S, A>
+{
+    fn predict_qsa(&mut self, s: &S, a: A) -> f64 { self.learner.predict_qsa(s, a) }
+
+    fn predict_qs(&mut self, s: &S) -> Vector<f64> { self.learner.predict_qs(s) }
+}
+
+impl<L: Parameterised, S, A, P> Parameterised for EpisodicAdapter<L, S, A, P> {
+    fn weights(&self) -> Matrix<f64> { self.learner.weights() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{GridWorld, Observation};
+    use crate::fa::{mocking::OneHot, ScalarLFA, LFA};
+    use crate::policies::fixed::Random;
+    use crate::prediction::mc::GradientMC;
+
+    fn gradient_mc() -> GradientMC<ScalarLFA<OneHot>> {
+        GradientMC::new(make_shared(LFA::scalar_output(OneHot::new(3))), 0.5, 0.9)
+    }
+
+    #[test]
+    fn test_matches_batch() {
+        let episode = vec![
+            Transition { from: Observation::Full(0), action: 1, reward: 0.0, to: Observation::Full(1), truncated: false },
+            Transition { from: Observation::Full(1), action: 0, reward: -1.0, to: Observation::Full(0), truncated: false },
+            Transition { from: Observation::Full(0), action: 1, reward: 2.0, to: Observation::Terminal(2), truncated: false },
+        ];
+
+        let mut direct = gradient_mc();
+
+        direct.handle_batch(&episode);
+        direct.handle_terminal();
+
+        let mut adapted = EpisodicAdapter::new(gradient_mc());
+
+        for t in episode.iter() {
+            adapted.handle_transition(t);
+
+            // Nothing is learnt before the episode ends:
+            assert!(adapted.weights().iter().all(|&w| w == 0.0));
+        }
+
+        adapted.handle_terminal();
+
+        assert!(adapted.buffered().is_empty());
+        assert!(adapted.weights().all_close(&direct.weights(), 1e-12));
+        assert_eq!(adapted.learner.alpha.value(), direct.alpha.value());
+    }
+
+    #[test]
+    fn test_serial_experiment() {
+        let mut agent = EpisodicAdapter::new(gradient_mc()).with_policy(make_shared(Random::new(4)));
+
+        {
+            let domain_factory = Box::new(|| GridWorld::new(3, 1).with_goal((2, 0), 1.0));
+
+            run(SerialExperiment::new(&mut agent, domain_factory, 100), 10, None);
+        }
+
+        // Only the goal is rewarded, so the start state has positive value:
+        assert!(agent.predict_v(&0) > 0.0);
+        assert!(agent.buffered().is_empty());
+    }
+}
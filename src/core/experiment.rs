@@ -1,7 +1,9 @@
 use crate::core::*;
-use crate::domains::{Domain, Observation};
+use crate::domains::{Domain, Observation, Reset, Transition};
 use crate::geometry::Space;
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
 use slog::{Logger, Record, Result as LogResult, Serializer, KV};
+use std::cell::RefCell;
 
 /// Container for episodic statistics.
 #[derive(Debug)]
@@ -11,6 +13,10 @@ pub struct Episode {
 
     /// The total accumulated reward over the episode.
     pub reward: f64,
+
+    /// The accumulated reward over the episode, discounted from its first
+    /// step, if a discount factor was configured.
+    pub discounted_reward: Option<f64>,
 }
 
 impl KV for Episode {
@@ -18,10 +24,64 @@ impl KV for Episode {
         serializer.emit_u64("steps", self.steps)?;
         serializer.emit_f64("reward", self.reward)?;
 
+        if let Some(discounted_reward) = self.discounted_reward {
+            serializer.emit_f64("discounted_reward", discounted_reward)?;
+        }
+
         Ok(())
     }
 }
 
+/// Criterion for terminating an experiment before its episode budget is
+/// consumed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StopCondition {
+    /// Stop once the mean reward over the last `window` episodes is at least
+    /// `threshold`.
+    RollingAverage { window: usize, threshold: f64 },
+}
+
+impl StopCondition {
+    /// Returns true if the condition is satisfied by the episodes seen so far.
+    pub fn is_met(&self, episodes: &[Episode]) -> bool {
+        match *self {
+            StopCondition::RollingAverage { window, threshold } => {
+                if window == 0 || episodes.len() < window {
+                    return false;
+                }
+
+                let recent = &episodes[episodes.len() - window..];
+                let mean = recent.iter().map(|e| e.reward).sum::<f64>() / window as f64;
+
+                mean >= threshold
+            },
+        }
+    }
+}
+
+/// Reason for which an experiment was terminated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StopReason {
+    /// The full episode budget was consumed.
+    Budget,
+
+    /// The `StopCondition` was satisfied.
+    Condition,
+}
+
+/// Outcome of an experiment run with `run_until`.
+#[derive(Debug)]
+pub struct ExperimentResult {
+    /// Statistics of each episode, in order.
+    pub episodes: Vec<Episode>,
+
+    /// Reason for which the experiment was terminated.
+    pub stop_reason: StopReason,
+
+    /// Running summary of the episode returns.
+    pub statistics: RunningStatistics,
+}
+
 /// Helper function for running experiments.
 pub fn run(
     runner: impl Iterator<Item = Episode>,
@@ -44,10 +104,154 @@ pub fn run(
     }
 }
 
+/// Helper function for running experiments with an optional early stopping
+/// criterion, checked after each episode.
+///
+/// A running summary of the episode returns is maintained throughout and, if
+/// `summary_every` is set, logged after every so many episodes.
+pub fn run_until(
+    runner: impl Iterator<Item = Episode>,
+    n_episodes: usize,
+    stop: Option<StopCondition>,
+    summary_every: Option<usize>,
+    logger: Option<Logger>,
+) -> ExperimentResult
+{
+    let mut episodes = Vec::with_capacity(n_episodes);
+    let mut statistics = RunningStatistics::new();
+
+    for (res, i) in runner.take(n_episodes).zip(1..(n_episodes + 1)) {
+        statistics.update(res.reward);
+
+        if let Some(ref logger) = logger {
+            info!(logger, "episode {}", i; &res);
+
+            if summary_every.map_or(false, |k| k > 0 && i % k == 0) {
+                info!(logger, "summary after {} episodes", i; statistics);
+            }
+        }
+
+        episodes.push(res);
+
+        if stop.map_or(false, |c| c.is_met(&episodes)) {
+            return ExperimentResult {
+                episodes,
+                stop_reason: StopReason::Condition,
+                statistics,
+            };
+        }
+    }
+
+    ExperimentResult {
+        episodes,
+        stop_reason: StopReason::Budget,
+        statistics,
+    }
+}
+
+/// Helper function for running reproducible training experiments.
+///
+/// A master `StdRng` is seeded from `seed` (or from the thread-local generator
+/// if `None`) and passed to `agent_builder`, from which any stochastic agent
+/// components, such as policies, should derive their own generators. Each
+/// episode's domain is constructed by `domain_builder` from a seed drawn from
+/// the master generator, so that the entire experiment is determined by a
+/// single value.
+pub fn run_seeded<S, A, C, D, FC, FD>(
+    agent_builder: FC,
+    domain_builder: FD,
+    n_episodes: usize,
+    step_limit: u64,
+    seed: Option<u64>,
+    logger: Option<Logger>,
+) -> Vec<Episode>
+where
+    S: Space,
+    A: Space,
+    C: OnlineLearner<S::Value, A::Value> + Controller<S::Value, A::Value>,
+    D: Domain<StateSpace = S, ActionSpace = A>,
+    FC: FnOnce(&mut StdRng) -> C,
+    FD: Fn(u64) -> D + 'static,
+{
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_rng(thread_rng()).expect("Failed to seed master generator."),
+    };
+
+    let mut agent = agent_builder(&mut rng);
+    let rng = RefCell::new(rng);
+
+    let domain_factory = Box::new(move || domain_builder(rng.borrow_mut().gen()));
+
+    run(SerialExperiment::new(&mut agent, domain_factory, step_limit), n_episodes, logger)
+}
+
+/// Summary of a single evaluation episode.
+#[derive(Debug)]
+pub struct EvalReport<S, A> {
+    /// The number of steps taken to reach the terminal state.
+    pub steps: u64,
+
+    /// The total accumulated reward over the episode.
+    pub total_reward: f64,
+
+    /// The accumulated reward over the episode, discounted by `gamma`.
+    pub discounted_reward: f64,
+
+    /// The sequence of transitions observed, if recorded.
+    pub trajectory: Option<Vec<Transition<S, A>>>,
+}
+
+impl<S, A> Into<Episode> for EvalReport<S, A> {
+    fn into(self) -> Episode {
+        Episode {
+            steps: self.steps,
+            reward: self.total_reward,
+            discounted_reward: Some(self.discounted_reward),
+        }
+    }
+}
+
+/// Aggregate statistics over a batch of evaluation episodes.
+#[derive(Debug)]
+pub struct EvalSummary {
+    /// The mean total reward per episode.
+    pub mean_return: f64,
+
+    /// The (population) standard deviation of the total reward per episode.
+    pub std_return: f64,
+
+    /// The lowest total reward of any episode.
+    pub min: f64,
+
+    /// The highest total reward of any episode.
+    pub max: f64,
+
+    /// The mean number of steps per episode.
+    pub mean_length: f64,
+}
+
+/// Source of the domain of each evaluation episode.
+enum DomainSource<D> {
+    Factory(Box<Fn() -> D>),
+    Builder(Box<Fn(u64) -> D>),
+}
+
 /// Utility for running a single evaluation episode.
+///
+/// If constructed with `from_builder` and a base seed is set via `with_seed`,
+/// the domain of evaluation episode `i` is built from the seed `base_seed +
+/// i`. Competing agents evaluated with the same base seed thus face identical
+/// sequences of stochastic domains.
 pub struct Evaluation<'a, C: 'a, D> {
     agent: &'a mut C,
-    domain_factory: Box<Fn() -> D>,
+    domains: DomainSource<D>,
+
+    gamma: f64,
+    record_trajectory: bool,
+
+    seed: Option<u64>,
+    episode: u64,
 }
 
 impl<'a, S: Space, A: Space, C, D> Evaluation<'a, C, D>
@@ -56,9 +260,123 @@ where
     D: Domain<StateSpace = S, ActionSpace = A>,
 {
     pub fn new(agent: &'a mut C, domain_factory: Box<Fn() -> D>) -> Evaluation<'a, C, D> {
+        Evaluation::from_source(agent, DomainSource::Factory(domain_factory))
+    }
+
+    /// Construct an evaluation whose domains are built from a seed for each
+    /// episode; see `with_seed`.
+    pub fn from_builder(agent: &'a mut C, domain_builder: Box<Fn(u64) -> D>) -> Evaluation<'a, C, D> {
+        Evaluation::from_source(agent, DomainSource::Builder(domain_builder))
+    }
+
+    fn from_source(agent: &'a mut C, domains: DomainSource<D>) -> Evaluation<'a, C, D> {
         Evaluation {
             agent,
-            domain_factory,
+            domains,
+
+            gamma: 1.0,
+            record_trajectory: false,
+
+            seed: None,
+            episode: 0,
+        }
+    }
+
+    /// Set the discount factor used to compute `EvalReport::discounted_reward`.
+    pub fn with_discount(mut self, gamma: f64) -> Evaluation<'a, C, D> {
+        self.gamma = gamma;
+
+        self
+    }
+
+    /// Record the full trajectory of each evaluation episode.
+    pub fn with_trajectory(mut self) -> Evaluation<'a, C, D> {
+        self.record_trajectory = true;
+
+        self
+    }
+
+    /// Build the domain of the `i`th evaluation episode from the seed
+    /// `base_seed + i`, rather than from a seed drawn at random.
+    ///
+    /// This has no effect on evaluations constructed with `new`, as their
+    /// domain factory takes no seed.
+    pub fn with_seed(mut self, base_seed: u64) -> Evaluation<'a, C, D> {
+        self.seed = Some(base_seed);
+
+        self
+    }
+
+    /// Run a single evaluation episode and report its statistics.
+    pub fn report(&mut self) -> EvalReport<S::Value, A::Value> {
+        let mut domain = match self.domains {
+            DomainSource::Factory(ref factory) => factory(),
+            DomainSource::Builder(ref builder) => builder(match self.seed {
+                Some(base_seed) => base_seed.wrapping_add(self.episode),
+                None => thread_rng().gen(),
+            }),
+        };
+
+        self.episode += 1;
+
+        let mut a = self.agent.sample_target(&domain.emit().state());
+
+        let mut report = EvalReport {
+            steps: 0,
+            total_reward: 0.0,
+            discounted_reward: 0.0,
+            trajectory: if self.record_trajectory { Some(vec![]) } else { None },
+        };
+        let mut discount = 1.0;
+
+        loop {
+            let t = domain.step(a);
+
+            report.steps += 1;
+            report.total_reward += t.reward;
+            report.discounted_reward += discount * t.reward;
+            discount *= self.gamma;
+
+            let next = if t.ended() {
+                None
+            } else {
+                Some(self.agent.sample_target(&t.to.state()))
+            };
+
+            if let Some(ref mut trajectory) = report.trajectory {
+                trajectory.push(t);
+            }
+
+            match next {
+                Some(na) => a = na,
+                None => break,
+            }
+        }
+
+        report
+    }
+
+    /// Run `n_episodes` evaluation episodes, each on a fresh domain instance,
+    /// and summarise their returns and lengths.
+    pub fn run(&mut self, n_episodes: usize) -> EvalSummary {
+        assert!(n_episodes > 0, "Evaluation requires at least one episode.");
+
+        let mut returns = RunningStatistics::new();
+        let mut lengths = RunningStatistics::new();
+
+        for _ in 0..n_episodes {
+            let report = self.report();
+
+            returns.update(report.total_reward);
+            lengths.update(report.steps as f64);
+        }
+
+        EvalSummary {
+            mean_return: returns.mean(),
+            std_return: returns.std(),
+            min: returns.min(),
+            max: returns.max(),
+            mean_length: lengths.mean(),
         }
     }
 }
@@ -70,40 +388,198 @@ where
 {
     type Item = Episode;
 
+    fn next(&mut self) -> Option<Episode> { Some(self.report().into()) }
+}
+
+/// Utility for running a sequence of training episodes.
+pub struct SerialExperiment<'a, C: 'a, D> {
+    agent: &'a mut C,
+    domain_factory: Box<Fn() -> D>,
+
+    step_limit: u64,
+    gamma: Option<f64>,
+
+    episode: usize,
+    checkpoint: Option<(usize, Box<FnMut(&C, usize) + 'a>)>,
+
+    domain: Option<D>,
+    reset: Option<fn(&mut D)>,
+}
+
+impl<'a, S: Space, A: Space, C, D> SerialExperiment<'a, C, D>
+where
+    C: Controller<S::Value, A::Value>,
+    D: Domain<StateSpace = S, ActionSpace = A>,
+{
+    pub fn new(
+        agent: &'a mut C,
+        domain_factory: Box<Fn() -> D>,
+        step_limit: u64,
+    ) -> SerialExperiment<'a, C, D>
+    {
+        SerialExperiment {
+            agent,
+            domain_factory,
+            step_limit,
+            gamma: None,
+
+            episode: 0,
+            checkpoint: None,
+
+            domain: None,
+            reset: None,
+        }
+    }
+
+    /// Invoke `callback` with the agent and the (one-based) episode index
+    /// after every `n_episodes` completed episodes.
+    pub fn every<F>(mut self, n_episodes: usize, callback: F) -> SerialExperiment<'a, C, D>
+    where F: FnMut(&C, usize) + 'a {
+        assert!(n_episodes > 0, "Checkpoint interval must be positive.");
+
+        self.checkpoint = Some((n_episodes, Box::new(callback)));
+
+        self
+    }
+
+    /// Track the return of each episode discounted by `gamma`, e.g. the
+    /// agent's own discount factor, and report it in
+    /// `Episode::discounted_reward`.
+    pub fn with_discount(mut self, gamma: f64) -> SerialExperiment<'a, C, D> {
+        self.gamma = Some(gamma);
+
+        self
+    }
+}
+
+impl<'a, S: Space, A: Space, C, D> SerialExperiment<'a, C, D>
+where
+    C: Controller<S::Value, A::Value>,
+    D: Domain<StateSpace = S, ActionSpace = A> + Reset,
+{
+    /// Reuse a single domain instance across episodes, restoring it with
+    /// `Domain::reset` instead of constructing a new one from the factory.
+    pub fn with_reset(mut self) -> SerialExperiment<'a, C, D> {
+        self.reset = Some(|domain: &mut D| { domain.reset(); });
+
+        self
+    }
+}
+
+impl<'a, S: Space, A: Space, C, D> Iterator for SerialExperiment<'a, C, D>
+where
+    C: OnlineLearner<S::Value, A::Value> + Controller<S::Value, A::Value>,
+    D: Domain<StateSpace = S, ActionSpace = A>,
+{
+    type Item = Episode;
+
     fn next(&mut self) -> Option<Episode> {
-        let mut domain = (self.domain_factory)();
-        let mut a = self.agent.sample_target(&domain.emit().state());
+        let mut domain = match (self.domain.take(), self.reset) {
+            (Some(mut domain), Some(reset)) => {
+                reset(&mut domain);
+
+                domain
+            },
+            _ => (self.domain_factory)(),
+        };
+
+        self.agent.handle_episode(self.episode);
+
+        let mut a = self.agent.sample_behaviour(domain.emit().state());
 
         let mut e = Episode {
             steps: 1,
             reward: 0.0,
+            discounted_reward: self.gamma.map(|_| 0.0),
         };
+        let mut discount = 1.0;
 
-        loop {
+        for j in 1..(self.step_limit + 1) {
             let t = domain.step(a);
 
-            e.steps += 1;
+            e.steps = j;
             e.reward += t.reward;
 
-            a = match t.to {
-                Observation::Terminal(_) => break,
-                _ => self.agent.sample_target(&t.to.state()),
-            };
+            if let Some(gamma) = self.gamma {
+                e.discounted_reward = e.discounted_reward.map(|g| g + discount * t.reward);
+                discount *= gamma;
+            }
+
+            if t.partially_observed() {
+                self.agent.handle_partial_transition(&t);
+            } else {
+                self.agent.handle_transition(&t);
+            }
+
+            if t.ended() || j >= self.step_limit {
+                self.agent.handle_terminal();
+
+                break
+            } else {
+                a = self.agent.sample_behaviour(t.to.state());
+            }
+        }
+
+        if self.reset.is_some() {
+            self.domain = Some(domain);
+        }
+
+        self.episode += 1;
+
+        if let Some((n, ref mut callback)) = self.checkpoint {
+            if self.episode % n == 0 {
+                callback(&*self.agent, self.episode);
+            }
         }
 
         Some(e)
     }
 }
 
-/// Utility for running a sequence of training episodes.
-pub struct SerialExperiment<'a, C: 'a, D> {
+/// Split a batch of transitions into its constituent episodes.
+///
+/// A new episode begins after every transition that `ended`; any trailing
+/// transitions that do not end in a terminal state form the final episode.
+pub fn split_episodes<S, A>(batch: &[Transition<S, A>]) -> Vec<&[Transition<S, A>]> {
+    let mut episodes = vec![];
+    let mut start = 0;
+
+    for (i, t) in batch.iter().enumerate() {
+        if t.ended() {
+            episodes.push(&batch[start..=i]);
+            start = i + 1;
+        }
+    }
+
+    if start < batch.len() {
+        episodes.push(&batch[start..]);
+    }
+
+    episodes
+}
+
+/// Utility for running training episodes in batches, for use with
+/// `BatchLearner`s.
+///
+/// The behaviour policy is run for `batch_size` episodes, after which all of
+/// the collected transitions are passed to `handle_batch` in a single call.
+/// Episodes cut short by the step limit end in a truncated transition, such
+/// that the episode boundaries can always be recovered with `split_episodes`.
+pub struct BatchExperiment<'a, C: 'a, D: Domain> {
     agent: &'a mut C,
     domain_factory: Box<Fn() -> D>,
 
     step_limit: u64,
+    batch_size: usize,
+
+    episode: usize,
+    batch: Vec<Transition<
+        <D::StateSpace as Space>::Value,
+        <D::ActionSpace as Space>::Value,
+    >>,
 }
 
-impl<'a, S: Space, A: Space, C, D> SerialExperiment<'a, C, D>
+impl<'a, S: Space, A: Space, C, D> BatchExperiment<'a, C, D>
 where
     C: Controller<S::Value, A::Value>,
     D: Domain<StateSpace = S, ActionSpace = A>,
@@ -112,30 +588,42 @@ where
         agent: &'a mut C,
         domain_factory: Box<Fn() -> D>,
         step_limit: u64,
-    ) -> SerialExperiment<'a, C, D>
+        batch_size: usize,
+    ) -> BatchExperiment<'a, C, D>
     {
-        SerialExperiment {
+        assert!(batch_size > 0, "Batch size must be positive.");
+
+        BatchExperiment {
             agent,
             domain_factory,
+
             step_limit,
+            batch_size,
+
+            episode: 0,
+            batch: vec![],
         }
     }
 }
 
-impl<'a, S: Space, A: Space, C, D> Iterator for SerialExperiment<'a, C, D>
+impl<'a, S: Space, A: Space, C, D> Iterator for BatchExperiment<'a, C, D>
 where
-    C: OnlineLearner<S::Value, A::Value> + Controller<S::Value, A::Value>,
+    C: BatchLearner<S::Value, A::Value> + Controller<S::Value, A::Value>,
     D: Domain<StateSpace = S, ActionSpace = A>,
 {
     type Item = Episode;
 
     fn next(&mut self) -> Option<Episode> {
         let mut domain = (self.domain_factory)();
+
+        self.agent.handle_episode(self.episode);
+
         let mut a = self.agent.sample_behaviour(domain.emit().state());
 
         let mut e = Episode {
             steps: 1,
             reward: 0.0,
+            discounted_reward: None,
         };
 
         for j in 1..(self.step_limit + 1) {
@@ -144,17 +632,374 @@ where
             e.steps = j;
             e.reward += t.reward;
 
-            self.agent.handle_transition(&t);
-
-            if t.terminated() || j >= self.step_limit {
-                self.agent.handle_terminal();
+            if t.ended() || j >= self.step_limit {
+                self.batch.push(t.into_truncated());
 
                 break
             } else {
                 a = self.agent.sample_behaviour(t.to.state());
+
+                self.batch.push(t);
             }
         }
 
+        self.episode += 1;
+
+        if self.episode % self.batch_size == 0 {
+            self.agent.handle_batch(&self.batch);
+            self.batch.clear();
+        }
+
+        self.agent.handle_terminal();
+
         Some(e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mocking::{CountingAgent, MoveRight, RandomAgent};
+    use crate::domains::GridWorld;
+    use crate::policies::fixed::Random;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_checkpoint() {
+        let fired = Rc::new(RefCell::new(vec![]));
+        let mut agent = MoveRight;
+
+        {
+            let fired = fired.clone();
+            let domain_factory = Box::new(|| GridWorld::new(3, 1).with_goal((2, 0), 1.0));
+            let exp = SerialExperiment::new(&mut agent, domain_factory, 10)
+                .every(3, move |_, i| fired.borrow_mut().push(i));
+
+            let episodes = run(exp, 10, None);
+
+            assert_eq!(episodes.len(), 10);
+            assert!(episodes.iter().all(|e| e.steps == 2 && e.reward == 1.0));
+        }
+
+        assert_eq!(*fired.borrow(), vec![3, 6, 9]);
+    }
+
+    fn seeded_returns(seed: u64) -> Vec<f64> {
+        run_seeded(
+            |rng| RandomAgent(Random::with_rng(4, StdRng::from_rng(rng).unwrap())),
+            |seed| GridWorld::new(5, 5)
+                .with_goal((4, 4), 1.0)
+                .with_slip(0.2)
+                .with_seed(seed),
+            20,
+            50,
+            Some(seed),
+            None,
+        ).into_iter().map(|e| e.reward - e.steps as f64).collect()
+    }
+
+    #[test]
+    fn test_seeded_reproducibility() {
+        assert_eq!(seeded_returns(0), seeded_returns(0));
+        assert_eq!(seeded_returns(42), seeded_returns(42));
+    }
+
+    /// Corridor in which only the parity of the agent's position is observed.
+    #[derive(Default)]
+    struct ParityCorridor(usize);
+
+    impl Domain for ParityCorridor {
+        type StateSpace = crate::geometry::discrete::Ordinal;
+        type ActionSpace = crate::geometry::discrete::Ordinal;
+
+        fn emit(&self) -> Observation<usize> {
+            if self.is_terminal() {
+                Observation::Terminal(self.0 % 2)
+            } else {
+                Observation::Partial(self.0 % 2)
+            }
+        }
+
+        fn step(&mut self, action: usize) -> Transition<usize, usize> {
+            let from = self.emit();
+
+            self.0 += 1;
+            let to = self.emit();
+
+            Transition {
+                from,
+                action,
+                reward: -1.0,
+                to,
+                truncated: false,
+            }
+        }
+
+        fn is_terminal(&self) -> bool { self.0 >= 4 }
+
+        fn reward(&self, _: &Observation<usize>, _: &Observation<usize>) -> f64 { -1.0 }
+
+        fn state_space(&self) -> Self::StateSpace { crate::geometry::discrete::Ordinal::new(2) }
+
+        fn action_space(&self) -> Self::ActionSpace { crate::geometry::discrete::Ordinal::new(1) }
+    }
+
+    #[test]
+    fn test_partial_observations() {
+        let mut agent = CountingAgent::default();
+
+        {
+            let exp = SerialExperiment::new(&mut agent, Box::new(|| ParityCorridor(0)), 10);
+
+            assert!(run(exp, 2, None).iter().all(|e| e.steps == 4));
+        }
+
+        assert_eq!(agent.full, 0);
+        assert_eq!(agent.partial, 8);
+
+        let mut agent = CountingAgent::default();
+
+        {
+            let domain_factory = Box::new(|| GridWorld::new(3, 1).with_goal((2, 0), 1.0));
+            let exp = SerialExperiment::new(&mut agent, domain_factory, 10);
+
+            run(exp, 2, None);
+        }
+
+        assert_eq!(agent.full, 4);
+        assert_eq!(agent.partial, 0);
+    }
+
+    #[test]
+    fn test_discounted_reward() {
+        let gamma = 0.9;
+        let mut agent = CountingAgent::default();
+
+        let episodes = {
+            let exp = SerialExperiment::new(&mut agent, Box::new(|| ParityCorridor(0)), 10)
+                .with_discount(gamma);
+
+            run(exp, 3, None)
+        };
+
+        // Four steps of reward -1, with discounting restarted every episode:
+        let expected = -(1.0 - gamma.powi(4)) / (1.0 - gamma);
+
+        for e in episodes {
+            assert_eq!(e.reward, -4.0);
+            assert!((e.discounted_reward.unwrap() - expected).abs() < 1e-10);
+        }
+
+        let mut agent = CountingAgent::default();
+        let exp = SerialExperiment::new(&mut agent, Box::new(|| ParityCorridor(0)), 10);
+
+        assert!(run(exp, 1, None)[0].discounted_reward.is_none());
+    }
+
+    #[test]
+    fn test_domain_reset() {
+        let built = Rc::new(RefCell::new(0));
+        let mut agent = CountingAgent::default();
+
+        {
+            let built = built.clone();
+            let domain_factory = Box::new(move || {
+                *built.borrow_mut() += 1;
+
+                ParityCorridor(0)
+            });
+            let exp = SerialExperiment::new(&mut agent, domain_factory, 10).with_reset();
+
+            assert!(run(exp, 3, None).iter().all(|e| e.steps == 4));
+        }
+
+        assert_eq!(*built.borrow(), 1);
+        assert_eq!(agent.partial, 12);
+        assert_eq!(agent.episodes, vec![0, 1, 2]);
+    }
+
+    #[derive(Default)]
+    struct BatchCounter {
+        batches: Vec<(usize, usize)>,
+        truncated: usize,
+    }
+
+    impl Algorithm for BatchCounter {}
+
+    impl BatchLearner<usize, usize> for BatchCounter {
+        fn handle_batch(&mut self, batch: &[Transition<usize, usize>]) {
+            self.batches.push((batch.len(), split_episodes(batch).len()));
+            self.truncated += batch.iter().filter(|t| t.truncated).count();
+        }
+    }
+
+    impl Controller<usize, usize> for BatchCounter {
+        fn sample_target(&mut self, _: &usize) -> usize { 3 }
+
+        fn sample_behaviour(&mut self, _: &usize) -> usize { 3 }
+    }
+
+    #[test]
+    fn test_batch_experiment() {
+        let mut agent = BatchCounter::default();
+
+        {
+            let domain_factory = Box::new(|| GridWorld::new(3, 1).with_goal((2, 0), 1.0));
+            let exp = BatchExperiment::new(&mut agent, domain_factory, 10, 3);
+
+            assert!(run(exp, 7, None).iter().all(|e| e.steps == 2 && e.reward == 1.0));
+        }
+
+        // Two full batches of three two-step episodes; the last is pending:
+        assert_eq!(agent.batches, vec![(6, 3), (6, 3)]);
+        assert_eq!(agent.truncated, 0);
+
+        let mut agent = BatchCounter::default();
+
+        {
+            let domain_factory = Box::new(|| GridWorld::new(3, 1).with_goal((2, 0), 1.0));
+            let exp = BatchExperiment::new(&mut agent, domain_factory, 1, 2);
+
+            run(exp, 4, None);
+        }
+
+        // Episodes cut by the step limit are still delimited:
+        assert_eq!(agent.batches, vec![(2, 2), (2, 2)]);
+        assert_eq!(agent.truncated, 4);
+    }
+
+    #[test]
+    fn test_evaluation_report() {
+        let mut agent = MoveRight;
+        let domain_factory = Box::new(|| GridWorld::new(4, 1).with_goal((3, 0), 1.0));
+
+        let report = Evaluation::new(&mut agent, domain_factory)
+            .with_discount(0.9)
+            .with_trajectory()
+            .report();
+
+        assert_eq!(report.steps, 3);
+        assert_eq!(report.total_reward, 1.0);
+        assert!((report.discounted_reward - 0.81).abs() < 1e-10);
+
+        let trajectory = report.trajectory.unwrap();
+
+        assert_eq!(trajectory.len(), 3);
+        assert!(trajectory.iter().all(|t| t.action == 3));
+        assert_eq!(
+            trajectory.iter().map(|t| *t.from.state()).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert!(trajectory[2].terminated());
+
+        let mut agent = MoveRight;
+        let domain_factory = Box::new(|| GridWorld::new(4, 1).with_goal((3, 0), 1.0));
+        let report = Evaluation::new(&mut agent, domain_factory).report();
+
+        assert!(report.trajectory.is_none());
+    }
+
+    /// Greedy agent over a fixed set of action preferences.
+    struct Preferences(Vec<f64>);
+
+    impl Controller<usize, usize> for Preferences {
+        fn sample_target(&mut self, _: &usize) -> usize {
+            crate::utils::argmaxima(&self.0).1[0]
+        }
+
+        fn sample_behaviour(&mut self, s: &usize) -> usize { self.sample_target(s) }
+    }
+
+    fn seeded_trajectories<C: Controller<usize, usize>>(
+        agent: &mut C,
+        base_seed: u64,
+    ) -> Vec<Vec<(usize, usize, f64, usize)>>
+    {
+        let domain_builder = Box::new(|seed: u64| GridWorld::new(5, 5)
+            .with_goal((4, 0), 1.0)
+            .with_slip(0.3)
+            .with_seed(seed));
+        let mut evaluation = Evaluation::from_builder(agent, domain_builder)
+            .with_seed(base_seed)
+            .with_trajectory();
+
+        (0..5).map(|_| {
+            evaluation.report().trajectory.unwrap().into_iter().map(|t| {
+                (*t.from.state(), t.action, t.reward, *t.to.state())
+            }).collect()
+        }).collect()
+    }
+
+    #[test]
+    fn test_evaluation_seed() {
+        let a = seeded_trajectories(&mut MoveRight, 7);
+        let b = seeded_trajectories(&mut Preferences(vec![0.0, 0.5, -1.0, 2.0]), 7);
+
+        assert_eq!(a, b);
+
+        // Each episode is seeded by its index, so the sequence is the same
+        // as the tail of one started from an earlier base seed:
+        let c = seeded_trajectories(&mut MoveRight, 6);
+
+        assert_eq!(a[..4], c[1..]);
+    }
+
+    #[test]
+    fn test_evaluation_run() {
+        let mut agent = MoveRight;
+        let domain_factory = Box::new(|| GridWorld::new(4, 1).with_goal((3, 0), 1.0));
+
+        let summary = Evaluation::new(&mut agent, domain_factory).run(100);
+
+        assert_eq!(summary.mean_return, 1.0);
+        assert_eq!(summary.std_return, 0.0);
+        assert_eq!(summary.min, 1.0);
+        assert_eq!(summary.max, 1.0);
+        assert_eq!(summary.mean_length, 3.0);
+    }
+
+    #[test]
+    fn test_stop_condition() {
+        let mut agent = MoveRight;
+        let domain_factory = Box::new(|| GridWorld::new(3, 1).with_goal((2, 0), 1.0));
+        let exp = SerialExperiment::new(&mut agent, domain_factory, 10);
+
+        let stop = StopCondition::RollingAverage { window: 5, threshold: 0.9 };
+        let result = run_until(exp, 100, Some(stop), None, None);
+
+        assert_eq!(result.episodes.len(), 5);
+        assert_eq!(result.stop_reason, StopReason::Condition);
+
+        let mut agent = MoveRight;
+        let domain_factory = Box::new(|| GridWorld::new(3, 1).with_goal((2, 0), 1.0));
+        let exp = SerialExperiment::new(&mut agent, domain_factory, 10);
+
+        let stop = StopCondition::RollingAverage { window: 5, threshold: 2.0 };
+        let result = run_until(exp, 20, Some(stop), None, None);
+
+        assert_eq!(result.episodes.len(), 20);
+        assert_eq!(result.stop_reason, StopReason::Budget);
+    }
+
+    #[test]
+    fn test_run_statistics() {
+        let rewards = vec![1.0, -2.0, 4.0, 0.5, 3.0];
+        let runner = rewards.clone().into_iter().map(|reward| Episode {
+            steps: 1,
+            reward,
+            discounted_reward: None,
+        });
+
+        let stats = run_until(runner, 10, None, Some(2), None).statistics;
+
+        let n = rewards.len() as f64;
+        let mean = rewards.iter().sum::<f64>() / n;
+        let variance = rewards.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / n;
+
+        assert_eq!(stats.count(), 5);
+        assert!((stats.mean() - mean).abs() < 1e-10);
+        assert!((stats.variance() - variance).abs() < 1e-10);
+        assert_eq!(stats.min(), -2.0);
+        assert_eq!(stats.max(), 4.0);
+    }
+}
@@ -0,0 +1,58 @@
+/// Loss function from which the magnitude of a value function update is
+/// derived.
+///
+/// Updates are taken along the negative gradient of the loss with respect to
+/// the prediction, so each variant maps a TD error, `delta`, to the effective
+/// error used in the update.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LossKind {
+    /// Squared error, `delta^2 / 2`, whose gradient is the TD error itself.
+    Squared,
+
+    /// Huber (smooth L1) loss with threshold `k`: quadratic for `|delta| <= k`
+    /// and linear beyond it, so the effective error is bounded by `k`.
+    Huber(f64),
+}
+
+impl LossKind {
+    /// Return the effective error associated with the TD error `delta`.
+    pub fn gradient(&self, delta: f64) -> f64 {
+        match *self {
+            LossKind::Squared => delta,
+            LossKind::Huber(k) => if delta.abs() <= k {
+                delta
+            } else {
+                k * delta.signum()
+            },
+        }
+    }
+}
+
+impl Default for LossKind {
+    fn default() -> LossKind { LossKind::Squared }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LossKind;
+
+    #[test]
+    fn test_squared() {
+        for &delta in [-100.0, -1.0, 0.0, 0.5, 100.0].iter() {
+            assert_eq!(LossKind::Squared.gradient(delta), delta);
+        }
+    }
+
+    #[test]
+    fn test_huber() {
+        let loss = LossKind::Huber(1.0);
+
+        assert_eq!(loss.gradient(0.5), 0.5);
+        assert_eq!(loss.gradient(-0.5), -0.5);
+        assert_eq!(loss.gradient(1.0), 1.0);
+
+        assert_eq!(loss.gradient(3.0), 1.0);
+        assert_eq!(loss.gradient(-3.0), -1.0);
+        assert_eq!(loss.gradient(1e9), 1.0);
+    }
+}
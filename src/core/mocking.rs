@@ -0,0 +1,58 @@
+use crate::core::{Algorithm, Controller, OnlineLearner};
+use crate::domains::Transition;
+use crate::policies::{fixed::Random, Policy};
+use rand::rngs::StdRng;
+
+/// Agent that always moves right in a `GridWorld` and never learns.
+pub struct MoveRight;
+
+impl Algorithm for MoveRight {}
+
+impl OnlineLearner<usize, usize> for MoveRight {
+    fn handle_transition(&mut self, _: &Transition<usize, usize>) {}
+}
+
+impl Controller<usize, usize> for MoveRight {
+    fn sample_target(&mut self, _: &usize) -> usize { 3 }
+
+    fn sample_behaviour(&mut self, _: &usize) -> usize { 3 }
+}
+
+/// Agent that acts uniformly at random and never learns.
+pub struct RandomAgent(pub Random<StdRng>);
+
+impl Algorithm for RandomAgent {}
+
+impl OnlineLearner<usize, usize> for RandomAgent {
+    fn handle_transition(&mut self, _: &Transition<usize, usize>) {}
+}
+
+impl Controller<usize, usize> for RandomAgent {
+    fn sample_target(&mut self, s: &usize) -> usize { self.0.sample(s) }
+
+    fn sample_behaviour(&mut self, s: &usize) -> usize { self.0.sample(s) }
+}
+
+/// Agent that moves right and counts the transitions and episodes it is shown.
+#[derive(Default)]
+pub struct CountingAgent {
+    pub full: usize,
+    pub partial: usize,
+    pub episodes: Vec<usize>,
+}
+
+impl Algorithm for CountingAgent {
+    fn handle_episode(&mut self, episode: usize) { self.episodes.push(episode); }
+}
+
+impl OnlineLearner<usize, usize> for CountingAgent {
+    fn handle_transition(&mut self, _: &Transition<usize, usize>) { self.full += 1; }
+
+    fn handle_partial_transition(&mut self, _: &Transition<usize, usize>) { self.partial += 1; }
+}
+
+impl Controller<usize, usize> for CountingAgent {
+    fn sample_target(&mut self, _: &usize) -> usize { 3 }
+
+    fn sample_behaviour(&mut self, _: &usize) -> usize { 3 }
+}
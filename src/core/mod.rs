@@ -1,7 +1,22 @@
 pub use crate::geometry::{Matrix, Vector};
 
+#[cfg(test)]
+pub(crate) mod mocking;
+
 import_all!(trace);
 import_all!(memory);
+import_all!(replay);
+import_all!(prioritised_replay);
+import_all!(model);
 import_all!(parameter);
+import_all!(reward_normaliser);
+import_all!(statistics);
+import_all!(returns);
+import_all!(loss);
+import_all!(discount);
+import_all!(step_size);
+import_all!(target_network);
 import_all!(experiment);
+import_all!(episodic_adapter);
+import_all!(parallel);
 import_all!(algorithms);
@@ -0,0 +1,225 @@
+use crate::domains::{Observation, Transition};
+use rand::{rngs::ThreadRng, thread_rng, Rng};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// An interface for learned models of environment dynamics.
+pub trait Model<S, A> {
+    /// Incorporate an observed transition into the model.
+    fn record(&mut self, t: &Transition<S, A>);
+
+    /// Predict the reward and next observation resulting from taking action
+    /// `a` in state `s`, if the pair has been observed.
+    fn predict(&mut self, s: &S, a: &A) -> Option<(f64, Observation<S>)>;
+
+    /// Return all state-action pairs observed so far, in order of first
+    /// occurrence.
+    fn observed_pairs(&self) -> &[(S, A)];
+
+    /// Return all observed state-action pairs that have led to state `s`.
+    fn predecessors(&self, s: &S) -> Vec<(S, A)>;
+}
+
+/// Strategy used by a `TabularModel` to predict outcomes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModelKind {
+    /// Return the most recently observed outcome of each pair.
+    Deterministic,
+
+    /// Sample an outcome from the empirical distribution observed for each
+    /// pair.
+    Stochastic,
+}
+
+struct Outcomes<S> {
+    outcomes: Vec<(f64, Observation<S>, usize)>,
+    last: usize,
+    total: usize,
+}
+
+/// Tabular model over discrete state-action pairs.
+///
+/// Distinct outcomes, identified by their reward and next observation, are
+/// counted for each pair. Transitions that were truncated are recorded with a
+/// non-terminal next observation, since the underlying problem did not end.
+pub struct TabularModel<S, A = usize, R = ThreadRng> {
+    kind: ModelKind,
+
+    pairs: Vec<(S, A)>,
+    table: HashMap<(S, A), Outcomes<S>>,
+    predecessors: HashMap<S, Vec<(S, A)>>,
+
+    rng: R,
+}
+
+impl<S: Hash + Eq + Clone, A: Hash + Eq + Clone> TabularModel<S, A> {
+    pub fn new(kind: ModelKind) -> Self { TabularModel::with_rng(kind, thread_rng()) }
+
+    pub fn deterministic() -> Self { TabularModel::new(ModelKind::Deterministic) }
+
+    pub fn stochastic() -> Self { TabularModel::new(ModelKind::Stochastic) }
+}
+
+impl<S: Hash + Eq + Clone, A: Hash + Eq + Clone, R> TabularModel<S, A, R> {
+    pub fn with_rng(kind: ModelKind, rng: R) -> Self {
+        TabularModel {
+            kind,
+
+            pairs: vec![],
+            table: HashMap::new(),
+            predecessors: HashMap::new(),
+
+            rng,
+        }
+    }
+
+    /// Return the number of times the pair `(s, a)` has been observed.
+    pub fn count(&self, s: &S, a: &A) -> usize {
+        self.table.get(&(s.clone(), a.clone())).map_or(0, |o| o.total)
+    }
+
+    /// Number of distinct state-action pairs observed.
+    pub fn len(&self) -> usize { self.pairs.len() }
+
+    pub fn is_empty(&self) -> bool { self.pairs.is_empty() }
+}
+
+impl<S, A, R> Model<S, A> for TabularModel<S, A, R>
+where
+    S: Hash + Eq + Clone,
+    A: Hash + Eq + Clone,
+    R: Rng,
+{
+    fn record(&mut self, t: &Transition<S, A>) {
+        let key = (t.from.state().clone(), t.action.clone());
+        let to = match t.to {
+            Observation::Terminal(ref s) if t.truncated => Observation::Full(s.clone()),
+            ref to => to.clone(),
+        };
+
+        if !self.table.contains_key(&key) {
+            self.pairs.push(key.clone());
+        }
+
+        let predecessors = self.predecessors.entry(to.state().clone()).or_insert_with(|| vec![]);
+
+        if !predecessors.contains(&key) {
+            predecessors.push(key.clone());
+        }
+
+        let entry = self.table.entry(key).or_insert_with(|| Outcomes {
+            outcomes: vec![],
+            last: 0,
+            total: 0,
+        });
+
+        let position = entry.outcomes.iter().position(|&(r, ref o, _)| {
+            r == t.reward && o.is_terminal() == to.is_terminal() && o.state() == to.state()
+        });
+
+        entry.last = match position {
+            Some(i) => {
+                entry.outcomes[i].2 += 1;
+
+                i
+            },
+            None => {
+                entry.outcomes.push((t.reward, to, 1));
+
+                entry.outcomes.len() - 1
+            },
+        };
+        entry.total += 1;
+    }
+
+    fn predict(&mut self, s: &S, a: &A) -> Option<(f64, Observation<S>)> {
+        let entry = self.table.get(&(s.clone(), a.clone()))?;
+        let i = match self.kind {
+            ModelKind::Deterministic => entry.last,
+            ModelKind::Stochastic => {
+                let mut r = self.rng.gen_range(0, entry.total);
+
+                entry.outcomes.iter().position(|&(_, _, n)| {
+                    if r < n { true } else { r -= n; false }
+                }).unwrap()
+            },
+        };
+
+        let (reward, ref to, _) = entry.outcomes[i];
+
+        Some((reward, to.clone()))
+    }
+
+    fn observed_pairs(&self) -> &[(S, A)] { &self.pairs }
+
+    fn predecessors(&self, s: &S) -> Vec<(S, A)> {
+        self.predecessors.get(s).cloned().unwrap_or_else(|| vec![])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn transition(reward: f64, to: usize) -> Transition<usize, usize> {
+        Transition {
+            from: Observation::Full(0),
+            action: 1,
+            reward,
+            to: Observation::Full(to),
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let mut model = TabularModel::deterministic();
+
+        model.record(&transition(0.0, 1));
+        model.record(&transition(1.0, 2));
+
+        let (reward, to) = model.predict(&0, &1).unwrap();
+
+        assert_eq!(reward, 1.0);
+        assert_eq!(*to.state(), 2);
+
+        assert!(model.predict(&0, &0).is_none());
+        assert_eq!(model.observed_pairs(), &[(0, 1)]);
+        assert_eq!(model.count(&0, &1), 2);
+
+        assert_eq!(model.predecessors(&1), vec![(0, 1)]);
+        assert_eq!(model.predecessors(&2), vec![(0, 1)]);
+        assert!(model.predecessors(&0).is_empty());
+    }
+
+    #[test]
+    fn test_stochastic() {
+        let mut model = TabularModel::with_rng(ModelKind::Stochastic, StdRng::seed_from_u64(0));
+
+        for _ in 0..3 {
+            model.record(&transition(0.0, 1));
+        }
+        model.record(&transition(1.0, 2));
+
+        let n = 10000;
+        let n_first = (0..n).filter(|_| *model.predict(&0, &1).unwrap().1.state() == 1).count();
+
+        assert!((n_first as f64 / n as f64 - 0.75).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_truncated() {
+        let mut model = TabularModel::deterministic();
+
+        model.record(&Transition {
+            from: Observation::Full(0),
+            action: 0,
+            reward: 0.0,
+            to: Observation::Terminal(1),
+            truncated: true,
+        });
+
+        assert!(!model.predict(&0, &0).unwrap().1.is_terminal());
+    }
+}
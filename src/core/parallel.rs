@@ -0,0 +1,231 @@
+use crate::core::*;
+use crate::domains::Domain;
+use crate::geometry::Space;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::cell::RefCell;
+use std::f64;
+use std::sync::Arc;
+use std::thread;
+
+const DEFAULT_STEP_LIMIT: u64 = 1000;
+
+/// Summary statistics over the returns of a collection of episodes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Statistics {
+    pub n_episodes: usize,
+
+    pub mean: f64,
+    pub std: f64,
+
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Statistics {
+    pub fn from_episodes<'a, I>(episodes: I) -> Statistics
+    where I: IntoIterator<Item = &'a Episode> {
+        let mut stats = RunningStatistics::new();
+
+        for e in episodes {
+            stats.update(e.reward);
+        }
+
+        if stats.count() == 0 {
+            return Statistics {
+                n_episodes: 0,
+
+                mean: 0.0,
+                std: 0.0,
+
+                min: 0.0,
+                max: 0.0,
+            };
+        }
+
+        Statistics {
+            n_episodes: stats.count() as usize,
+
+            mean: stats.mean(),
+            std: stats.std(),
+
+            min: stats.min(),
+            max: stats.max(),
+        }
+    }
+}
+
+/// Results collected by a `ParallelExperiment`.
+#[derive(Debug)]
+pub struct ParallelResult {
+    /// The seed used by each worker.
+    pub seeds: Vec<u64>,
+
+    /// The episodes run by each worker, in order.
+    pub episodes: Vec<Vec<Episode>>,
+}
+
+impl ParallelResult {
+    /// Statistics for each worker individually.
+    pub fn worker_statistics(&self) -> Vec<Statistics> {
+        self.episodes.iter().map(|eps| Statistics::from_episodes(eps)).collect()
+    }
+
+    /// Statistics pooled over the episodes of all workers.
+    pub fn pooled_statistics(&self) -> Statistics {
+        Statistics::from_episodes(self.episodes.iter().flat_map(|eps| eps.iter()))
+    }
+}
+
+/// Utility for running independent training experiments across worker threads.
+///
+/// Each worker constructs its own agent and sequence of domains from the
+/// builders, which are passed the worker's seed and a seed for each episode,
+/// respectively. The seeds are derived from a single master seed, so that
+/// results are reproducible irrespective of thread scheduling.
+pub struct ParallelExperiment<C, D> {
+    agent_builder: Arc<Fn(u64) -> C + Send + Sync>,
+    domain_builder: Arc<Fn(u64) -> D + Send + Sync>,
+
+    n_workers: usize,
+    episodes_per_worker: usize,
+
+    seed: u64,
+    step_limit: u64,
+}
+
+impl<S: Space, A: Space, C, D> ParallelExperiment<C, D>
+where
+    C: OnlineLearner<S::Value, A::Value> + Controller<S::Value, A::Value> + 'static,
+    D: Domain<StateSpace = S, ActionSpace = A> + 'static,
+{
+    pub fn new<FC, FD>(
+        agent_builder: FC,
+        domain_builder: FD,
+        n_workers: usize,
+        episodes_per_worker: usize,
+    ) -> ParallelExperiment<C, D>
+    where
+        FC: Fn(u64) -> C + Send + Sync + 'static,
+        FD: Fn(u64) -> D + Send + Sync + 'static,
+    {
+        assert!(n_workers > 0, "ParallelExperiment requires at least one worker.");
+
+        ParallelExperiment {
+            agent_builder: Arc::new(agent_builder),
+            domain_builder: Arc::new(domain_builder),
+
+            n_workers,
+            episodes_per_worker,
+
+            seed: 0,
+            step_limit: DEFAULT_STEP_LIMIT,
+        }
+    }
+
+    /// Set the master seed from which all worker seeds are derived.
+    pub fn with_seed(mut self, seed: u64) -> ParallelExperiment<C, D> {
+        self.seed = seed;
+
+        self
+    }
+
+    /// Set the maximum number of steps per episode.
+    pub fn with_step_limit(mut self, step_limit: u64) -> ParallelExperiment<C, D> {
+        self.step_limit = step_limit;
+
+        self
+    }
+
+    /// Run all workers to completion and collect their episodes.
+    pub fn run(self) -> ParallelResult {
+        let mut master = StdRng::seed_from_u64(self.seed);
+        let seeds: Vec<u64> = (0..self.n_workers).map(|_| master.gen()).collect();
+
+        let handles: Vec<_> = seeds
+            .iter()
+            .map(|&seed| {
+                let agent_builder = self.agent_builder.clone();
+                let domain_builder = self.domain_builder.clone();
+
+                let n_episodes = self.episodes_per_worker;
+                let step_limit = self.step_limit;
+
+                thread::spawn(move || {
+                    let mut agent = agent_builder(seed);
+                    let rng = RefCell::new(StdRng::seed_from_u64(seed));
+
+                    let domain_factory = Box::new(move || domain_builder(rng.borrow_mut().gen()));
+                    let exp = SerialExperiment::new(&mut agent, domain_factory, step_limit);
+
+                    run(exp, n_episodes, None)
+                })
+            })
+            .collect();
+
+        ParallelResult {
+            seeds,
+            episodes: handles
+                .into_iter()
+                .map(|h| h.join().expect("Experiment worker panicked."))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mocking::MoveRight;
+    use crate::domains::GridWorld;
+
+    fn experiment(seed: u64) -> ParallelResult {
+        ParallelExperiment::new(
+            |_| MoveRight,
+            |seed| GridWorld::new(5, 5)
+                .with_goal((4, 0), 1.0)
+                .with_slip(0.3)
+                .with_seed(seed),
+            4,
+            10,
+        ).with_seed(seed)
+            .with_step_limit(100)
+            .run()
+    }
+
+    #[test]
+    fn test_statistics() {
+        let episodes = vec![
+            Episode { steps: 1, reward: 1.0, discounted_reward: None },
+            Episode { steps: 1, reward: 3.0, discounted_reward: None },
+        ];
+        let stats = Statistics::from_episodes(&episodes);
+
+        assert_eq!(stats.n_episodes, 2);
+        assert_eq!(stats.mean, 2.0);
+        assert_eq!(stats.std, 1.0);
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.max, 3.0);
+    }
+
+    #[test]
+    fn test_deterministic_aggregation() {
+        let r1 = experiment(0);
+        let r2 = experiment(0);
+
+        assert_eq!(r1.seeds, r2.seeds);
+        assert_eq!(r1.episodes.len(), 4);
+
+        for (e1, e2) in r1.episodes.iter().zip(r2.episodes.iter()) {
+            assert_eq!(e1.len(), 10);
+
+            for (a, b) in e1.iter().zip(e2.iter()) {
+                assert_eq!(a.steps, b.steps);
+                assert_eq!(a.reward, b.reward);
+            }
+        }
+
+        assert_eq!(r1.worker_statistics(), r2.worker_statistics());
+        assert_eq!(r1.pooled_statistics(), r2.pooled_statistics());
+        assert_eq!(r1.pooled_statistics().n_episodes, 40);
+    }
+}
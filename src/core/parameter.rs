@@ -30,6 +30,13 @@ pub enum Parameter {
         tau: f64,
         count: u32,
     },
+    PolynomialDecay {
+        start: f64,
+        end: f64,
+        power: f64,
+        steps: u32,
+        count: u32,
+    },
 }
 
 impl Parameter {
@@ -71,6 +78,23 @@ impl Parameter {
         }
     }
 
+    /// Linearly interpolate from `start` to `end` over `steps` steps.
+    pub fn linear(start: f64, end: f64, steps: u32) -> Parameter {
+        Parameter::polynomial_decay(start, end, 1.0, steps)
+    }
+
+    /// Interpolate from `start` to `end` over `steps` steps such that the
+    /// remaining distance decays as `(1 - t / steps)^power`.
+    pub fn polynomial_decay(start: f64, end: f64, power: f64, steps: u32) -> Parameter {
+        Parameter::PolynomialDecay {
+            start,
+            end,
+            power,
+            steps,
+            count: 0,
+        }
+    }
+
     pub fn value(&self) -> f64 {
         match *self {
             Parameter::Fixed(v) => v,
@@ -102,11 +126,40 @@ impl Parameter {
                 tau: t,
                 count: c,
             } => f64::max(i * t / (t + f64::from(c) - 1.0), f),
+
+            Parameter::PolynomialDecay {
+                start: s,
+                end: e,
+                power: p,
+                steps: n,
+                count: c,
+            } => if c >= n {
+                e
+            } else {
+                e + (s - e) * (1.0 - f64::from(c) / f64::from(n)).powf(p)
+            },
         }
     }
 
     pub fn to_fixed(self) -> Parameter { Parameter::Fixed(self.value()) }
 
+    /// Return the schedule as it would be after exactly `count` steps.
+    pub fn at(self, count: u32) -> Parameter {
+        match self {
+            Parameter::Fixed(_) => self,
+            Parameter::Exponential { init, floor, tau, .. } =>
+                Parameter::Exponential { init, floor, tau, count },
+            Parameter::Polynomial { init, floor, tau, .. } =>
+                Parameter::Polynomial { init, floor, tau, count },
+            Parameter::Boyan { init, floor, n0, .. } =>
+                Parameter::Boyan { init, floor, n0, count },
+            Parameter::GHC { init, floor, tau, .. } =>
+                Parameter::GHC { init, floor, tau, count },
+            Parameter::PolynomialDecay { start, end, power, steps, .. } =>
+                Parameter::PolynomialDecay { start, end, power, steps, count },
+        }
+    }
+
     pub fn step(self) -> Parameter {
         match self {
             Parameter::Fixed(_) => self,
@@ -154,6 +207,19 @@ impl Parameter {
                 tau: t,
                 count: c.saturating_add(1),
             },
+            Parameter::PolynomialDecay {
+                start: s,
+                end: e,
+                power: p,
+                steps: n,
+                count: c,
+            } => Parameter::PolynomialDecay {
+                start: s,
+                end: e,
+                power: p,
+                steps: n,
+                count: c.saturating_add(1),
+            },
         }
     }
 
@@ -204,6 +270,19 @@ impl Parameter {
                 tau: t,
                 count: c.saturating_sub(1),
             },
+            Parameter::PolynomialDecay {
+                start: s,
+                end: e,
+                power: p,
+                steps: n,
+                count: c,
+            } => Parameter::PolynomialDecay {
+                start: s,
+                end: e,
+                power: p,
+                steps: n,
+                count: c.saturating_sub(1),
+            },
         }
     }
 }
@@ -336,6 +415,57 @@ mod tests {
         assert!((p.value() - 0.1).abs() < 1e-7);
     }
 
+    #[test]
+    fn test_linear() {
+        let mut p = Parameter::linear(1.0, 0.1, 10);
+
+        assert!((p.value() - 1.0).abs() < 1e-7);
+
+        for i in 1..10 {
+            p = p.step();
+            assert!((p.value() - (1.0 - 0.09 * i as f64)).abs() < 1e-7);
+        }
+
+        p = p.step();
+        assert_eq!(p.value(), 0.1);
+
+        for _ in 0..1000 {
+            p = p.step();
+            assert_eq!(p.value(), 0.1);
+        }
+
+        let mut p = Parameter::linear(1.0, 0.1, 10);
+
+        p = p.step().step().back();
+        assert!((p.value() - 0.91).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_polynomial_decay() {
+        let mut p = Parameter::polynomial_decay(1.0, 0.0, 2.0, 4);
+
+        assert!((p.value() - 1.0).abs() < 1e-7);
+
+        p = p.step();
+        assert!((p.value() - 0.5625).abs() < 1e-7);
+
+        p = p.step();
+        assert!((p.value() - 0.25).abs() < 1e-7);
+
+        p = p.step().step();
+        assert_eq!(p.value(), 0.0);
+
+        let mut p1 = Parameter::polynomial_decay(0.8, 0.05, 1.0, 50);
+        let mut p2 = Parameter::linear(0.8, 0.05, 50);
+
+        for _ in 0..100 {
+            assert_eq!(p1.value(), p2.value());
+
+            p1 = p1.step();
+            p2 = p2.step();
+        }
+    }
+
     #[test]
     fn test_to_fixed() {
         let mut p = Parameter::exponential(1.0, 0.5, 0.9);
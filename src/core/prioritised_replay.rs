@@ -0,0 +1,285 @@
+use crate::domains::Transition;
+use rand::Rng;
+
+const PRIORITY_EPSILON: f64 = 1e-6;
+
+/// Binary tree in which each internal node stores the sum of its children.
+struct SumTree {
+    capacity: usize,
+    nodes: Vec<f64>,
+}
+
+impl SumTree {
+    fn new(capacity: usize) -> Self {
+        SumTree {
+            capacity,
+            nodes: vec![0.0; 2 * capacity],
+        }
+    }
+
+    fn total(&self) -> f64 { self.nodes[1] }
+
+    fn get(&self, index: usize) -> f64 { self.nodes[index + self.capacity] }
+
+    fn set(&mut self, index: usize, value: f64) {
+        let mut node = index + self.capacity;
+
+        self.nodes[node] = value;
+
+        while node > 1 {
+            node /= 2;
+            self.nodes[node] = self.nodes[2 * node] + self.nodes[2 * node + 1];
+        }
+    }
+
+    /// Find the leaf at which the cumulative sum first exceeds `mass`.
+    fn find(&self, mut mass: f64) -> usize {
+        let mut node = 1;
+
+        while node < self.capacity {
+            let left = 2 * node;
+
+            if mass < self.nodes[left] || self.nodes[left + 1] <= 0.0 {
+                node = left;
+            } else {
+                mass -= self.nodes[left];
+                node = left + 1;
+            }
+        }
+
+        node - self.capacity
+    }
+}
+
+/// Fixed capacity experience replay buffer with proportional prioritisation.
+///
+/// Each transition `i` is sampled with probability `p_i^alpha / sum_k
+/// p_k^alpha`, where `p_i` is its priority, using a sum-tree for `O(log n)`
+/// sampling and updates. The bias introduced by non-uniform sampling is
+/// corrected with importance sampling weights `(N * P(i))^-beta`, normalised by
+/// their maximum over the batch.
+///
+/// # References
+/// - Schaul, T., Quan, J., Antonoglou, I., & Silver, D. (2016). Prioritized
+/// experience replay. In Proceedings of the International Conference on
+/// Learning Representations.
+pub struct PrioritizedReplayBuffer<S, A> {
+    capacity: usize,
+    alpha: f64,
+
+    head: usize,
+    tree: SumTree,
+    transitions: Vec<Transition<S, A>>,
+}
+
+/// A batch sampled from a `PrioritizedReplayBuffer`.
+pub struct PrioritizedBatch<S, A> {
+    pub indices: Vec<usize>,
+    pub transitions: Vec<Transition<S, A>>,
+    pub weights: Vec<f64>,
+}
+
+impl<S, A> PrioritizedReplayBuffer<S, A> {
+    pub fn new(capacity: usize, alpha: f64) -> Self {
+        assert!(capacity > 0, "Replay buffer capacity must be positive.");
+        assert!(alpha >= 0.0, "Prioritisation exponent must be non-negative.");
+
+        PrioritizedReplayBuffer {
+            capacity,
+            alpha,
+
+            head: 0,
+            // The sum-tree is most easily implemented with a power of two leaves:
+            tree: SumTree::new(capacity.next_power_of_two()),
+            transitions: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize { self.capacity }
+
+    pub fn len(&self) -> usize { self.transitions.len() }
+
+    pub fn is_empty(&self) -> bool { self.transitions.is_empty() }
+
+    pub fn is_full(&self) -> bool { self.len() == self.capacity() }
+
+    fn scaled_priority(&self, priority: f64) -> f64 {
+        (priority.abs() + PRIORITY_EPSILON).powf(self.alpha)
+    }
+
+    /// Store a transition with a given priority, evicting the oldest if the
+    /// buffer is full.
+    pub fn push(&mut self, transition: Transition<S, A>, priority: f64) {
+        if self.is_full() {
+            self.transitions[self.head] = transition;
+        } else {
+            self.transitions.push(transition);
+        }
+
+        let p = self.scaled_priority(priority);
+
+        self.tree.set(self.head, p);
+        self.head = (self.head + 1) % self.capacity();
+    }
+
+    /// Replace the priorities of the transitions at `indices` using new TD
+    /// errors.
+    pub fn update_priorities(&mut self, indices: &[usize], errors: &[f64]) {
+        for (&i, &e) in indices.iter().zip(errors.iter()) {
+            let p = self.scaled_priority(e);
+
+            self.tree.set(i, p);
+        }
+    }
+
+    /// Return the probability of sampling the transition at `index`.
+    pub fn probability(&self, index: usize) -> f64 { self.tree.get(index) / self.tree.total() }
+
+    fn sample_indices(&self, batch_size: usize, rng: &mut impl Rng) -> Vec<usize> {
+        let segment = self.tree.total() / batch_size as f64;
+
+        (0..batch_size)
+            .map(|i| {
+                let mass = segment * (i as f64 + rng.gen::<f64>());
+
+                self.tree.find(mass).min(self.len() - 1)
+            })
+            .collect()
+    }
+
+    fn weights(&self, indices: &[usize], beta: f64) -> Vec<f64> {
+        let n = self.len() as f64;
+        let weights: Vec<f64> = indices
+            .iter()
+            .map(|&i| (n * self.probability(i)).powf(-beta))
+            .collect();
+        let max_weight = weights.iter().cloned().fold(0.0, f64::max);
+
+        weights.into_iter().map(|w| w / max_weight).collect()
+    }
+}
+
+impl<S: Clone, A: Clone> PrioritizedReplayBuffer<S, A> {
+    /// Sample a batch of transitions, with replacement, in proportion to their
+    /// priorities.
+    pub fn sample(
+        &self,
+        batch_size: usize,
+        beta: f64,
+        rng: &mut impl Rng,
+    ) -> PrioritizedBatch<S, A>
+    {
+        assert!(!self.is_empty(), "Cannot sample from an empty replay buffer.");
+
+        let indices = self.sample_indices(batch_size, rng);
+        let weights = self.weights(&indices, beta);
+        let transitions = indices.iter().map(|&i| self.transitions[i].clone()).collect();
+
+        PrioritizedBatch {
+            indices,
+            transitions,
+            weights,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::Observation;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn transition(i: usize) -> Transition<usize, usize> {
+        Transition {
+            from: Observation::Full(i),
+            action: 0,
+            reward: i as f64,
+            to: Observation::Full(i + 1),
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn test_sum_tree() {
+        let mut tree = SumTree::new(4);
+
+        for (i, &v) in [1.0, 2.0, 3.0, 4.0].iter().enumerate() {
+            tree.set(i, v);
+        }
+
+        assert_eq!(tree.total(), 10.0);
+        assert_eq!(tree.find(0.5), 0);
+        assert_eq!(tree.find(1.5), 1);
+        assert_eq!(tree.find(3.5), 2);
+        assert_eq!(tree.find(9.5), 3);
+
+        tree.set(3, 0.0);
+        assert_eq!(tree.total(), 6.0);
+    }
+
+    #[test]
+    fn test_eviction() {
+        let mut buffer = PrioritizedReplayBuffer::new(2, 1.0);
+
+        buffer.push(transition(0), 1.0);
+        buffer.push(transition(1), 1.0);
+        buffer.push(transition(2), 1.0);
+
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(*buffer.transitions[0].from.state(), 2);
+        assert_eq!(*buffer.transitions[1].from.state(), 1);
+    }
+
+    #[test]
+    fn test_prioritisation() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut buffer = PrioritizedReplayBuffer::new(2, 1.0);
+
+        buffer.push(transition(0), 1.0);
+        buffer.push(transition(1), 9.0);
+
+        let n_high = (0..10000)
+            .filter(|_| buffer.sample(1, 1.0, &mut rng).indices[0] == 1)
+            .count();
+
+        assert!((n_high as f64 / 10000.0 - 0.9).abs() < 0.02);
+
+        buffer.update_priorities(&[0, 1], &[9.0, 1.0]);
+
+        let n_high = (0..10000)
+            .filter(|_| buffer.sample(1, 1.0, &mut rng).indices[0] == 1)
+            .count();
+
+        assert!((n_high as f64 / 10000.0 - 0.1).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_importance_weights() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut buffer = PrioritizedReplayBuffer::new(4, 1.0);
+
+        for i in 0..4 {
+            buffer.push(transition(i), (i + 1) as f64);
+        }
+
+        let (mut biased, mut weighted, mut total_weight) = (0.0, 0.0, 0.0);
+
+        for _ in 0..20000 {
+            let batch = buffer.sample(1, 1.0, &mut rng);
+            let w = (4.0 * buffer.probability(batch.indices[0])).recip();
+
+            biased += batch.transitions[0].reward;
+            weighted += w * batch.transitions[0].reward;
+            total_weight += w;
+        }
+
+        // Prioritised sampling favours larger rewards, E[r] = 2.0, whereas the
+        // importance weighted estimate recovers the uniform mean of 1.5:
+        assert!((biased / 20000.0 - 2.0).abs() < 0.05);
+        assert!((weighted / total_weight - 1.5).abs() < 0.05);
+
+        let batch = buffer.sample(4, 1.0, &mut rng);
+
+        assert!(batch.weights.iter().all(|&w| w > 0.0 && w <= 1.0));
+    }
+}
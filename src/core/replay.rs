@@ -0,0 +1,134 @@
+use crate::domains::Transition;
+use rand::{seq::index, Rng};
+
+/// Fixed capacity experience replay buffer.
+///
+/// Transitions are stored in a ring buffer such that, once full, each new
+/// transition evicts the oldest. Batches are sampled uniformly without
+/// replacement and may be passed directly to `BatchLearner::handle_batch`.
+pub struct ReplayBuffer<S, A> {
+    capacity: usize,
+
+    head: usize,
+    transitions: Vec<Transition<S, A>>,
+}
+
+impl<S, A> ReplayBuffer<S, A> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Replay buffer capacity must be positive.");
+
+        ReplayBuffer {
+            capacity,
+
+            head: 0,
+            transitions: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize { self.capacity }
+
+    pub fn len(&self) -> usize { self.transitions.len() }
+
+    pub fn is_empty(&self) -> bool { self.transitions.is_empty() }
+
+    pub fn is_full(&self) -> bool { self.len() == self.capacity }
+
+    /// Store a transition, evicting the oldest if the buffer is full.
+    pub fn push(&mut self, transition: Transition<S, A>) {
+        if self.is_full() {
+            self.transitions[self.head] = transition;
+        } else {
+            self.transitions.push(transition);
+        }
+
+        self.head = (self.head + 1) % self.capacity;
+    }
+
+    /// Iterate over the stored transitions from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = &Transition<S, A>> {
+        let split = if self.is_full() { self.head } else { 0 };
+
+        self.transitions[split..].iter().chain(self.transitions[..split].iter())
+    }
+
+    /// Sample references to (at most) `batch_size` distinct transitions.
+    pub fn sample_refs(&self, batch_size: usize, rng: &mut impl Rng) -> Vec<&Transition<S, A>> {
+        let n = batch_size.min(self.len());
+
+        index::sample(rng, self.len(), n)
+            .into_iter()
+            .map(|i| &self.transitions[i])
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.transitions.clear();
+    }
+}
+
+impl<S: Clone, A: Clone> ReplayBuffer<S, A> {
+    /// Sample a batch of (at most) `batch_size` distinct transitions.
+    pub fn sample(&self, batch_size: usize, rng: &mut impl Rng) -> Vec<Transition<S, A>> {
+        self.sample_refs(batch_size, rng).into_iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::Observation;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashSet;
+
+    fn transition(i: usize) -> Transition<usize, usize> {
+        Transition {
+            from: Observation::Full(i),
+            action: 0,
+            reward: i as f64,
+            to: Observation::Full(i + 1),
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn test_eviction() {
+        let mut buffer = ReplayBuffer::new(3);
+
+        assert!(buffer.is_empty());
+
+        for i in 0..3 {
+            buffer.push(transition(i));
+        }
+
+        assert!(buffer.is_full());
+        assert_eq!(buffer.iter().map(|t| *t.from.state()).collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        buffer.push(transition(3));
+        buffer.push(transition(4));
+
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.iter().map(|t| *t.from.state()).collect::<Vec<_>>(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_sampling_without_replacement() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut buffer = ReplayBuffer::new(50);
+
+        for i in 0..100 {
+            buffer.push(transition(i));
+        }
+
+        for _ in 0..100 {
+            let batch = buffer.sample(20, &mut rng);
+            let states: HashSet<usize> = batch.iter().map(|t| *t.from.state()).collect();
+
+            assert_eq!(batch.len(), 20);
+            assert_eq!(states.len(), 20);
+            assert!(states.iter().all(|&s| s >= 50));
+        }
+
+        assert_eq!(buffer.sample(100, &mut rng).len(), 50);
+    }
+}
@@ -0,0 +1,58 @@
+/// Compute the discounted return from every time step of a reward sequence.
+///
+/// The element at index `t` is given by `sum_k gamma^k * rewards[t + k]`.
+pub fn discounted_returns(rewards: &[f64], gamma: f64) -> Vec<f64> {
+    let mut ret = 0.0;
+    let mut returns: Vec<f64> = rewards
+        .iter()
+        .rev()
+        .map(|r| {
+            ret = r + gamma * ret;
+
+            ret
+        })
+        .collect();
+
+    returns.reverse();
+
+    returns
+}
+
+/// Compute the n-step return of a reward sequence, bootstrapped from the
+/// value estimate of the state reached after the final reward.
+///
+/// The result is `sum_k gamma^k * rewards[k] + gamma^n * bootstrap`, where
+/// `n = rewards.len()`.
+pub fn n_step_return(rewards: &[f64], bootstrap: f64, gamma: f64) -> f64 {
+    rewards.iter().rev().fold(bootstrap, |ret, r| r + gamma * ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REWARDS: [f64; 4] = [1.0, 0.0, -2.0, 4.0];
+
+    #[test]
+    fn test_discounted_returns() {
+        let returns = discounted_returns(&REWARDS, 0.5);
+        let expected = [1.0 + 0.25 * -2.0 + 0.125 * 4.0, -1.0 + 0.25 * 4.0, 0.0, 4.0];
+
+        assert_eq!(returns.len(), 4);
+
+        for (r, e) in returns.iter().zip(expected.iter()) {
+            assert!((r - e).abs() < 1e-10);
+        }
+
+        assert_eq!(discounted_returns(&REWARDS, 1.0), vec![3.0, 2.0, 2.0, 4.0]);
+        assert!(discounted_returns(&[], 0.9).is_empty());
+    }
+
+    #[test]
+    fn test_n_step_return() {
+        assert!((n_step_return(&REWARDS, 0.0, 0.5) - 1.0).abs() < 1e-10);
+        assert!((n_step_return(&REWARDS, 10.0, 0.5) - (1.0 + 0.0625 * 10.0)).abs() < 1e-10);
+        assert!((n_step_return(&REWARDS[..2], 8.0, 0.5) - 3.0).abs() < 1e-10);
+        assert_eq!(n_step_return(&[], 5.0, 0.9), 5.0);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::core::RunningStatistics;
+
+/// Online reward normaliser based on running moment estimates.
+///
+/// The mean and (population) variance of all rewards passed to `update` are
+/// tracked by a `RunningStatistics` accumulator, and `normalize` rescales a
+/// reward to zero mean and unit variance under these estimates. If a clipping
+/// bound is set, normalised rewards are additionally restricted to the
+/// interval `[-clip, clip]`.
+#[derive(Clone, Copy, Debug)]
+pub struct RewardNormalizer {
+    pub clip: Option<f64>,
+
+    stats: RunningStatistics,
+}
+
+impl RewardNormalizer {
+    pub fn new() -> Self {
+        RewardNormalizer {
+            clip: None,
+
+            stats: RunningStatistics::new(),
+        }
+    }
+
+    pub fn with_clip(mut self, clip: f64) -> Self {
+        self.clip = Some(clip.abs());
+
+        self
+    }
+
+    /// Incorporate a new reward into the running statistics.
+    pub fn update(&mut self, r: f64) { self.stats.update(r); }
+
+    /// Number of rewards observed so far.
+    pub fn count(&self) -> u64 { self.stats.count() }
+
+    /// Running estimate of the mean reward.
+    pub fn mean(&self) -> f64 { self.stats.mean() }
+
+    /// Running estimate of the (population) variance of the rewards.
+    pub fn variance(&self) -> f64 { self.stats.variance() }
+
+    /// Running estimate of the standard deviation of the rewards.
+    pub fn std(&self) -> f64 { self.stats.std() }
+
+    /// Normalise a reward using the current statistics.
+    ///
+    /// While the variance estimate is zero, rewards are only centred.
+    pub fn normalize(&self, r: f64) -> f64 {
+        let std = self.std();
+        let mean = self.mean();
+        let z = if std > 0.0 { (r - mean) / std } else { r - mean };
+
+        match self.clip {
+            Some(c) => clip!(-c, z, c),
+            None => z,
+        }
+    }
+}
+
+impl Default for RewardNormalizer {
+    fn default() -> Self { RewardNormalizer::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RewardNormalizer;
+
+    const REWARDS: [f64; 8] = [-1.0, 3.5, 0.0, 10.0, -4.25, 2.0, 2.0, 7.5];
+
+    #[test]
+    fn test_running_statistics() {
+        let mut rn = RewardNormalizer::new();
+
+        for (i, &r) in REWARDS.iter().enumerate() {
+            rn.update(r);
+
+            let batch = &REWARDS[..(i + 1)];
+            let n = batch.len() as f64;
+            let mean = batch.iter().sum::<f64>() / n;
+            let var = batch.iter().map(|r| (r - mean) * (r - mean)).sum::<f64>() / n;
+
+            assert_eq!(rn.count(), i as u64 + 1);
+            assert!((rn.mean() - mean).abs() < 1e-10);
+            assert!((rn.variance() - var).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut rn = RewardNormalizer::new();
+
+        assert_eq!(rn.normalize(2.0), 2.0);
+
+        for &r in [1.0, 3.0].iter() {
+            rn.update(r);
+        }
+
+        assert_eq!(rn.normalize(2.0), 0.0);
+        assert_eq!(rn.normalize(3.0), 1.0);
+        assert_eq!(rn.normalize(-2.0), -4.0);
+
+        let rn = rn.with_clip(2.5);
+
+        assert_eq!(rn.normalize(-2.0), -2.5);
+        assert_eq!(rn.normalize(10.0), 2.5);
+        assert_eq!(rn.normalize(1.0), -1.0);
+    }
+}
@@ -0,0 +1,116 @@
+use slog::{Record, Result as LogResult, Serializer, KV};
+
+/// Running summary statistics of a sequence of values, such as episode
+/// returns or rewards.
+///
+/// The mean and (population) variance are tracked incrementally using
+/// Welford's algorithm, alongside the extreme values observed.
+///
+/// # References
+/// - Welford, B. P. (1962). Note on a method for calculating corrected sums of
+/// squares and products. Technometrics, 4(3), 419-420.
+#[derive(Clone, Copy, Debug)]
+pub struct RunningStatistics {
+    count: u64,
+    mean: f64,
+    m2: f64,
+
+    min: f64,
+    max: f64,
+}
+
+impl RunningStatistics {
+    pub fn new() -> Self {
+        RunningStatistics {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+
+            min: ::std::f64::INFINITY,
+            max: ::std::f64::NEG_INFINITY,
+        }
+    }
+
+    /// Incorporate a new value into the summary.
+    pub fn update(&mut self, x: f64) {
+        self.count += 1;
+
+        let delta = x - self.mean;
+
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+    }
+
+    /// Number of values observed so far.
+    pub fn count(&self) -> u64 { self.count }
+
+    /// Mean of the values observed so far.
+    pub fn mean(&self) -> f64 { self.mean }
+
+    /// (Population) variance of the values observed so far.
+    pub fn variance(&self) -> f64 {
+        if self.count == 0 { 0.0 } else { self.m2 / self.count as f64 }
+    }
+
+    /// Standard deviation of the values observed so far.
+    pub fn std(&self) -> f64 { self.variance().sqrt() }
+
+    /// Smallest value observed so far, or `+inf` if none.
+    pub fn min(&self) -> f64 { self.min }
+
+    /// Largest value observed so far, or `-inf` if none.
+    pub fn max(&self) -> f64 { self.max }
+}
+
+impl Default for RunningStatistics {
+    fn default() -> RunningStatistics { RunningStatistics::new() }
+}
+
+impl KV for RunningStatistics {
+    fn serialize(&self, _: &Record, serializer: &mut Serializer) -> LogResult {
+        serializer.emit_u64("count", self.count)?;
+        serializer.emit_f64("mean", self.mean)?;
+        serializer.emit_f64("std", self.std())?;
+        serializer.emit_f64("min", self.min)?;
+        serializer.emit_f64("max", self.max)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RunningStatistics;
+
+    #[test]
+    fn test_empty() {
+        let stats = RunningStatistics::new();
+
+        assert_eq!(stats.count(), 0);
+        assert_eq!(stats.mean(), 0.0);
+        assert_eq!(stats.variance(), 0.0);
+    }
+
+    #[test]
+    fn test_matches_batch() {
+        let xs = [3.0, -1.5, 10.0, 0.0, 2.5, -7.0, 4.0];
+        let mut stats = RunningStatistics::new();
+
+        for &x in xs.iter() {
+            stats.update(x);
+        }
+
+        let n = xs.len() as f64;
+        let mean = xs.iter().sum::<f64>() / n;
+        let variance = xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+
+        assert_eq!(stats.count(), 7);
+        assert!((stats.mean() - mean).abs() < 1e-10);
+        assert!((stats.variance() - variance).abs() < 1e-10);
+        assert_eq!(stats.min(), -7.0);
+        assert_eq!(stats.max(), 10.0);
+    }
+}
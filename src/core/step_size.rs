@@ -0,0 +1,150 @@
+use crate::core::Parameter;
+use crate::fa::{Projection, VFunction};
+use crate::geometry::{norms::l1, Vector};
+
+/// Rule for deriving the step size applied to each feature in a linear
+/// update.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StepSize {
+    /// A single, fixed step size shared by all features.
+    Constant(f64),
+
+    /// AdaGrad: the step size of each feature is `alpha / sqrt(G + eps)`, where
+    /// `G` is the sum of its squared gradients.
+    AdaGrad(f64),
+
+    /// RMSProp: as AdaGrad, but `G` is an exponential moving average of the
+    /// squared gradients with decay rate `rho`.
+    RMSProp { alpha: f64, rho: f64 },
+}
+
+/// Per-feature step size state for a `StepSize` rule.
+#[derive(Clone, Debug)]
+pub struct AdaptiveStepSize {
+    pub rule: StepSize,
+    pub epsilon: f64,
+
+    accumulator: Option<Vector<f64>>,
+}
+
+impl AdaptiveStepSize {
+    pub fn new(rule: StepSize) -> Self {
+        AdaptiveStepSize {
+            rule,
+            epsilon: 1e-8,
+
+            accumulator: None,
+        }
+    }
+
+    /// Configure a learner for the rule `step_size`, returning the state of
+    /// the rule if it is adaptive.
+    ///
+    /// Adaptive rules require the value function to implement
+    /// `VFunction::features`, as `ScalarLFA` does, and replace `alpha`
+    /// entirely; `StepSize::Constant` simply resets `alpha`.
+    pub fn configure(step_size: StepSize, alpha: &mut Parameter) -> Option<AdaptiveStepSize> {
+        match step_size {
+            StepSize::Constant(a) => {
+                *alpha = a.into();
+
+                None
+            },
+            rule => Some(AdaptiveStepSize::new(rule)),
+        }
+    }
+
+    /// Return the current step size of each feature, if any gradients have
+    /// been observed.
+    pub fn step_sizes(&self) -> Option<Vector<f64>> {
+        let alpha = match self.rule {
+            StepSize::Constant(alpha) | StepSize::AdaGrad(alpha) => alpha,
+            StepSize::RMSProp { alpha, .. } => alpha,
+        };
+
+        self.accumulator.as_ref().map(|acc| match self.rule {
+            StepSize::Constant(_) => Vector::from_elem(acc.len(), alpha),
+            _ => acc.mapv(|g| alpha / (g + self.epsilon).sqrt()),
+        })
+    }
+
+    /// Accumulate the gradient `error * phi` and return the resulting weight
+    /// increment for each feature.
+    pub fn update(&mut self, phi: &Vector<f64>, error: f64) -> Vector<f64> {
+        let grad = phi * error;
+        let acc = self.accumulator.get_or_insert_with(|| Vector::zeros(phi.len()));
+
+        match self.rule {
+            StepSize::Constant(_) => {},
+            StepSize::AdaGrad(_) => acc.zip_mut_with(&grad, |g, &d| *g += d * d),
+            StepSize::RMSProp { rho, .. } => acc.zip_mut_with(&grad, |g, &d| {
+                *g = rho * *g + (1.0 - rho) * d * d
+            }),
+        }
+
+        grad * &self.step_sizes().unwrap()
+    }
+
+    /// Accumulate the gradient `error * phi` and apply the resulting
+    /// increments to the weights of `v_func`.
+    pub fn apply<S: ?Sized, V>(&mut self, v_func: &mut V, phi: &Vector<f64>, error: f64)
+    where V: VFunction<S> {
+        let delta = self.update(phi, error);
+
+        // Dense updates are normalised by their L1 norm, which is undone here so
+        // that each weight moves by exactly its own increment:
+        let norm = l1(delta.as_slice().unwrap());
+
+        if norm > 0.0 {
+            v_func.update_phi(&Projection::Dense(delta), norm);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant() {
+        let mut step = AdaptiveStepSize::new(StepSize::Constant(0.1));
+
+        let delta = step.update(&Vector::from_vec(vec![1.0, 0.0, 2.0]), 2.0);
+
+        assert!(delta.all_close(&Vector::from_vec(vec![0.2, 0.0, 0.4]), 1e-10));
+    }
+
+    #[test]
+    fn test_adagrad_rare_features() {
+        let mut step = AdaptiveStepSize::new(StepSize::AdaGrad(0.1));
+
+        // The first feature is always active, the second only once in ten:
+        for i in 0..100 {
+            let phi = if i % 10 == 0 { vec![1.0, 1.0] } else { vec![1.0, 0.0] };
+
+            step.update(&Vector::from_vec(phi), 1.0);
+        }
+
+        let steps = step.step_sizes().unwrap();
+
+        // Accumulators are 100 and 10 respectively, so the rarely activated
+        // feature's step is sqrt(10) times larger:
+        assert!((steps[0] - 0.01).abs() < 1e-6);
+        assert!((steps[1] / steps[0] - 10f64.sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rmsprop() {
+        let mut step = AdaptiveStepSize::new(StepSize::RMSProp { alpha: 0.1, rho: 0.5 });
+
+        step.update(&Vector::from_vec(vec![2.0, 0.0]), 1.0);
+        step.update(&Vector::from_vec(vec![2.0, 0.0]), 1.0);
+
+        // G = 0.5 * (0.5 * 4) + 0.5 * 4 = 3 for the active feature, while the
+        // inactive feature's step is bounded only by epsilon:
+        let steps = step.step_sizes().unwrap();
+
+        assert!((steps[0] - 0.1 / 3f64.sqrt()).abs() < 1e-6);
+        assert!((steps[1] - 0.1 / step.epsilon.sqrt()).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,260 @@
+use crate::core::Shared;
+use crate::fa::{Parameterised, Projection, WeightedQFunction};
+use crate::geometry::{Matrix, Vector};
+
+/// Slowly-updated copy of a linear function approximator's weights.
+///
+/// The online approximator is shared with, and trained by, the learning
+/// algorithm, while bootstrapped targets are computed with the frozen target
+/// weights. These are refreshed either by copying the online weights outright
+/// (`sync_hard`) or by Polyak averaging (`sync_soft`).
+///
+/// # References
+/// - Mnih, V., et al. (2015). Human-level control through deep reinforcement
+/// learning. Nature, 518(7540), 529-533.
+/// - Lillicrap, T. P., et al. (2015). Continuous control with deep
+/// reinforcement learning. arXiv preprint arXiv:1509.02971.
+pub struct TargetNetwork<F> {
+    pub online: Shared<F>,
+
+    target: Matrix<f64>,
+}
+
+impl<F: Parameterised> TargetNetwork<F> {
+    pub fn new(online: Shared<F>) -> Self {
+        let target = online.weights();
+
+        TargetNetwork { online, target }
+    }
+
+    /// Return the target weights.
+    pub fn weights(&self) -> &Matrix<f64> { &self.target }
+
+    /// Copy the online weights into the target.
+    pub fn sync_hard(&mut self) { self.target = self.online.weights(); }
+
+    /// Move the target weights towards the online weights:
+    /// `target <- tau * online + (1 - tau) * target`.
+    pub fn sync_soft(&mut self, tau: f64) {
+        let online = self.online.weights();
+
+        self.target *= 1.0 - tau;
+        self.target.scaled_add(tau, &online);
+    }
+
+    /// Evaluate the target weights for a given feature projection.
+    pub fn evaluate_phi(&self, phi: &Projection) -> Vector<f64> {
+        phi.clone().expanded(self.target.rows()).dot(&self.target)
+    }
+}
+
+/// A `TargetNetwork` that is hard-synchronised with the online weights every
+/// `period` learning steps.
+pub struct PeriodicTarget<F> {
+    pub network: TargetNetwork<F>,
+    pub period: usize,
+
+    steps: usize,
+}
+
+impl<F: Parameterised> PeriodicTarget<F> {
+    pub fn new(online: Shared<F>, period: usize) -> Self {
+        assert!(period > 0, "Target synchronisation interval must be positive.");
+
+        PeriodicTarget {
+            network: TargetNetwork::new(online),
+            period,
+
+            steps: 0,
+        }
+    }
+}
+
+/// Source of the action values from which a controller bootstraps its TD
+/// targets.
+///
+/// The unit type bootstraps from the online action-value function, whereas a
+/// `PeriodicTarget` evaluates it with the frozen target weights; the latter
+/// requires a `WeightedQFunction`.
+pub trait BootstrapTarget<S: ?Sized, Q: ?Sized> {
+    /// Advance by one learning step, synchronising the target if it is due.
+    fn step(&mut self);
+
+    /// Evaluate `q_func` at `input` with the target weights, or return `None`
+    /// if the online weights are to be used.
+    fn evaluate(&self, q_func: &Q, input: &S) -> Option<Vector<f64>>;
+}
+
+impl<S: ?Sized, Q: ?Sized> BootstrapTarget<S, Q> for () {
+    fn step(&mut self) {}
+
+    fn evaluate(&self, _: &Q, _: &S) -> Option<Vector<f64>> { None }
+}
+
+impl<S: ?Sized, Q: WeightedQFunction<S>> BootstrapTarget<S, Q> for PeriodicTarget<Q> {
+    fn step(&mut self) {
+        self.steps += 1;
+
+        if self.steps % self.period == 0 {
+            self.network.sync_hard();
+        }
+    }
+
+    fn evaluate(&self, q_func: &Q, input: &S) -> Option<Vector<f64>> {
+        Some(q_func.evaluate_with_weights(input, self.network.weights()))
+    }
+}
+
+/// Polyak (exponential moving) average of a function approximator's weights.
+///
+/// Each call to `update` applies `average <- tau * online + (1 - tau) *
+/// average`, so `tau = 0` freezes the initial weights and `tau = 1` tracks the
+/// online weights exactly.
+pub struct PolyakAverage {
+    pub tau: f64,
+
+    weights: Matrix<f64>,
+    source: Box<Fn() -> Matrix<f64>>,
+}
+
+impl PolyakAverage {
+    pub fn new<F: Parameterised + 'static>(online: Shared<F>, tau: f64) -> Self {
+        PolyakAverage {
+            tau,
+
+            weights: online.weights(),
+            source: Box::new(move || online.weights()),
+        }
+    }
+
+    /// Return the averaged weights.
+    pub fn weights(&self) -> &Matrix<f64> { &self.weights }
+
+    /// Move the averaged weights towards the current online weights.
+    pub fn update(&mut self) {
+        let online = (self.source)();
+
+        self.weights *= 1.0 - self.tau;
+        self.weights.scaled_add(self.tau, &online);
+    }
+}
+
+/// Evaluation-time averaging of a controller's action-value weights.
+///
+/// The unit type performs no averaging, whereas a `PolyakAverage` evaluates
+/// the action-value function with its averaged weights; the latter requires a
+/// `WeightedQFunction`.
+pub trait Averaging<S: ?Sized, Q: ?Sized> {
+    /// Move the averaged weights towards the online weights.
+    fn update(&mut self);
+
+    /// Evaluate `q_func` at `input` with the averaged weights, or return `None`
+    /// if no averaging is performed.
+    fn evaluate(&self, q_func: &Q, input: &S) -> Option<Vector<f64>>;
+}
+
+impl<S: ?Sized, Q: ?Sized> Averaging<S, Q> for () {
+    fn update(&mut self) {}
+
+    fn evaluate(&self, _: &Q, _: &S) -> Option<Vector<f64>> { None }
+}
+
+impl<S: ?Sized, Q: WeightedQFunction<S>> Averaging<S, Q> for PolyakAverage {
+    fn update(&mut self) { PolyakAverage::update(self) }
+
+    fn evaluate(&self, q_func: &Q, input: &S) -> Option<Vector<f64>> {
+        Some(q_func.evaluate_with_weights(input, &self.weights))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::make_shared;
+    use crate::fa::{mocking::OneHot, LFA, VectorLFA};
+
+    fn network() -> TargetNetwork<VectorLFA<OneHot>> {
+        let online = make_shared(LFA::vector_output(OneHot::new(3), 2));
+        let target = TargetNetwork::new(online.clone());
+
+        for (i, w) in online.borrow_mut().approximator.weights.iter_mut().enumerate() {
+            *w = i as f64 + 1.0;
+        }
+
+        target
+    }
+
+    #[test]
+    fn test_sync_hard() {
+        let mut target = network();
+
+        assert!(target.weights().iter().all(|&w| w == 0.0));
+
+        target.sync_hard();
+
+        assert_eq!(target.weights(), &target.online.weights());
+    }
+
+    #[test]
+    fn test_sync_soft() {
+        let mut a = network();
+        let mut b = network();
+
+        a.sync_soft(1.0);
+        b.sync_hard();
+
+        assert_eq!(a.weights(), b.weights());
+
+        let mut c = network();
+
+        c.sync_soft(0.0);
+
+        assert!(c.weights().iter().all(|&w| w == 0.0));
+
+        c.sync_soft(0.5);
+
+        assert!(c.weights().all_close(&(c.online.weights() * 0.5), 1e-10));
+    }
+
+    #[test]
+    fn test_evaluate_phi() {
+        let mut target = network();
+
+        target.sync_hard();
+        target.online.borrow_mut().approximator.weights.fill(0.0);
+
+        let phi = Projection::Sparse(Some(1).into_iter().collect());
+
+        assert_eq!(target.evaluate_phi(&phi), Vector::from_vec(vec![3.0, 4.0]));
+    }
+
+    #[test]
+    fn test_periodic_target() {
+        let online = make_shared(LFA::vector_output(OneHot::new(3), 2));
+        let mut target = PeriodicTarget::new(online.clone(), 2);
+
+        online.borrow_mut().approximator.weights.fill(1.0);
+
+        BootstrapTarget::<usize, _>::step(&mut target);
+        assert_eq!(target.evaluate(&*online, &0), Some(Vector::zeros(2)));
+
+        BootstrapTarget::<usize, _>::step(&mut target);
+        assert_eq!(target.evaluate(&*online, &0), Some(Vector::ones(2)));
+    }
+
+    #[test]
+    fn test_polyak_average() {
+        let online = make_shared(LFA::vector_output(OneHot::new(3), 2));
+        let mut average = PolyakAverage::new(online.clone(), 0.25);
+
+        for w in online.borrow_mut().approximator.weights.iter_mut() {
+            *w = 4.0;
+        }
+
+        average.update();
+        assert!(average.weights().iter().all(|&w| w == 1.0));
+
+        average.update();
+        assert!(average.weights().iter().all(|&w| w == 1.75));
+    }
+}
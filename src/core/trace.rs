@@ -1,16 +1,39 @@
 //! Eligibility trace types
 use crate::core::{Parameter, Vector};
+use ndarray::ArrayViewMut1;
 
 pub enum TraceType {
+    /// Activations are added to the trace.
     Accumulating,
+
+    /// Activations are added to the trace, with each component clipped to
+    /// `[-1, 1]`.
     Replacing,
+
+    /// The true online trace for a given learning rate; i.e. `e += (1 - alpha *
+    /// e.phi) * phi`.
+    Dutch(f64),
 }
 
+/// Eligibility trace over a fixed number of features.
+///
+/// If a positive `cutoff` is set, components whose magnitude falls below it
+/// are set to exactly zero after every `decay` and `update`, and the indices
+/// of the remaining active components are tracked. The active set is
+/// maintained incrementally: `decay` only visits active components, and
+/// `update` only those with a non-zero activation. Applying the trace with
+/// `scaled_add_to` then only touches the active set whenever fewer than half
+/// of the components are active. Callers that modify `eligibility` directly
+/// should call `refresh` afterwards.
 pub struct Trace {
     pub trace_type: TraceType,
 
     pub lambda: Parameter,
     pub eligibility: Vector,
+
+    pub cutoff: f64,
+    active: Vec<usize>,
+    is_active: Vec<bool>,
 }
 
 impl Trace {
@@ -20,9 +43,21 @@ impl Trace {
 
             lambda: lambda.into(),
             eligibility: Vector::zeros((activation,)),
+
+            cutoff: 0.0,
+            active: vec![],
+            is_active: vec![],
         }
     }
 
+    /// Set the magnitude below which trace components are truncated to zero.
+    pub fn with_cutoff(mut self, cutoff: f64) -> Self {
+        self.cutoff = cutoff;
+        self.refresh();
+
+        self
+    }
+
     pub fn accumulating<T: Into<Parameter>>(lambda: T, activation: usize) -> Trace {
         Trace::new(TraceType::Accumulating, lambda, activation)
     }
@@ -31,9 +66,113 @@ impl Trace {
         Trace::new(TraceType::Replacing, lambda, activation)
     }
 
+    pub fn dutch<T: Into<Parameter>>(lambda: T, alpha: f64, activation: usize) -> Trace {
+        Trace::new(TraceType::Dutch(alpha), lambda, activation)
+    }
+
     pub fn get(&self) -> Vector { self.eligibility.clone() }
 
-    pub fn decay(&mut self, rate: f64) { self.eligibility *= rate; }
+    /// Zero the eligibility of every component.
+    pub fn clear(&mut self) {
+        self.eligibility.fill(0.0);
+
+        for &i in self.active.iter() {
+            self.is_active[i] = false;
+        }
+
+        self.active.clear();
+    }
+
+    pub fn decay(&mut self, rate: f64) {
+        if !self.is_tracking() {
+            self.eligibility *= rate;
+
+            return;
+        }
+
+        let cutoff = self.cutoff;
+        let eligibility = &mut self.eligibility;
+        let is_active = &mut self.is_active;
+
+        self.active.retain(|&i| {
+            eligibility[i] *= rate;
+
+            if eligibility[i].abs() < cutoff {
+                eligibility[i] = 0.0;
+                is_active[i] = false;
+
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Return the indices of the non-zero components, as of the last
+    /// `refresh`; only tracked if a cutoff is set.
+    pub fn active(&self) -> &[usize] { &self.active }
+
+    /// Truncate sub-threshold components to zero and recompute the active set.
+    pub fn refresh(&mut self) {
+        if self.cutoff <= 0.0 {
+            return;
+        }
+
+        let cutoff = self.cutoff;
+
+        self.active.clear();
+        self.is_active = vec![false; self.eligibility.len()];
+
+        for (i, e) in self.eligibility.iter_mut().enumerate() {
+            if e.abs() < cutoff {
+                *e = 0.0;
+            } else {
+                self.active.push(i);
+                self.is_active[i] = true;
+            }
+        }
+    }
+
+    /// Returns true if the active set is tracked, recomputing it in full if
+    /// the cutoff was enabled without a call to `refresh`.
+    fn is_tracking(&mut self) -> bool {
+        if self.cutoff <= 0.0 {
+            return false;
+        }
+
+        if self.is_active.len() != self.eligibility.len() {
+            self.refresh();
+        }
+
+        true
+    }
+
+    /// Apply the cutoff to component `i` and update its membership of the
+    /// active set.
+    fn track(&mut self, i: usize) {
+        if self.eligibility[i].abs() < self.cutoff {
+            self.eligibility[i] = 0.0;
+
+            if self.is_active[i] {
+                self.is_active[i] = false;
+                self.active.retain(|&j| j != i);
+            }
+        } else if !self.is_active[i] {
+            self.is_active[i] = true;
+            self.active.push(i);
+        }
+    }
+
+    /// Add the trace, scaled by `scale`, to `target`.
+    pub fn scaled_add_to(&self, scale: f64, mut target: ArrayViewMut1<f64>) {
+        if self.cutoff > 0.0 && 2 * self.active.len() < self.eligibility.len() {
+            for &i in self.active.iter() {
+                target[i] += scale * self.eligibility[i];
+            }
+        } else {
+            target.scaled_add(scale, &self.eligibility);
+        }
+    }
 
     pub fn update(&mut self, activation: &Vector) {
         match self.trace_type {
@@ -43,6 +182,19 @@ impl Trace {
                     *val = f64::max(-1.0, f64::min(1.0, *val + a));
                 });
             },
+            TraceType::Dutch(alpha) => {
+                let scale = 1.0 - alpha * self.eligibility.dot(activation);
+
+                self.eligibility.scaled_add(scale, activation);
+            },
+        }
+
+        if self.is_tracking() {
+            for (i, &a) in activation.iter().enumerate() {
+                if a != 0.0 {
+                    self.track(i);
+                }
+            }
         }
     }
 }
@@ -93,4 +245,120 @@ mod tests {
         trace.update(&arr1(&[1.0f64; 10]));
         assert_eq!(trace.get(), arr1(&[1.0f64; 10]));
     }
+
+    #[test]
+    fn test_replacing_clip() {
+        let mut trace = Trace::replacing(0.5, 4);
+
+        trace.update(&arr1(&[2.0, -0.5, 0.0, 0.0]));
+        assert_eq!(trace.get(), arr1(&[1.0, -0.5, 0.0, 0.0]));
+
+        let l = trace.lambda.value();
+        trace.decay(l);
+        assert_eq!(trace.get(), arr1(&[0.5, -0.25, 0.0, 0.0]));
+
+        trace.update(&arr1(&[2.0, -0.5, 0.0, 1.0]));
+        assert_eq!(trace.get(), arr1(&[1.0, -0.75, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn test_dutch() {
+        let mut trace = Trace::dutch(0.5, 0.5, 4);
+
+        assert_eq!(trace.get(), arr1(&[0.0f64; 4]));
+
+        trace.update(&arr1(&[1.0, 0.5, 0.0, 0.0]));
+        assert_eq!(trace.get(), arr1(&[1.0, 0.5, 0.0, 0.0]));
+
+        let l = trace.lambda.value();
+        trace.decay(l);
+        assert_eq!(trace.get(), arr1(&[0.5, 0.25, 0.0, 0.0]));
+
+        // e.phi = 0.625, so the activation is scaled by 1 - 0.5 * 0.625:
+        trace.update(&arr1(&[1.0, 0.5, 0.0, 0.0]));
+        assert_eq!(trace.get(), arr1(&[1.1875, 0.59375, 0.0, 0.0]));
+    }
+
+    #[test]
+    fn test_trace_types() {
+        let activations = [arr1(&[1.0, 0.5, 0.0]), arr1(&[1.0, 0.0, 0.5])];
+
+        let mut acc = Trace::accumulating(0.5, 3);
+        let mut rep = Trace::replacing(0.5, 3);
+        let mut dut = Trace::dutch(0.5, 0.5, 3);
+
+        for phi in activations.iter() {
+            for trace in [&mut acc, &mut rep, &mut dut].iter_mut() {
+                let l = trace.lambda.value();
+
+                trace.decay(l);
+                trace.update(phi);
+            }
+        }
+
+        assert_eq!(acc.get(), arr1(&[1.5, 0.25, 0.5]));
+        assert_eq!(rep.get(), arr1(&[1.0, 0.25, 0.5]));
+        assert_eq!(dut.get(), arr1(&[1.25, 0.25, 0.375]));
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut trace = Trace::accumulating(0.5, 4);
+
+        trace.update(&arr1(&[1.0, 2.0, 0.0, -1.0]));
+        trace.clear();
+        assert_eq!(trace.get(), arr1(&[0.0f64; 4]));
+    }
+
+    #[test]
+    fn test_cutoff() {
+        let mut activation = arr1(&[0.0f64; 10]);
+        activation[0] = 1.0;
+        activation[1] = 0.15;
+
+        let mut trace = Trace::accumulating(0.5, 10).with_cutoff(0.1);
+
+        trace.update(&activation);
+        assert_eq!(trace.active(), &[0, 1]);
+
+        trace.decay(0.5);
+        assert_eq!(trace.eligibility[1], 0.0);
+        assert_eq!(trace.active(), &[0]);
+
+        // Only the active component of the target is touched:
+        let mut target = arr1(&[1.0f64; 10]);
+        trace.scaled_add_to(2.0, target.view_mut());
+
+        assert_eq!(target[0], 2.0);
+        assert!(target.iter().skip(1).all(|&x| x == 1.0));
+
+        for _ in 0..3 {
+            trace.decay(0.5);
+        }
+
+        assert!(trace.get().iter().all(|&e| e == 0.0));
+        assert!(trace.active().is_empty());
+    }
+
+    #[test]
+    fn test_cutoff_reactivation() {
+        let mut activation = arr1(&[0.0f64; 4]);
+        activation[2] = 1.0;
+
+        let mut trace = Trace::accumulating(0.5, 4).with_cutoff(0.1);
+
+        trace.update(&activation);
+        trace.decay(0.05);
+        assert!(trace.active().is_empty());
+
+        // A later activation brings the component back into the active set:
+        trace.update(&activation);
+        assert_eq!(trace.active(), &[2]);
+
+        trace.clear();
+        assert!(trace.active().is_empty());
+
+        trace.update(&activation);
+        assert_eq!(trace.active(), &[2]);
+    }
 }
@@ -15,7 +15,6 @@ const M2: f64 = 1.0;
 
 // Link lengths:
 const L1: f64 = 1.0;
-#[allow(dead_code)]
 const L2: f64 = 1.0;
 
 // Link centre of masses:
@@ -27,6 +26,7 @@ const I1: f64 = 1.0;
 const I2: f64 = 1.0;
 
 const DT: f64 = 0.2;
+const SIM_STEPS: usize = 4;
 
 const LIMITS_THETA1: (f64, f64) = (-PI, PI);
 const LIMITS_THETA2: (f64, f64) = (-PI, PI);
@@ -64,6 +64,28 @@ unsafe impl NdIndex<Ix1> for StateIndex {
 /// length of one link above the base.
 ///
 /// See [https://www.math24.net/double-pendulum/](https://www.math24.net/double-pendulum/)
+///
+/// # Technical details
+/// The **state** is represented by a `Vector` with components:
+///
+/// | Index | Name         | Min   | Max  |
+/// | ----- | ------------ | ----- | ---- |
+/// | 0     | cos(theta1)  | -1.0  | 1.0  |
+/// | 1     | sin(theta1)  | -1.0  | 1.0  |
+/// | 2     | cos(theta2)  | -1.0  | 1.0  |
+/// | 3     | sin(theta2)  | -1.0  | 1.0  |
+/// | 4     | dtheta1      | -4pi  | 4pi  |
+/// | 5     | dtheta2      | -9pi  | 9pi  |
+///
+/// The dynamics are integrated using RK4 over four sub-steps per call to
+/// `step`, with link masses and lengths as given in Sutton & Barto (1998).
+///
+/// # References
+/// - Sutton, R. S. (1996). Generalization in reinforcement learning:
+/// Successful examples using sparse coarse coding. Advances in Neural
+/// Information Processing Systems, 1038-1044.
+/// - Sutton, R. S., & Barto, A. G. (1998). Reinforcement learning: An
+/// introduction (Vol. 1, No. 1). Cambridge: MIT press.
 pub struct Acrobot {
     state: Vector,
 }
@@ -78,8 +100,8 @@ impl Acrobot {
     fn update_state(&mut self, a: usize) {
         let fx = |y: &Vector| Acrobot::grad(y);
         let state_aug = stack![Axis(0), self.state, array![ALL_ACTIONS[a] as f64]];
-        let mut ns = runge_kutta4(&fx, state_aug, array![0.0, DT]);
-
+        let times = Vector::linspace(0.0, DT, SIM_STEPS + 1);
+        let mut ns = runge_kutta4(&fx, state_aug, times);
 
         ns[StateIndex::THETA1] = wrap!(LIMITS_THETA1.0, ns[StateIndex::THETA1], LIMITS_THETA1.1);
         ns[StateIndex::THETA2] = wrap!(LIMITS_THETA2.0, ns[StateIndex::THETA2], LIMITS_THETA2.1);
@@ -106,7 +128,7 @@ impl Acrobot {
         let d2 = M2 * (LC2 * LC2 + L1 * LC2 * cos_t2) + I2;
 
         let phi2 = M2 * LC2 * G * (theta1 + theta2 - PI_OVER_2).cos();
-        let phi1 = -1.0 * L1 * LC2 * dtheta2 * dtheta2 * sin_t2
+        let phi1 = -1.0 * M2 * L1 * LC2 * dtheta2 * dtheta2 * sin_t2
             - 2.0 * M2 * L1 * LC2 * dtheta2 * dtheta1 * sin_t2
             + (M1 * LC1 + M2 * L1) * G * (theta1 - PI_OVER_2).cos() + phi2;
 
@@ -116,6 +138,28 @@ impl Acrobot {
 
         Vector::from_vec(vec![dtheta1, dtheta2, ddtheta1, ddtheta2, 0.0])
     }
+
+    /// Height of the end-effector relative to the base of the first link.
+    fn tip_height(&self) -> f64 {
+        let theta1 = self.state[StateIndex::THETA1];
+        let theta2 = self.state[StateIndex::THETA2];
+
+        -L1 * theta1.cos() - L2 * (theta1 + theta2).cos()
+    }
+
+    fn observation(&self) -> Vector {
+        let theta1 = self.state[StateIndex::THETA1];
+        let theta2 = self.state[StateIndex::THETA2];
+
+        Vector::from_vec(vec![
+            theta1.cos(),
+            theta1.sin(),
+            theta2.cos(),
+            theta2.sin(),
+            self.state[StateIndex::DTHETA1],
+            self.state[StateIndex::DTHETA2],
+        ])
+    }
 }
 
 impl Default for Acrobot {
@@ -127,10 +171,12 @@ impl Domain for Acrobot {
     type ActionSpace = Ordinal;
 
     fn emit(&self) -> Observation<Vector<f64>> {
+        let s = self.observation();
+
         if self.is_terminal() {
-            Observation::Terminal(self.state.clone())
+            Observation::Terminal(s)
         } else {
-            Observation::Full(self.state.clone())
+            Observation::Full(s)
         }
     }
 
@@ -146,15 +192,11 @@ impl Domain for Acrobot {
             action,
             reward,
             to,
+            truncated: false,
         }
     }
 
-    fn is_terminal(&self) -> bool {
-        let theta1 = self.state[StateIndex::THETA1];
-        let theta2 = self.state[StateIndex::THETA2];
-
-        theta1.cos() + (theta1 + theta2).cos() < -1.0
-    }
+    fn is_terminal(&self) -> bool { self.tip_height() > L1 }
 
     fn reward(&self, _: &Observation<Vector<f64>>, to: &Observation<Vector<f64>>) -> f64 {
         match *to {
@@ -164,8 +206,10 @@ impl Domain for Acrobot {
     }
 
     fn state_space(&self) -> Self::StateSpace {
-        LinearSpace::empty() + Interval::bounded(LIMITS_THETA1.0, LIMITS_THETA1.1)
-            + Interval::bounded(LIMITS_THETA2.0, LIMITS_THETA2.1)
+        LinearSpace::empty() + Interval::bounded(-1.0, 1.0)
+            + Interval::bounded(-1.0, 1.0)
+            + Interval::bounded(-1.0, 1.0)
+            + Interval::bounded(-1.0, 1.0)
             + Interval::bounded(LIMITS_DTHETA1.0, LIMITS_DTHETA1.1)
             + Interval::bounded(LIMITS_DTHETA2.0, LIMITS_DTHETA2.1)
     }
@@ -214,6 +258,7 @@ impl Domain for Acrobot {
 mod tests {
     use super::*;
     use crate::domains::{Domain, Observation};
+    use crate::geometry::Space;
 
     #[test]
     fn test_initial_observation() {
@@ -221,17 +266,60 @@ mod tests {
 
         match m.emit() {
             Observation::Full(ref state) => {
-                assert_eq!(state[0], 0.0);
+                assert_eq!(state.len(), 6);
+
+                assert_eq!(state[0], 1.0);
                 assert_eq!(state[1], 0.0);
-                assert_eq!(state[2], 0.0);
+                assert_eq!(state[2], 1.0);
                 assert_eq!(state[3], 0.0);
+                assert_eq!(state[4], 0.0);
+                assert_eq!(state[5], 0.0);
             },
             _ => panic!("Should yield a fully observable state."),
         }
     }
 
+    #[test]
+    fn test_observation_layout() {
+        let m = Acrobot::new(0.5, -0.3, 1.0, 2.0);
+        let s = m.emit();
+        let s = s.state();
+
+        assert!((s[0] - 0.5f64.cos()).abs() < 1e-7);
+        assert!((s[1] - 0.5f64.sin()).abs() < 1e-7);
+        assert!((s[2] - (-0.3f64).cos()).abs() < 1e-7);
+        assert!((s[3] - (-0.3f64).sin()).abs() < 1e-7);
+        assert!((s[4] - 1.0).abs() < 1e-7);
+        assert!((s[5] - 2.0).abs() < 1e-7);
+
+        assert_eq!(m.state_space().dim(), 6);
+    }
+
+    #[test]
+    fn test_tip_height() {
+        assert!((Acrobot::new(0.0, 0.0, 0.0, 0.0).tip_height() + 2.0).abs() < 1e-7);
+        assert!((Acrobot::new(PI, 0.0, 0.0, 0.0).tip_height() - 2.0).abs() < 1e-7);
+        assert!((Acrobot::new(PI_OVER_2, 0.0, 0.0, 0.0).tip_height()).abs() < 1e-7);
+        assert!((Acrobot::new(PI, PI, 0.0, 0.0).tip_height()).abs() < 1e-7);
+    }
+
     #[test]
     fn test_is_terminal() {
         assert!(!Acrobot::default().is_terminal());
+
+        assert!(Acrobot::new(PI, 0.0, 0.0, 0.0).is_terminal());
+        assert!(!Acrobot::new(PI_OVER_2, 0.0, 0.0, 0.0).is_terminal());
+        assert!(!Acrobot::new(PI, PI, 0.0, 0.0).is_terminal());
+    }
+
+    #[test]
+    fn test_reward() {
+        let m = Acrobot::default();
+
+        let s = m.emit();
+        let ns = Acrobot::new(PI, 0.0, 0.0, 0.0).emit();
+
+        assert_eq!(m.reward(&s, &s), REWARD_STEP);
+        assert_eq!(m.reward(&s, &ns), REWARD_TERMINAL);
     }
 }
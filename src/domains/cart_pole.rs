@@ -6,7 +6,8 @@ use crate::geometry::{
     Vector,
 };
 use ndarray::{Ix1, NdIndex};
-use super::{runge_kutta4, Domain, Observation, Transition};
+use rand::{thread_rng, Rng};
+use super::{Domain, Observation, Transition};
 
 const TAU: f64 = 0.02;
 
@@ -17,6 +18,8 @@ const POLE_COM: f64 = 0.5;
 const POLE_MASS: f64 = 0.1;
 const POLE_MOMENT: f64 = POLE_COM * POLE_MASS;
 
+const INIT_RANGE: f64 = 0.05;
+
 const TOTAL_MASS: f64 = CART_MASS + POLE_MASS;
 
 const LIMITS_X: (f64, f64) = (-2.4, 2.4);
@@ -24,8 +27,8 @@ const LIMITS_DX: (f64, f64) = (-6.0, 6.0);
 const LIMITS_THETA: (f64, f64) = (-TWELVE_DEGREES, TWELVE_DEGREES);
 const LIMITS_DTHETA: (f64, f64) = (-2.0, 2.0);
 
-const REWARD_STEP: f64 = 0.0;
-const REWARD_TERMINAL: f64 = -1.0;
+const REWARD_STEP: f64 = 1.0;
+const REWARD_TERMINAL: f64 = 0.0;
 
 const ALL_ACTIONS: [f64; 2] = [-1.0 * CART_FORCE, 1.0 * CART_FORCE];
 
@@ -47,6 +50,32 @@ unsafe impl NdIndex<Ix1> for StateIndex {
     fn index_unchecked(&self, strides: &Ix1) -> isize { (*self as usize).index_unchecked(strides) }
 }
 
+/// Classic cart-pole balancing domain.
+///
+/// A pole is attached by an un-actuated joint to a cart which moves along a
+/// frictionless track. The system is controlled by applying a force of +/-10N
+/// to the cart, and the goal is to prevent the pole from falling over for as
+/// long as possible. The dynamics mirror those of the `CartPole-v1`
+/// environment in OpenAI Gym, integrated using the semi-implicit Euler
+/// method.
+///
+/// # Technical details
+/// The **state** is represented by a `Vector` with components:
+///
+/// | Index | Name                  | Min    | Max   |
+/// | ----- | --------------------- | ------ | ----- |
+/// | 0     | Cart position         | -2.4   | 2.4   |
+/// | 1     | Cart velocity         | -6.0   | 6.0   |
+/// | 2     | Pole angle            | -12deg | 12deg |
+/// | 3     | Pole angular velocity | -2.0   | 2.0   |
+///
+/// An episode terminates once the pole angle or cart position leave the
+/// bounds above, and a reward of +1 is given for every non-terminal step.
+///
+/// # References
+/// - Barto, A. G., Sutton, R. S., & Anderson, C. W. (1983). Neuronlike
+/// adaptive elements that can solve difficult learning control problems. IEEE
+/// Transactions on Systems, Man, and Cybernetics, 13(5), 834-846.
 pub struct CartPole {
     state: Vector,
 }
@@ -58,21 +87,33 @@ impl CartPole {
         }
     }
 
+    /// Construct a cart-pole with initial state components drawn uniformly
+    /// from `[-0.05, 0.05]` using the given generator.
+    pub fn from_rng<R: Rng>(rng: &mut R) -> CartPole {
+        CartPole::new(
+            rng.gen_range(-INIT_RANGE, INIT_RANGE),
+            rng.gen_range(-INIT_RANGE, INIT_RANGE),
+            rng.gen_range(-INIT_RANGE, INIT_RANGE),
+            rng.gen_range(-INIT_RANGE, INIT_RANGE),
+        )
+    }
+
     fn update_state(&mut self, a: usize) {
-        let fx = |y: &Vector| CartPole::grad(ALL_ACTIONS[a], y);
-        let mut ns = runge_kutta4(&fx, self.state.clone(), array![0.0, TAU]);
+        let (ddx, ddtheta) = CartPole::accelerations(ALL_ACTIONS[a], &self.state);
 
-        ns[StateIndex::X] = clip!(LIMITS_X.0, ns[StateIndex::X], LIMITS_X.1);
-        ns[StateIndex::DX] = clip!(LIMITS_DX.0, ns[StateIndex::DX], LIMITS_DX.1);
+        // Semi-implicit Euler: velocities are updated first and then used to
+        // update the positions.
+        let dx = self.state[StateIndex::DX] + TAU * ddx;
+        let dtheta = self.state[StateIndex::DTHETA] + TAU * ddtheta;
 
-        ns[StateIndex::THETA] = clip!(LIMITS_THETA.0, ns[StateIndex::THETA], LIMITS_THETA.1);
-        ns[StateIndex::DTHETA] = clip!(LIMITS_DTHETA.0, ns[StateIndex::DTHETA], LIMITS_DTHETA.1);
+        self.state[StateIndex::DX] = clip!(LIMITS_DX.0, dx, LIMITS_DX.1);
+        self.state[StateIndex::DTHETA] = clip!(LIMITS_DTHETA.0, dtheta, LIMITS_DTHETA.1);
 
-        self.state = ns;
+        self.state[StateIndex::X] += TAU * self.state[StateIndex::DX];
+        self.state[StateIndex::THETA] += TAU * self.state[StateIndex::DTHETA];
     }
 
-    fn grad(force: f64, state: &Vector) -> Vector {
-        let dx = state[StateIndex::DX];
+    fn accelerations(force: f64, state: &Vector) -> (f64, f64) {
         let theta = state[StateIndex::THETA];
         let dtheta = state[StateIndex::DTHETA];
 
@@ -82,17 +123,17 @@ impl CartPole {
         let z = (force + POLE_MOMENT * dtheta * dtheta * sin_theta) / TOTAL_MASS;
 
         let numer = G * sin_theta - cos_theta * z;
-        let denom = FOUR_THIRDS * POLE_COM - POLE_MOMENT * cos_theta * cos_theta;
+        let denom = POLE_COM * (FOUR_THIRDS - POLE_MASS * cos_theta * cos_theta / TOTAL_MASS);
 
         let ddtheta = numer / denom;
-        let ddx = z - POLE_COM * ddtheta * cos_theta;
+        let ddx = z - POLE_MOMENT * ddtheta * cos_theta / TOTAL_MASS;
 
-        Vector::from_vec(vec![dx, ddx, dtheta, ddtheta])
+        (ddx, ddtheta)
     }
 }
 
 impl Default for CartPole {
-    fn default() -> CartPole { CartPole::new(0.0, 0.0, 0.0, 0.0) }
+    fn default() -> CartPole { CartPole::from_rng(&mut thread_rng()) }
 }
 
 impl Domain for CartPole {
@@ -119,6 +160,7 @@ impl Domain for CartPole {
             action,
             reward,
             to,
+            truncated: false,
         }
     }
 
@@ -126,7 +168,7 @@ impl Domain for CartPole {
         let x = self.state[StateIndex::X];
         let theta = self.state[StateIndex::THETA];
 
-        x <= LIMITS_X.0 || x >= LIMITS_X.1 || theta <= LIMITS_THETA.0 || theta >= LIMITS_THETA.1
+        x.abs() > LIMITS_X.1 || theta.abs() > LIMITS_THETA.1
     }
 
     fn reward(&self, _: &Observation<Vector<f64>>, to: &Observation<Vector<f64>>) -> f64 {
@@ -150,6 +192,7 @@ impl Domain for CartPole {
 mod tests {
     use super::*;
     use crate::domains::{Domain, Observation};
+    use rand::{rngs::StdRng, SeedableRng};
 
     #[test]
     fn test_initial_observation() {
@@ -157,50 +200,85 @@ mod tests {
 
         match m.emit() {
             Observation::Full(ref state) => {
-                assert_eq!(state[0], 0.0);
-                assert_eq!(state[1], 0.0);
-                assert_eq!(state[2], 0.0);
-                assert_eq!(state[3], 0.0);
+                assert!(state[0].abs() <= INIT_RANGE);
+                assert!(state[1].abs() <= INIT_RANGE);
+                assert!(state[2].abs() <= INIT_RANGE);
+                assert!(state[3].abs() <= INIT_RANGE);
             },
             _ => panic!("Should yield a fully observable state."),
         }
     }
 
+    #[test]
+    fn test_from_rng() {
+        let a = CartPole::from_rng(&mut StdRng::seed_from_u64(0));
+        let b = CartPole::from_rng(&mut StdRng::seed_from_u64(0));
+
+        assert_eq!(a.emit().state(), b.emit().state());
+    }
+
+    #[test]
+    fn test_is_terminal() {
+        assert!(!CartPole::default().is_terminal());
+        assert!(!CartPole::new(0.0, 0.0, 0.0, 0.0).is_terminal());
+
+        assert!(!CartPole::new(LIMITS_X.0, 0.0, 0.0, 0.0).is_terminal());
+        assert!(!CartPole::new(LIMITS_X.1, 0.0, 0.0, 0.0).is_terminal());
+        assert!(CartPole::new(LIMITS_X.0 - 1e-4, 0.0, 0.0, 0.0).is_terminal());
+        assert!(CartPole::new(LIMITS_X.1 + 1e-4, 0.0, 0.0, 0.0).is_terminal());
+
+        assert!(!CartPole::new(0.0, 0.0, LIMITS_THETA.0, 0.0).is_terminal());
+        assert!(!CartPole::new(0.0, 0.0, LIMITS_THETA.1, 0.0).is_terminal());
+        assert!(CartPole::new(0.0, 0.0, LIMITS_THETA.0 - 1e-4, 0.0).is_terminal());
+        assert!(CartPole::new(0.0, 0.0, LIMITS_THETA.1 + 1e-4, 0.0).is_terminal());
+    }
+
+    #[test]
+    fn test_reward() {
+        let m = CartPole::new(0.0, 0.0, 0.0, 0.0);
+
+        let s = m.emit();
+        let ns = CartPole::new(LIMITS_X.1 + 1e-4, 0.0, 0.0, 0.0).emit();
+
+        assert_eq!(m.reward(&s, &s), REWARD_STEP);
+        assert_eq!(m.reward(&s, &ns), REWARD_TERMINAL);
+    }
+
     #[test]
     fn test_step_0() {
-        let mut m = CartPole::default();
+        let mut m = CartPole::new(0.0, 0.0, 0.0, 0.0);
 
         let t = m.step(0);
         let s = t.to.state();
-        assert!((s[0] + 0.0032931628891235).abs() < 1e-7);
-        assert!((s[1] + 0.3293940797883472).abs() < 1e-7);
-        assert!((s[2] - 0.0029499634056967).abs() < 1e-7);
-        assert!((s[3] - 0.2951522145037250).abs() < 1e-7);
+        assert!((s[0] + 0.0039024390243902).abs() < 1e-7);
+        assert!((s[1] + 0.1951219512195122).abs() < 1e-7);
+        assert!((s[2] - 0.0058536585365854).abs() < 1e-7);
+        assert!((s[3] - 0.2926829268292683).abs() < 1e-7);
 
         let t = m.step(0);
         let s = t.to.state();
-        assert!((s[0] + 0.0131819582085161).abs() < 1e-7);
-        assert!((s[1] + 0.6597158115002169).abs() < 1e-7);
-        assert!((s[2] - 0.0118185373734479).abs() < 1e-7);
-        assert!((s[3] - 0.5921703414056713).abs() < 1e-7);
+        assert!((s[0] + 0.0117089764628848).abs() < 1e-7);
+        assert!((s[1] + 0.3903268719247303).abs() < 1e-7);
+        assert!((s[2] - 0.0175977836788623).abs() < 1e-7);
+        assert!((s[3] - 0.5872062571138459).abs() < 1e-7);
     }
 
     #[test]
     fn test_step_1() {
-        let mut m = CartPole::default();
+        let mut m = CartPole::new(0.0, 0.0, 0.0, 0.0);
 
         let t = m.step(1);
         let s = t.to.state();
-        assert!((s[0] - 0.0032931628891235).abs() < 1e-7);
-        assert!((s[1] - 0.3293940797883472).abs() < 1e-7);
-        assert!((s[2] + 0.0029499634056967).abs() < 1e-7);
-        assert!((s[3] + 0.2951522145037250).abs() < 1e-7);
+        assert!((s[0] - 0.0039024390243902).abs() < 1e-7);
+        assert!((s[1] - 0.1951219512195122).abs() < 1e-7);
+        assert!((s[2] + 0.0058536585365854).abs() < 1e-7);
+        assert!((s[3] + 0.2926829268292683).abs() < 1e-7);
 
         let t = m.step(1);
         let s = t.to.state();
-        assert!((s[0] - 0.0131819582085161).abs() < 1e-7);
-        assert!((s[1] - 0.6597158115002169).abs() < 1e-7);
-        assert!((s[2] + 0.0118185373734479).abs() < 1e-7);
-        assert!((s[3] + 0.5921703414056713).abs() < 1e-7);
+        assert!((s[0] - 0.0117089764628848).abs() < 1e-7);
+        assert!((s[1] - 0.3903268719247303).abs() < 1e-7);
+        assert!((s[2] + 0.0175977836788623).abs() < 1e-7);
+        assert!((s[3] + 0.5872062571138459).abs() < 1e-7);
     }
 }
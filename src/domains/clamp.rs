@@ -0,0 +1,103 @@
+use crate::geometry::{continuous::Interval, product::LinearSpace, BoundedSpace, Space, Vector};
+
+/// Trait for continuous action spaces that can project arbitrary values onto
+/// their support.
+///
+/// Continuous domains clamp each action before applying it, but report the
+/// action as given in the resulting `Transition`.
+pub trait Clamp<T = f64> {
+    /// Clamp `value` to the bounds of the space.
+    ///
+    /// Values outside the space are mapped to the nearest bound, and `NaN` is
+    /// mapped to the midpoint of the space (or its only finite bound).
+    fn clamp(&self, value: T) -> T;
+}
+
+impl Clamp for Interval {
+    fn clamp(&self, value: f64) -> f64 {
+        let (lb, ub) = (self.inf(), self.sup());
+
+        if value.is_nan() {
+            return match (lb, ub) {
+                (Some(lb), Some(ub)) => (lb + ub) / 2.0,
+                (Some(bound), None) | (None, Some(bound)) => bound,
+                (None, None) => 0.0,
+            };
+        }
+
+        let value = lb.map_or(value, |lb| value.max(lb));
+
+        ub.map_or(value, |ub| value.min(ub))
+    }
+}
+
+impl Clamp<Vector<f64>> for LinearSpace<Interval> {
+    /// Clamp each component of `value` to the corresponding dimension.
+    fn clamp(&self, value: Vector<f64>) -> Vector<f64> {
+        assert_eq!(
+            value.len(),
+            self.dim(),
+            "Cannot clamp a {}-dimensional value to a {}-dimensional space.",
+            value.len(),
+            self.dim()
+        );
+
+        value.iter().zip(self.iter()).map(|(&x, d)| d.clamp(x)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64;
+
+    #[test]
+    fn test_bounded() {
+        let space = Interval::bounded(-2.0, 4.0);
+
+        assert_eq!(space.clamp(0.5), 0.5);
+        assert_eq!(space.clamp(-2.0), -2.0);
+        assert_eq!(space.clamp(10.0), 4.0);
+        assert_eq!(space.clamp(-10.0), -2.0);
+        assert_eq!(space.clamp(f64::INFINITY), 4.0);
+        assert_eq!(space.clamp(f64::NEG_INFINITY), -2.0);
+        assert_eq!(space.clamp(f64::NAN), 1.0);
+    }
+
+    #[test]
+    fn test_half_bounded() {
+        let space = Interval::left_bounded(1.0);
+
+        assert_eq!(space.clamp(-5.0), 1.0);
+        assert_eq!(space.clamp(5.0), 5.0);
+        assert_eq!(space.clamp(f64::NAN), 1.0);
+
+        let space = Interval::right_bounded(1.0);
+
+        assert_eq!(space.clamp(-5.0), -5.0);
+        assert_eq!(space.clamp(5.0), 1.0);
+        assert_eq!(space.clamp(f64::NAN), 1.0);
+    }
+
+    #[test]
+    fn test_linear_space() {
+        let space = LinearSpace::empty() + Interval::bounded(-1.0, 1.0) + Interval::bounded(0.0, 2.0);
+
+        assert_eq!(
+            space.clamp(Vector::from_vec(vec![5.0, -3.0])),
+            Vector::from_vec(vec![1.0, 0.0])
+        );
+        assert_eq!(
+            space.clamp(Vector::from_vec(vec![0.5, f64::NAN])),
+            Vector::from_vec(vec![0.5, 1.0])
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_linear_space_dim_mismatch() {
+        let space = LinearSpace::empty() + Interval::bounded(-1.0, 1.0);
+
+        space.clamp(Vector::from_vec(vec![0.0, 0.0]));
+    }
+}
@@ -60,6 +60,7 @@ impl Domain for CliffWalk {
             action,
             reward,
             to,
+            truncated: false,
         }
     }
 
@@ -0,0 +1,175 @@
+use crate::core::Matrix;
+use crate::geometry::discrete::Ordinal;
+use super::{
+    grid_world::{GridWorld, Motion},
+    Domain,
+    Observation,
+    Transition,
+};
+
+// A `Motion` moves north by increasing `y`, which here runs from the top of
+// the grid downwards; hence up and down map to south and north, respectively.
+const ALL_ACTIONS: [Motion; 4] = [
+    Motion::South(1),
+    Motion::North(1),
+    Motion::West(1),
+    Motion::East(1),
+];
+
+const WIDTH: usize = 12;
+const HEIGHT: usize = 4;
+
+const START: (usize, usize) = (0, HEIGHT - 1);
+const GOAL: (usize, usize) = (WIDTH - 1, HEIGHT - 1);
+
+const REWARD_STEP: f64 = -1.0;
+const REWARD_CLIFF: f64 = -100.0;
+
+/// Cliff walking domain.
+///
+/// The agent navigates a 12x4 grid using the four cardinal moves: up (0), down
+/// (1), left (2) and right (3). It starts in the bottom-left corner and must
+/// reach the goal in the bottom-right corner; the cells between them form a
+/// cliff. Stepping into the cliff yields a reward of -100 and returns the
+/// agent to the start without ending the episode. All other steps yield -1,
+/// and reaching the goal terminates the episode.
+///
+/// This differs from `CliffWalk`, in which falling off the cliff ends the
+/// episode, but shares its grid mechanics.
+///
+/// # Technical details
+/// The **state** is the index of the agent's cell, `y * 12 + x`, where `(0,
+/// 0)` is the top-left corner of the grid.
+///
+/// # References
+/// - Sutton, R. S. and Barto, A. G. (2017). Reinforcement Learning: An
+/// Introduction (2nd ed.). Manuscript in preparation.
+pub struct CliffWalking {
+    gw: GridWorld<u8>,
+    loc: (usize, usize),
+    fell: bool,
+}
+
+impl CliffWalking {
+    pub fn new() -> CliffWalking {
+        CliffWalking {
+            // The layout is indexed by `(x, y)` so that each motion is bounded
+            // by the matching extent of the grid.
+            gw: GridWorld::new(Matrix::zeros((WIDTH, HEIGHT))),
+            loc: START,
+            fell: false,
+        }
+    }
+
+    fn is_cliff(cell: (usize, usize)) -> bool {
+        cell.1 == HEIGHT - 1 && cell.0 > 0 && cell.0 < WIDTH - 1
+    }
+
+    fn index(cell: (usize, usize)) -> usize { cell.1 * WIDTH + cell.0 }
+
+    fn update_state(&mut self, a: usize) {
+        let next = self.gw.perform_motion(self.loc, ALL_ACTIONS[a]);
+
+        self.fell = CliffWalking::is_cliff(next);
+        self.loc = if self.fell { START } else { next };
+    }
+}
+
+impl Default for CliffWalking {
+    fn default() -> CliffWalking { CliffWalking::new() }
+}
+
+impl Domain for CliffWalking {
+    type StateSpace = Ordinal;
+    type ActionSpace = Ordinal;
+
+    fn emit(&self) -> Observation<usize> {
+        let s = CliffWalking::index(self.loc);
+
+        if self.is_terminal() {
+            Observation::Terminal(s)
+        } else {
+            Observation::Full(s)
+        }
+    }
+
+    fn step(&mut self, action: usize) -> Transition<usize, usize> {
+        let from = self.emit();
+
+        self.update_state(action);
+        let to = self.emit();
+        let reward = self.reward(&from, &to);
+
+        Transition {
+            from,
+            action,
+            reward,
+            to,
+            truncated: false,
+        }
+    }
+
+    fn is_terminal(&self) -> bool { self.loc == GOAL }
+
+    fn state_index(&self) -> Option<usize> { Some(CliffWalking::index(self.loc)) }
+
+    fn reward(&self, _: &Observation<usize>, _: &Observation<usize>) -> f64 {
+        if self.fell { REWARD_CLIFF } else { REWARD_STEP }
+    }
+
+    fn state_space(&self) -> Ordinal { Ordinal::new(WIDTH * HEIGHT) }
+
+    fn action_space(&self) -> Ordinal { Ordinal::new(4) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{Domain, Observation};
+
+    const UP: usize = 0;
+    const DOWN: usize = 1;
+    const RIGHT: usize = 3;
+
+    #[test]
+    fn test_initial_observation() {
+        match CliffWalking::new().emit() {
+            Observation::Full(state) => assert_eq!(state, 36),
+            _ => panic!("Should yield a fully observable state."),
+        }
+    }
+
+    #[test]
+    fn test_cliff() {
+        let mut m = CliffWalking::new();
+
+        let t = m.step(RIGHT);
+
+        assert_eq!(t.reward, REWARD_CLIFF);
+        assert!(!t.terminated());
+        assert_eq!(*t.to.state(), 36);
+
+        // Subsequent safe steps are back to the usual cost:
+        let t = m.step(UP);
+
+        assert_eq!(t.reward, REWARD_STEP);
+        assert_eq!(*t.to.state(), 24);
+    }
+
+    #[test]
+    fn test_safe_path() {
+        let mut m = CliffWalking::new();
+
+        assert_eq!(m.step(UP).reward, REWARD_STEP);
+
+        for _ in 0..11 {
+            assert_eq!(m.step(RIGHT).reward, REWARD_STEP);
+        }
+
+        let t = m.step(DOWN);
+
+        assert!(t.terminated());
+        assert_eq!(t.reward, REWARD_STEP);
+        assert_eq!(*t.to.state(), 47);
+    }
+}
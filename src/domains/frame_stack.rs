@@ -0,0 +1,156 @@
+use crate::geometry::{continuous::Interval, product::LinearSpace, Space, Vector};
+use std::collections::VecDeque;
+use super::{Domain, Observation, Reset, Transition};
+
+type ActionOf<D> = <<D as Domain>::ActionSpace as Space>::Value;
+
+/// Wrapper that stacks the last `k` states emitted by a domain into a single
+/// vector.
+///
+/// Frames are concatenated from oldest to newest, so that the final block is
+/// always the current state of the underlying domain. Until `k` steps have
+/// been taken, the missing history is padded with the initial observation.
+pub struct FrameStack<D> {
+    domain: D,
+
+    n_frames: usize,
+    frames: VecDeque<Vector<f64>>,
+}
+
+impl<D: Domain<StateSpace = LinearSpace<Interval>>> FrameStack<D> {
+    pub fn new(domain: D, n_frames: usize) -> FrameStack<D> {
+        assert!(n_frames > 0, "A frame stack requires at least one frame.");
+
+        let frames = FrameStack::initial_frames(&domain, n_frames);
+
+        FrameStack {
+            domain,
+
+            n_frames,
+            frames,
+        }
+    }
+
+    fn initial_frames(domain: &D, n_frames: usize) -> VecDeque<Vector<f64>> {
+        let initial = domain.emit().state().clone();
+
+        (0..n_frames).map(|_| initial.clone()).collect()
+    }
+
+    /// Return a reference to the wrapped domain.
+    pub fn inner(&self) -> &D { &self.domain }
+
+    fn stacked(&self) -> Vector<f64> {
+        Vector::from_vec(self.frames.iter().flat_map(|f| f.iter().cloned()).collect())
+    }
+
+    fn unstack(&self, obs: &Observation<Vector<f64>>) -> Observation<Vector<f64>> {
+        let n = obs.state().len();
+        let d = n / self.n_frames;
+
+        obs.clone().map(|v| Vector::from_vec(v.iter().skip(n - d).cloned().collect()))
+    }
+}
+
+impl<D: Domain<StateSpace = LinearSpace<Interval>> + Reset> Reset for FrameStack<D> {
+    fn reset_state(&mut self) {
+        self.domain.reset();
+        self.frames = FrameStack::initial_frames(&self.domain, self.n_frames);
+    }
+}
+
+impl<D: Domain<StateSpace = LinearSpace<Interval>>> Domain for FrameStack<D> {
+    type StateSpace = LinearSpace<Interval>;
+    type ActionSpace = D::ActionSpace;
+
+    fn emit(&self) -> Observation<Vector<f64>> {
+        let stacked = self.stacked();
+
+        self.domain.emit().map(|_| stacked)
+    }
+
+    fn step(&mut self, a: ActionOf<D>) -> Transition<Vector<f64>, ActionOf<D>> {
+        let from = self.stacked();
+        let t = self.domain.step(a);
+
+        self.frames.pop_front();
+        self.frames.push_back(t.to.state().clone());
+
+        let to = self.stacked();
+
+        Transition {
+            from: t.from.map(|_| from),
+            action: t.action,
+            reward: t.reward,
+            to: t.to.map(|_| to),
+            truncated: t.truncated,
+        }
+    }
+
+    fn is_terminal(&self) -> bool { self.domain.is_terminal() }
+
+    fn reward(&self, from: &Observation<Vector<f64>>, to: &Observation<Vector<f64>>) -> f64 {
+        self.domain.reward(&self.unstack(from), &self.unstack(to))
+    }
+
+    fn state_space(&self) -> LinearSpace<Interval> {
+        let inner = self.domain.state_space();
+
+        (0..self.n_frames).fold(LinearSpace::empty(), |acc, _| {
+            inner.iter().fold(acc, |acc, d| acc + d.clone())
+        })
+    }
+
+    fn action_space(&self) -> Self::ActionSpace { self.domain.action_space() }
+
+    fn reward_range(&self) -> (Option<f64>, Option<f64>) { self.domain.reward_range() }
+
+    fn render(&self, ctx: &mut ggez::Context) { self.domain.render(ctx) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{Domain, MountainCar};
+
+    #[test]
+    fn test_history() {
+        let mut m = FrameStack::new(MountainCar::default(), 3);
+        let mut reference = MountainCar::default();
+
+        let s0 = reference.emit().state().clone();
+
+        assert_eq!(m.emit().state().to_vec(), [&s0.to_vec()[..]; 3].concat());
+
+        let s1 = reference.step(2).to.state().clone();
+        m.step(2);
+
+        assert_eq!(m.emit().state().to_vec(), [&s0.to_vec()[..], &s0.to_vec()[..], &s1.to_vec()[..]].concat());
+
+        let s2 = reference.step(2).to.state().clone();
+        let s3 = reference.step(0).to.state().clone();
+        m.step(2);
+        let t = m.step(0);
+
+        assert_eq!(t.to.state().to_vec(), [&s1.to_vec()[..], &s2.to_vec()[..], &s3.to_vec()[..]].concat());
+        assert_eq!(m.reward(&t.from, &t.to), -1.0);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut m = FrameStack::new(MountainCar::default(), 2);
+        let s0 = m.emit().state().clone();
+
+        m.step(2);
+        m.step(2);
+
+        assert_eq!(*m.reset().state(), s0);
+    }
+
+    #[test]
+    fn test_state_space() {
+        let m = FrameStack::new(MountainCar::default(), 4);
+
+        assert_eq!(m.state_space().dim(), 4 * MountainCar::default().state_space().dim());
+    }
+}
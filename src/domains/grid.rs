@@ -0,0 +1,290 @@
+use crate::geometry::discrete::Ordinal;
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+use super::{Domain, Observation, Transition};
+
+const UP: usize = 0;
+const DOWN: usize = 1;
+const LEFT: usize = 2;
+const RIGHT: usize = 3;
+
+const REWARD_STEP: f64 = 0.0;
+
+/// Configurable tabular grid world domain.
+///
+/// The agent navigates a rectangular grid of `width * height` cells using the
+/// four cardinal moves: up (0), down (1), left (2) and right (3). Any move that
+/// would take the agent off the grid, or into a wall, leaves it in place. With
+/// probability `slip`, the chosen move is redirected to one of the two
+/// perpendicular directions, chosen uniformly at random. An episode terminates
+/// once the agent enters any goal cell, at which point the associated goal
+/// reward is given.
+///
+/// # Technical details
+/// The **state** is the index of the agent's cell, `y * width + x`, where `(0,
+/// 0)` is the top-left corner of the grid.
+pub struct GridWorld {
+    width: usize,
+    height: usize,
+
+    walls: HashSet<usize>,
+    goals: HashMap<usize, f64>,
+
+    start: usize,
+    slip: f64,
+
+    loc: usize,
+    rng: StdRng,
+}
+
+impl GridWorld {
+    pub fn new(width: usize, height: usize) -> GridWorld {
+        GridWorld {
+            width,
+            height,
+
+            walls: HashSet::new(),
+            goals: HashMap::new(),
+
+            start: 0,
+            slip: 0.0,
+
+            loc: 0,
+            rng: StdRng::from_rng(thread_rng()).unwrap(),
+        }
+    }
+
+    /// Set the cell, `(x, y)`, from which the agent starts.
+    pub fn with_start(mut self, cell: (usize, usize)) -> Self {
+        self.start = self.index(cell);
+        self.loc = self.start;
+
+        self
+    }
+
+    /// Add an impassable wall at the cell `(x, y)`.
+    pub fn with_wall(mut self, cell: (usize, usize)) -> Self {
+        let idx = self.index(cell);
+        self.walls.insert(idx);
+
+        self
+    }
+
+    /// Add a terminal goal at the cell `(x, y)` with a given reward.
+    pub fn with_goal(mut self, cell: (usize, usize), reward: f64) -> Self {
+        let idx = self.index(cell);
+        self.goals.insert(idx, reward);
+
+        self
+    }
+
+    /// Set the probability that a move is redirected to a perpendicular
+    /// direction.
+    pub fn with_slip(mut self, prob: f64) -> Self {
+        assert!(prob >= 0.0 && prob <= 1.0, "Slip probability must lie in [0, 1].");
+
+        self.slip = prob;
+
+        self
+    }
+
+    /// Seed the random number generator used to sample slips.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+
+        self
+    }
+
+    pub fn width(&self) -> usize { self.width }
+
+    pub fn height(&self) -> usize { self.height }
+
+    fn index(&self, cell: (usize, usize)) -> usize {
+        assert!(cell.0 < self.width && cell.1 < self.height, "Cell {:?} is out of bounds.", cell);
+
+        cell.1 * self.width + cell.0
+    }
+
+    fn coords(&self, idx: usize) -> (usize, usize) { (idx % self.width, idx / self.width) }
+
+    fn motion(&mut self, a: usize) -> usize {
+        if self.slip > 0.0 && self.rng.gen_bool(self.slip) {
+            let flip = self.rng.gen_bool(0.5);
+
+            match a {
+                UP | DOWN => if flip { LEFT } else { RIGHT },
+                _ => if flip { UP } else { DOWN },
+            }
+        } else {
+            a
+        }
+    }
+
+    fn update_state(&mut self, a: usize) {
+        let (x, y) = self.coords(self.loc);
+        let next = match self.motion(a) {
+            UP if y > 0 => (x, y - 1),
+            DOWN if y + 1 < self.height => (x, y + 1),
+            LEFT if x > 0 => (x - 1, y),
+            RIGHT if x + 1 < self.width => (x + 1, y),
+            UP | DOWN | LEFT | RIGHT => (x, y),
+            _ => panic!("Unknown motion {}!", a),
+        };
+        let next = self.index(next);
+
+        if !self.walls.contains(&next) {
+            self.loc = next;
+        }
+    }
+}
+
+impl Domain for GridWorld {
+    type StateSpace = Ordinal;
+    type ActionSpace = Ordinal;
+
+    fn emit(&self) -> Observation<usize> {
+        if self.is_terminal() {
+            Observation::Terminal(self.loc)
+        } else {
+            Observation::Full(self.loc)
+        }
+    }
+
+    fn step(&mut self, action: usize) -> Transition<usize, usize> {
+        let from = self.emit();
+
+        self.update_state(action);
+        let to = self.emit();
+        let reward = self.reward(&from, &to);
+
+        Transition {
+            from,
+            action,
+            reward,
+            to,
+            truncated: false,
+        }
+    }
+
+    fn is_terminal(&self) -> bool { self.goals.contains_key(&self.loc) }
+
+    fn state_index(&self) -> Option<usize> { Some(self.loc) }
+
+    fn reward(&self, _: &Observation<usize>, to: &Observation<usize>) -> f64 {
+        match *to {
+            Observation::Terminal(ref s) => self.goals.get(s).cloned().unwrap_or(REWARD_STEP),
+            _ => REWARD_STEP,
+        }
+    }
+
+    fn state_space(&self) -> Ordinal { Ordinal::new(self.width * self.height) }
+
+    fn action_space(&self) -> Ordinal { Ordinal::new(4) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{Domain, Observation};
+
+    #[test]
+    fn test_initial_observation() {
+        let m = GridWorld::new(3, 3);
+
+        match m.emit() {
+            Observation::Full(state) => assert_eq!(state, 0),
+            _ => panic!("Should yield a fully observable state."),
+        }
+
+        let m = GridWorld::new(3, 3).with_start((1, 2));
+
+        match m.emit() {
+            Observation::Full(state) => assert_eq!(state, 7),
+            _ => panic!("Should yield a fully observable state."),
+        }
+    }
+
+    #[test]
+    fn test_moves() {
+        let mut m = GridWorld::new(3, 3).with_start((1, 1));
+
+        assert_eq!(*m.step(UP).to.state(), 1);
+        assert_eq!(*m.step(DOWN).to.state(), 4);
+        assert_eq!(*m.step(LEFT).to.state(), 3);
+        assert_eq!(*m.step(RIGHT).to.state(), 4);
+    }
+
+    #[test]
+    fn test_edges() {
+        let mut m = GridWorld::new(3, 3);
+
+        assert_eq!(*m.step(UP).to.state(), 0);
+        assert_eq!(*m.step(LEFT).to.state(), 0);
+
+        let mut m = GridWorld::new(3, 3).with_start((2, 2));
+
+        assert_eq!(*m.step(DOWN).to.state(), 8);
+        assert_eq!(*m.step(RIGHT).to.state(), 8);
+    }
+
+    #[test]
+    fn test_wall_bouncing() {
+        let mut m = GridWorld::new(3, 3).with_wall((1, 0)).with_wall((0, 1));
+
+        for a in 0..4 {
+            let t = m.step(a);
+
+            assert_eq!(*t.to.state(), 0);
+            assert_eq!(t.reward, REWARD_STEP);
+        }
+    }
+
+    #[test]
+    fn test_goal() {
+        let mut m = GridWorld::new(3, 1).with_goal((2, 0), 10.0);
+
+        let t = m.step(RIGHT);
+        assert!(!t.terminated());
+        assert_eq!(t.reward, REWARD_STEP);
+
+        let t = m.step(RIGHT);
+        assert!(t.terminated());
+        assert_eq!(t.reward, 10.0);
+        assert!(m.is_terminal());
+    }
+
+    #[test]
+    fn test_slip() {
+        // With certain slipping, moving right is always redirected up or down:
+        let mut m = GridWorld::new(5, 5).with_slip(1.0).with_seed(0);
+
+        for _ in 0..100 {
+            let t = m.step(RIGHT);
+            let (x, _) = m.coords(*t.to.state());
+
+            assert_eq!(x, 0);
+        }
+
+        // Identical seeds yield identical trajectories:
+        let mut m1 = GridWorld::new(5, 5).with_slip(0.5).with_seed(1);
+        let mut m2 = GridWorld::new(5, 5).with_slip(0.5).with_seed(1);
+
+        for i in 0..100 {
+            assert_eq!(m1.step(i % 4).to.state(), m2.step(i % 4).to.state());
+        }
+    }
+
+    #[test]
+    fn test_state_index() {
+        let mut m = GridWorld::new(4, 3).with_start((1, 1)).with_goal((3, 2), 1.0);
+
+        assert_eq!(m.state_index(), Some(*m.emit().state()));
+
+        for &a in [RIGHT, DOWN, LEFT, UP, RIGHT, RIGHT, DOWN].iter() {
+            let t = m.step(a);
+
+            assert_eq!(m.state_index(), Some(*t.to.state()));
+            assert_eq!(m.state_index(), Some(*m.emit().state()));
+        }
+    }
+}
@@ -151,6 +151,7 @@ impl Domain for HIVTreatment {
             action,
             reward,
             to,
+            truncated: false,
         }
     }
 
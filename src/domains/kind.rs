@@ -0,0 +1,94 @@
+use crate::geometry::{continuous::Interval, discrete::Ordinal, product::LinearSpace};
+use super::{Acrobot, CartPole, Domain, HIVTreatment, MountainCar};
+
+/// Boxed domain with a continuous state vector and a finite set of actions.
+pub type BoxedDomain = Box<Domain<StateSpace = LinearSpace<Interval>, ActionSpace = Ordinal>>;
+
+/// Runtime selector for the benchmark domains sharing the `BoxedDomain`
+/// interface.
+///
+/// This allows experiments to choose a domain by name, e.g. from a config
+/// file or the command line, rather than hard-coding its type:
+///
+/// ```ignore
+/// let kind = DomainKind::from_name("cart_pole").unwrap();
+/// let domain_factory = Box::new(move || kind.build());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainKind {
+    MountainCar,
+    CartPole,
+    Acrobot,
+    HIVTreatment,
+}
+
+impl DomainKind {
+    /// All selectable domains, in declaration order.
+    pub const ALL: [DomainKind; 4] = [
+        DomainKind::MountainCar,
+        DomainKind::CartPole,
+        DomainKind::Acrobot,
+        DomainKind::HIVTreatment,
+    ];
+
+    /// Return the kind with the given `name`, if any; see `DomainKind::name`.
+    pub fn from_name(name: &str) -> Option<DomainKind> {
+        DomainKind::ALL.iter().cloned().find(|kind| kind.name() == name)
+    }
+
+    /// Return the snake case name of the domain.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DomainKind::MountainCar => "mountain_car",
+            DomainKind::CartPole => "cart_pole",
+            DomainKind::Acrobot => "acrobot",
+            DomainKind::HIVTreatment => "hiv_treatment",
+        }
+    }
+
+    /// Construct the default instance of the domain.
+    pub fn build(&self) -> BoxedDomain {
+        match self {
+            DomainKind::MountainCar => Box::new(MountainCar::default()),
+            DomainKind::CartPole => Box::new(CartPole::default()),
+            DomainKind::Acrobot => Box::new(Acrobot::default()),
+            DomainKind::HIVTreatment => Box::new(HIVTreatment::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Space;
+
+    #[test]
+    fn test_build() {
+        let domain = DomainKind::MountainCar.build();
+        let expected = MountainCar::default();
+
+        assert_eq!(domain.emit().state(), expected.emit().state());
+        assert_eq!(domain.state_space().dim(), expected.state_space().dim());
+        assert_eq!(domain.spec(), expected.spec());
+    }
+
+    #[test]
+    fn test_from_name() {
+        for kind in DomainKind::ALL.iter() {
+            assert_eq!(DomainKind::from_name(kind.name()), Some(*kind));
+        }
+
+        assert_eq!(DomainKind::from_name("mountain car"), None);
+    }
+
+    #[test]
+    fn test_boxed_step() {
+        let mut domain = DomainKind::Acrobot.build();
+        let mut expected = Acrobot::default();
+
+        let t = domain.step(2);
+
+        assert_eq!(t.action, 2);
+        assert_eq!(t.to.state(), expected.step(2).to.state());
+    }
+}
@@ -58,6 +58,16 @@ impl<S> Observation<S> {
             _ => false,
         }
     }
+
+    /// Apply a closure to the state values, preserving the kind of
+    /// observation.
+    pub fn map<T>(self, f: impl FnOnce(S) -> T) -> Observation<T> {
+        match self {
+            Observation::Full(state) => Observation::Full(f(state)),
+            Observation::Partial(state) => Observation::Partial(f(state)),
+            Observation::Terminal(state) => Observation::Terminal(f(state)),
+        }
+    }
 }
 
 /// Container class for data associated with a domain transition.
@@ -74,6 +84,10 @@ pub struct Transition<S, A> {
 
     /// State transitioned _to_, `s'`.
     pub to: Observation<S>,
+
+    /// True if `to` is only terminal because the episode was cut short, e.g.
+    /// by a `TimeLimit`, rather than the underlying problem having ended.
+    pub truncated: bool,
 }
 
 impl<S, A> Transition<S, A> {
@@ -87,11 +101,40 @@ impl<S, A> Transition<S, A> {
         (f(self.from.state()), f(self.to.state()))
     }
 
-    /// Returns true if the transition ends in a terminal state.
+    /// Returns true if the transition ends in a true terminal state, from which
+    /// no further reward can be obtained.
+    ///
+    /// Transitions that end the episode only due to truncation return false,
+    /// such that learners continue to bootstrap from `to`; see `ended`.
     pub fn terminated(&self) -> bool {
+        self.to.is_terminal() && !self.truncated
+    }
+
+    /// Returns true if the transition ends the episode, either by termination
+    /// or truncation.
+    pub fn ended(&self) -> bool {
         self.to.is_terminal()
     }
 
+    /// Mark the transition as ending the episode by truncation, converting `to`
+    /// into a terminal observation, unless it is already terminal.
+    pub fn into_truncated(mut self) -> Self {
+        if !self.to.is_terminal() {
+            self.to = match self.to {
+                Observation::Full(s) | Observation::Partial(s) => Observation::Terminal(s),
+                terminal => terminal,
+            };
+            self.truncated = true;
+        }
+
+        self
+    }
+
+    /// Returns true if either the `from` or `to` state was only partially observed.
+    pub fn partially_observed(&self) -> bool {
+        self.from.is_partial() || self.to.is_partial()
+    }
+
     /// Replace the action associated with this transition and return a new instance.
     pub fn replace_action<T>(self, action: T) -> Transition<S, T> {
         Transition {
@@ -99,6 +142,7 @@ impl<S, A> Transition<S, A> {
             action: action,
             reward: self.reward,
             to: self.to,
+            truncated: self.truncated,
         }
     }
 
@@ -121,6 +165,19 @@ impl_into!(Transition<S, isize> => Transition<S, ()>);
 impl_into!(Transition<S, f32> => Transition<S, ()>);
 impl_into!(Transition<S, f64> => Transition<S, ()>);
 
+/// Restoration of a domain's initial state, as used by `Domain::reset`.
+///
+/// Any domain implementing `Default` is rebuilt from `Self::default()`, whereas
+/// the domain wrappers reset their own state along with that of the domain
+/// they wrap.
+pub trait Reset {
+    fn reset_state(&mut self);
+}
+
+impl<D: Default> Reset for D {
+    fn reset_state(&mut self) { *self = D::default(); }
+}
+
 /// An interface for constructing reinforcement learning problem domains.
 pub trait Domain {
     /// State space representation type class.
@@ -141,6 +198,26 @@ pub trait Domain {
     /// Returns true if the current state is terminal.
     fn is_terminal(&self) -> bool;
 
+    /// Return the integer index of the current state, if the state space is
+    /// discrete.
+    ///
+    /// This allows tabular methods to key on a `usize` regardless of how
+    /// observations are represented. The default implementation returns
+    /// `None`.
+    fn state_index(&self) -> Option<usize> { None }
+
+    /// Restore the environment to its initial state and emit the resulting
+    /// observation.
+    ///
+    /// The default implementation defers to `Reset::reset_state`, which for
+    /// most domains replaces the domain with `Self::default()`.
+    fn reset(&mut self) -> Observation<<Self::StateSpace as Space>::Value>
+    where Self: Reset + Sized {
+        self.reset_state();
+
+        self.emit()
+    }
+
     /// Compute the reward associated with a transition from one state to
     /// another.
     fn reward(
@@ -155,20 +232,135 @@ pub trait Domain {
     /// Returns an instance of the action space type class.
     fn action_space(&self) -> Self::ActionSpace;
 
+    /// Return the lower and upper bounds on the reward, where `None` denotes
+    /// an unbounded side.
+    ///
+    /// The default implementation is unbounded in both directions.
+    fn reward_range(&self) -> (Option<f64>, Option<f64>) { (None, None) }
+
+    /// Return a serializable description of the state space, action space and
+    /// reward range of the domain.
+    fn spec(&self) -> DomainSpec
+    where
+        Self::StateSpace: Specify,
+        Self::ActionSpace: Specify,
+    {
+        DomainSpec {
+            state_space: self.state_space().spec(),
+            action_space: self.action_space().spec(),
+            reward_range: self.reward_range(),
+        }
+    }
+
     /// Render environment
     fn render(&self, _ctx: &mut ggez::Context) {}
 }
 
+/// Boxed domains forward to the domain they contain, such that trait objects
+/// (e.g. those built by `DomainKind`) can be used wherever a `Domain` is
+/// expected.
+impl<D: Domain + ?Sized> Domain for Box<D> {
+    type StateSpace = D::StateSpace;
+    type ActionSpace = D::ActionSpace;
+
+    fn emit(&self) -> Observation<<Self::StateSpace as Space>::Value> { (**self).emit() }
+
+    fn step(
+        &mut self,
+        a: <Self::ActionSpace as Space>::Value,
+    ) -> Transition<<Self::StateSpace as Space>::Value, <Self::ActionSpace as Space>::Value>
+    {
+        (**self).step(a)
+    }
+
+    fn is_terminal(&self) -> bool { (**self).is_terminal() }
+
+    fn state_index(&self) -> Option<usize> { (**self).state_index() }
+
+    fn reward(
+        &self,
+        from: &Observation<<Self::StateSpace as Space>::Value>,
+        to: &Observation<<Self::StateSpace as Space>::Value>,
+    ) -> f64
+    {
+        (**self).reward(from, to)
+    }
+
+    fn state_space(&self) -> Self::StateSpace { (**self).state_space() }
+
+    fn action_space(&self) -> Self::ActionSpace { (**self).action_space() }
+
+    fn reward_range(&self) -> (Option<f64>, Option<f64>) { (**self).reward_range() }
+
+    fn render(&self, ctx: &mut ggez::Context) { (**self).render(ctx) }
+}
+
 mod ode;
 use self::ode::*;
 
 mod grid_world;
 
+import_all!(clamp);
+import_all!(spec);
+import_all!(time_limit);
+import_all!(wrappers);
+import_all!(frame_stack);
+
 import_all!(mountain_car);
 import_all!(cart_pole);
 import_all!(acrobot);
+import_all!(pendulum);
+import_all!(reacher);
 import_all!(hiv);
 import_all!(cliff_walk);
+import_all!(cliff_walking);
+import_all!(grid);
+import_all!(windy_grid_world);
+import_all!(random_walk);
+import_all!(two_state_mdp);
+
+import_all!(kind);
 
 #[cfg(feature = "openai")]
 import_all!(openai);
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{Observation, Transition};
+
+    /// Construct a non-truncated transition from a fully observed state.
+    pub fn transition<S, A>(from: S, action: A, reward: f64, to: Observation<S>) -> Transition<S, A> {
+        Transition {
+            from: Observation::Full(from),
+            action,
+            reward,
+            to,
+            truncated: false,
+        }
+    }
+
+    /// Two episodes on a four-state chain with two actions, the second of which
+    /// revisits states via both actions before terminating.
+    pub fn chain_transitions() -> Vec<Transition<usize, usize>> {
+        vec![
+            transition(0, 1, 0.0, Observation::Full(1)),
+            transition(1, 0, -1.0, Observation::Full(2)),
+            transition(2, 1, 1.0, Observation::Terminal(3)),
+            transition(0, 1, 0.0, Observation::Full(1)),
+            transition(1, 1, 0.5, Observation::Full(2)),
+            transition(2, 0, 0.0, Observation::Full(1)),
+            transition(1, 0, -1.0, Observation::Full(2)),
+            transition(2, 1, 1.0, Observation::Terminal(3)),
+        ]
+    }
+
+    /// A single episode along a corridor of `length` states, taking action 0
+    /// throughout and rewarded only upon reaching the end.
+    pub fn corridor(length: usize) -> Vec<Transition<usize, usize>> {
+        (0..length - 1).map(|s| {
+            let to = if s + 2 == length { Observation::Terminal(s + 1) } else { Observation::Full(s + 1) };
+
+            transition(s, 0, if s + 2 == length { 1.0 } else { 0.0 }, to)
+        }).collect()
+    }
+}
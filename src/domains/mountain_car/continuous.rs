@@ -1,6 +1,5 @@
-use crate::domains::{Domain, Observation, Transition};
+use crate::domains::{Clamp, Domain, Observation, Transition};
 use crate::geometry::{
-    Surjection,
     Vector,
     continuous::Interval,
     product::LinearSpace,
@@ -23,11 +22,15 @@ const REWARD_GOAL: f64 = 0.0;
 const MIN_ACTION: f64 = -1.0;
 const MAX_ACTION: f64 = 1.0;
 
+const CONTROL_COST: f64 = 0.1;
+const REWARD_BONUS: f64 = 100.0;
+
 pub struct ContinuousMountainCar {
     x: f64,
     v: f64,
 
     action_space: Interval,
+    gym_rewards: bool,
 }
 
 impl ContinuousMountainCar {
@@ -35,14 +38,27 @@ impl ContinuousMountainCar {
         ContinuousMountainCar {
             x, v,
             action_space: Interval::bounded(MIN_ACTION, MAX_ACTION),
+            gym_rewards: false,
+        }
+    }
+
+    /// Construct the variant matching Gym's `MountainCarContinuous-v0`.
+    ///
+    /// The dynamics are unchanged, but rather than a constant penalty per step,
+    /// each step incurs a quadratic control cost of `0.1 * a^2`, and reaching
+    /// the goal at the top of the rightmost hill yields a bonus of 100. Since
+    /// `Domain::reward` has no access to the action, the control cost is
+    /// applied in `step`.
+    pub fn gym() -> ContinuousMountainCar {
+        ContinuousMountainCar {
+            gym_rewards: true,
+            ..ContinuousMountainCar::default()
         }
     }
 
     fn dv(x: f64, a: f64) -> f64 { FORCE_CAR * a + FORCE_G * (HILL_FREQ * x).cos() }
 
     fn update_state(&mut self, a: f64) {
-        let a = self.action_space.map(a);
-
         self.v = clip!(V_MIN, self.v + Self::dv(self.x, a), V_MAX);
         self.x = clip!(X_MIN, self.x + self.v, X_MAX);
     }
@@ -68,16 +84,22 @@ impl Domain for ContinuousMountainCar {
 
     fn step(&mut self, action: f64) -> Transition<Vector<f64>, f64> {
         let from = self.emit();
+        let a = self.action_space.clamp(action);
 
-        self.update_state(action);
+        self.update_state(a);
         let to = self.emit();
-        let reward = self.reward(&from, &to);
+        let reward = if self.gym_rewards {
+            self.reward(&from, &to) - CONTROL_COST * a * a
+        } else {
+            self.reward(&from, &to)
+        };
 
         Transition {
             from,
             action,
             reward,
             to,
+            truncated: false,
         }
     }
 
@@ -85,7 +107,9 @@ impl Domain for ContinuousMountainCar {
 
     fn reward(&self, _: &Observation<Vector<f64>>, to: &Observation<Vector<f64>>) -> f64 {
         match *to {
+            Observation::Terminal(_) if self.gym_rewards => REWARD_BONUS,
             Observation::Terminal(_) => REWARD_GOAL,
+            _ if self.gym_rewards => 0.0,
             _ => REWARD_STEP,
         }
     }
@@ -138,4 +162,35 @@ mod tests {
         assert_eq!(mc.reward(&s, &s), REWARD_STEP);
         assert_eq!(mc.reward(&s, &ns), REWARD_GOAL);
     }
+
+    #[test]
+    fn test_gym_zero_force() {
+        let mut mc = ContinuousMountainCar::gym();
+        let t = mc.step(0.0);
+
+        let ns = t.to.state();
+        let dv = FORCE_G * (HILL_FREQ * -0.5).cos();
+
+        assert!((ns[1] - dv).abs() < 1e-12);
+        assert!((ns[0] - (-0.5 + dv)).abs() < 1e-12);
+        assert_eq!(t.reward, 0.0);
+    }
+
+    #[test]
+    fn test_gym_reward() {
+        let mut mc = ContinuousMountainCar::gym();
+
+        let t = mc.step(2.0);
+        assert_eq!(t.action, 2.0);
+        assert!((t.reward + CONTROL_COST).abs() < 1e-12);
+
+        let mut mc = ContinuousMountainCar {
+            gym_rewards: true,
+            ..ContinuousMountainCar::new(X_MAX - 0.01, V_MAX)
+        };
+        let t = mc.step(0.5);
+
+        assert!(t.terminated());
+        assert!((t.reward - (REWARD_BONUS - CONTROL_COST * 0.25)).abs() < 1e-12);
+    }
 }
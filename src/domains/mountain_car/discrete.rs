@@ -48,13 +48,28 @@ const ALL_ACTIONS: [f64; 3] = [-1.0, 0.0, 1.0];
 /// replacing eligibility traces. Recent Advances in Reinforcement Learning,
 /// 123-158. - Sutton, R. S., & Barto, A. G. (1998). Reinforcement learning: An
 /// introduction (Vol. 1, No. 1). Cambridge: MIT press.
+/// - Ng, A. Y., Harada, D., & Russell, S. (1999). Policy invariance under
+/// reward transformations: Theory and application to reward shaping. In
+/// Proceedings of the 16th International Conference on Machine Learning, pp.
+/// 278-287.
 pub struct MountainCar {
     x: f64,
     v: f64,
+
+    step_reward: f64,
+    goal_reward: f64,
+    shaping: Option<f64>,
 }
 
 impl MountainCar {
-    fn new(x: f64, v: f64) -> MountainCar { MountainCar { x, v } }
+    fn new(x: f64, v: f64) -> MountainCar { MountainCarBuilder::default().build_at(x, v) }
+
+    pub fn builder() -> MountainCarBuilder { MountainCarBuilder::default() }
+
+    /// Shaping potential given by the height of the car; zero when terminal.
+    fn potential(x: f64) -> f64 {
+        if x >= X_MAX { 0.0 } else { (HILL_FREQ * x).sin() }
+    }
 
     fn dv(x: f64, a: f64) -> f64 { FORCE_CAR * a + FORCE_G * (HILL_FREQ * x).cos() }
 
@@ -70,6 +85,73 @@ impl Default for MountainCar {
     fn default() -> MountainCar { MountainCar::new(-0.5, 0.0) }
 }
 
+/// Builder for `MountainCar` domains with custom reward signals.
+///
+/// If shaping is enabled, the potential-based term `gamma * phi(s') - phi(s)`
+/// is added to every reward, where `phi(s) = sin(3 * x)` is the height of the
+/// car. This preserves the optimal policy with respect to the discount factor
+/// `gamma`.
+#[derive(Clone, Copy, Debug)]
+pub struct MountainCarBuilder {
+    step_reward: f64,
+    goal_reward: f64,
+
+    shaping: bool,
+    gamma: f64,
+}
+
+impl Default for MountainCarBuilder {
+    fn default() -> MountainCarBuilder {
+        MountainCarBuilder {
+            step_reward: REWARD_STEP,
+            goal_reward: REWARD_GOAL,
+
+            shaping: false,
+            gamma: 1.0,
+        }
+    }
+}
+
+impl MountainCarBuilder {
+    pub fn step_reward(mut self, r: f64) -> Self {
+        self.step_reward = r;
+
+        self
+    }
+
+    pub fn goal_reward(mut self, r: f64) -> Self {
+        self.goal_reward = r;
+
+        self
+    }
+
+    pub fn shaping(mut self, enabled: bool) -> Self {
+        self.shaping = enabled;
+
+        self
+    }
+
+    /// Set the discount factor used by the shaping term.
+    pub fn gamma(mut self, gamma: f64) -> Self {
+        self.gamma = gamma;
+
+        self
+    }
+
+    pub fn build(self) -> MountainCar { self.build_at(-0.5, 0.0) }
+
+    fn build_at(self, x: f64, v: f64) -> MountainCar {
+        MountainCar {
+            x,
+            v,
+
+            step_reward: self.step_reward,
+            goal_reward: self.goal_reward,
+            shaping: if self.shaping { Some(self.gamma) } else { None },
+        }
+    }
+}
+
 impl Domain for MountainCar {
     type StateSpace = LinearSpace<Interval>;
     type ActionSpace = Ordinal;
@@ -96,15 +178,32 @@ impl Domain for MountainCar {
             action,
             reward,
             to,
+            truncated: false,
         }
     }
 
     fn is_terminal(&self) -> bool { self.x >= X_MAX }
 
-    fn reward(&self, _: &Observation<Vector<f64>>, to: &Observation<Vector<f64>>) -> f64 {
-        match *to {
-            Observation::Terminal(_) => REWARD_GOAL,
-            _ => REWARD_STEP,
+    /// Return the car to the bottom of the valley, at rest, without discarding
+    /// the configured reward signal.
+    fn reset(&mut self) -> Observation<Vector<f64>> {
+        self.x = -0.5;
+        self.v = 0.0;
+
+        self.emit()
+    }
+
+    fn reward(&self, from: &Observation<Vector<f64>>, to: &Observation<Vector<f64>>) -> f64 {
+        let r = match *to {
+            Observation::Terminal(_) => self.goal_reward,
+            _ => self.step_reward,
+        };
+
+        match self.shaping {
+            Some(gamma) => {
+                r + gamma * Self::potential(to.state()[0]) - Self::potential(from.state()[0])
+            },
+            None => r,
         }
     }
 
@@ -113,12 +212,28 @@ impl Domain for MountainCar {
     }
 
     fn action_space(&self) -> Ordinal { Ordinal::new(3) }
+
+    /// Shaped rewards depend on the potential difference between states, so
+    /// the reward range is only bounded in the unshaped case.
+    fn reward_range(&self) -> (Option<f64>, Option<f64>) {
+        match self.shaping {
+            Some(_) => (None, None),
+            None => (
+                Some(self.step_reward.min(self.goal_reward)),
+                Some(self.step_reward.max(self.goal_reward)),
+            ),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domains::{Domain, Observation};
+    use crate::control::td::QLearning;
+    use crate::core::{make_shared, OnlineLearner};
+    use crate::domains::{Domain, Observation, SpaceSpec};
+    use crate::fa::{QFunction, Table};
+    use crate::policies::fixed::Random;
 
     #[test]
     fn test_initial_observation() {
@@ -133,6 +248,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_reset() {
+        let mut m = MountainCar::builder().step_reward(-2.0).build();
+
+        for _ in 0..10 {
+            m.step(2);
+        }
+
+        match m.reset() {
+            Observation::Full(ref state) => {
+                assert_eq!(state[0], -0.5);
+                assert_eq!(state[1], 0.0);
+            },
+            _ => panic!("Should yield a fully observable state."),
+        }
+
+        let s = m.emit();
+
+        assert_eq!(m.reward(&s, &s), -2.0);
+    }
+
     #[test]
     fn test_is_terminal() {
         assert!(!MountainCar::default().is_terminal());
@@ -156,4 +292,93 @@ mod tests {
         assert_eq!(mc.reward(&s, &s), REWARD_STEP);
         assert_eq!(mc.reward(&s, &ns), REWARD_GOAL);
     }
+
+    #[test]
+    fn test_custom_rewards() {
+        let mc = MountainCar::builder().step_reward(-2.0).goal_reward(10.0).build();
+
+        let s = mc.emit();
+        let ns = MountainCar::new(X_MAX, 0.0).emit();
+
+        assert_eq!(mc.reward(&s, &s), -2.0);
+        assert_eq!(mc.reward(&s, &ns), 10.0);
+    }
+
+    #[test]
+    fn test_spec() {
+        let spec = MountainCar::default().spec();
+
+        assert_eq!(spec.state_space, SpaceSpec::Box {
+            low: vec![Some(X_MIN), Some(V_MIN)],
+            high: vec![Some(X_MAX), Some(V_MAX)],
+        });
+        assert_eq!(spec.action_space, SpaceSpec::Discrete { n: 3 });
+        assert_eq!(spec.reward_range, (Some(REWARD_STEP), Some(REWARD_GOAL)));
+
+        let spec = MountainCar::builder().shaping(true).build().spec();
+
+        assert_eq!(spec.reward_range, (None, None));
+    }
+
+    #[test]
+    fn test_shaping() {
+        let gamma = 0.99;
+        let key = |s: &Vector<f64>| (s[0].to_bits(), s[1].to_bits());
+        let potential = |k: &(u64, u64)| MountainCar::potential(f64::from_bits(k.0));
+
+        // Identical trajectories that differ only in their rewards, keyed by the
+        // exact state so that they can be learned from with a table:
+        let trajectory = |shaping| -> Vec<Transition<(u64, u64), usize>> {
+            let mut mc = MountainCar::builder().shaping(shaping).gamma(gamma).build();
+
+            (0..30).map(|i| {
+                let t = mc.step(i % 3);
+
+                assert!(!t.terminated());
+
+                Transition {
+                    from: Observation::Full(key(t.from.state())),
+                    action: t.action,
+                    reward: t.reward,
+                    to: Observation::Full(key(t.to.state())),
+                    truncated: false,
+                }
+            }).collect()
+        };
+        let (plain, shaped) = (trajectory(false), trajectory(true));
+
+        // Starting the shaped learner from the unshaped initial values less the
+        // potential, its action values differ from the unshaped learner's by
+        // exactly the potential of each state after any number of updates:
+        let q_plain = make_shared(Table::vector_output(3));
+        let q_shaped = make_shared(Table::vector_output(3));
+
+        for t in shaped.iter() {
+            for s in [t.from.state(), t.to.state()].iter() {
+                q_shaped.borrow_mut().0.insert(**s, Vector::from_elem(3, -potential(s)));
+            }
+        }
+
+        let mut plain_agent = QLearning::new(q_plain.clone(), make_shared(Random::new(3)), 0.5, gamma);
+        let mut shaped_agent = QLearning::new(q_shaped.clone(), make_shared(Random::new(3)), 0.5, gamma);
+
+        for _ in 0..10 {
+            plain.iter().for_each(|t| plain_agent.handle_transition(t));
+            shaped.iter().for_each(|t| shaped_agent.handle_transition(t));
+        }
+
+        assert!(q_plain.evaluate_action(plain[0].from.state(), 0) < 0.0);
+
+        for (tp, ts) in plain.iter().zip(shaped.iter()) {
+            let s = tp.from.state();
+
+            assert_eq!(s, ts.from.state());
+
+            for a in 0..3 {
+                let expected = q_plain.evaluate_action(s, a) - potential(s);
+
+                assert!((q_shaped.evaluate_action(s, a) - expected).abs() < 1e-9);
+            }
+        }
+    }
 }
@@ -102,6 +102,7 @@ impl Domain for OpenAIGym {
             action: a,
             reward: self.last_reward,
             to: to,
+            truncated: false,
         }
     }
 
@@ -0,0 +1,185 @@
+use crate::consts::G;
+use crate::geometry::{
+    continuous::Interval,
+    product::LinearSpace,
+    Vector,
+};
+use std::f64::consts::PI;
+use super::{Clamp, Domain, Observation, Transition};
+
+const DT: f64 = 0.05;
+
+const MASS: f64 = 1.0;
+const LENGTH: f64 = 1.0;
+
+const MAX_SPEED: f64 = 8.0;
+const MAX_TORQUE: f64 = 2.0;
+
+/// Classic inverted pendulum swing-up domain.
+///
+/// A frictionless pendulum starts hanging downwards and must be swung up and
+/// balanced in the upright position. Unlike the discrete control domains, the
+/// action is a continuous torque applied at the pivot that is clamped to the
+/// interval [-2, 2]. The dynamics mirror those of the `Pendulum-v0`
+/// environment in OpenAI Gym.
+///
+/// # Technical details
+/// The **state** is represented by a `Vector` with components:
+///
+/// | Index | Name        | Min  | Max |
+/// | ----- | ----------- | ---- | --- |
+/// | 0     | cos(theta)  | -1.0 | 1.0 |
+/// | 1     | sin(theta)  | -1.0 | 1.0 |
+/// | 2     | dtheta      | -8.0 | 8.0 |
+///
+/// The reward is given by the negative quadratic cost `-(theta^2 + 0.1 *
+/// dtheta^2 + 0.001 * torque^2)`, where `theta` is normalised to [-pi, pi] and
+/// equals zero in the upright position.
+pub struct Pendulum {
+    theta: f64,
+    dtheta: f64,
+
+    torque: f64,
+}
+
+impl Pendulum {
+    fn new(theta: f64, dtheta: f64) -> Pendulum {
+        Pendulum {
+            theta,
+            dtheta,
+
+            torque: 0.0,
+        }
+    }
+
+    fn update_state(&mut self, a: f64) {
+        self.torque = self.action_space().clamp(a);
+
+        let ddtheta = -3.0 * G / (2.0 * LENGTH) * (self.theta + PI).sin()
+            + 3.0 / (MASS * LENGTH * LENGTH) * self.torque;
+
+        self.dtheta = clip!(-MAX_SPEED, self.dtheta + ddtheta * DT, MAX_SPEED);
+        self.theta = wrap!(-PI, self.theta + self.dtheta * DT, PI);
+    }
+}
+
+impl Default for Pendulum {
+    fn default() -> Pendulum { Pendulum::new(PI, 0.0) }
+}
+
+impl Domain for Pendulum {
+    type StateSpace = LinearSpace<Interval>;
+    type ActionSpace = Interval;
+
+    fn emit(&self) -> Observation<Vector<f64>> {
+        Observation::Full(Vector::from_vec(vec![
+            self.theta.cos(),
+            self.theta.sin(),
+            self.dtheta,
+        ]))
+    }
+
+    fn step(&mut self, action: f64) -> Transition<Vector<f64>, f64> {
+        let from = self.emit();
+
+        self.update_state(action);
+        let to = self.emit();
+        let reward = self.reward(&from, &to);
+
+        Transition {
+            from,
+            action,
+            reward,
+            to,
+            truncated: false,
+        }
+    }
+
+    fn is_terminal(&self) -> bool { false }
+
+    fn reward(&self, from: &Observation<Vector<f64>>, _: &Observation<Vector<f64>>) -> f64 {
+        let s = from.state();
+        let theta = s[1].atan2(s[0]);
+
+        -(theta * theta + 0.1 * s[2] * s[2] + 0.001 * self.torque * self.torque)
+    }
+
+    fn state_space(&self) -> Self::StateSpace {
+        LinearSpace::empty() + Interval::bounded(-1.0, 1.0) + Interval::bounded(-1.0, 1.0)
+            + Interval::bounded(-MAX_SPEED, MAX_SPEED)
+    }
+
+    fn action_space(&self) -> Interval { Interval::bounded(-MAX_TORQUE, MAX_TORQUE) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{Domain, Observation};
+
+    #[test]
+    fn test_initial_observation() {
+        let m = Pendulum::default();
+
+        match m.emit() {
+            Observation::Full(ref state) => {
+                assert!((state[0] + 1.0).abs() < 1e-7);
+                assert!(state[1].abs() < 1e-7);
+                assert_eq!(state[2], 0.0);
+            },
+            _ => panic!("Should yield a fully observable state."),
+        }
+    }
+
+    #[test]
+    fn test_is_terminal() {
+        assert!(!Pendulum::default().is_terminal());
+        assert!(!Pendulum::new(0.0, 0.0).is_terminal());
+        assert!(!Pendulum::new(0.0, MAX_SPEED).is_terminal());
+    }
+
+    #[test]
+    fn test_reward() {
+        let mut m = Pendulum::new(0.0, 0.0);
+
+        let t = m.step(0.0);
+        assert_eq!(t.reward, 0.0);
+
+        let mut m = Pendulum::new(0.5, 1.0);
+
+        let t = m.step(1.0);
+        assert!((t.reward + (0.25 + 0.1 + 0.001)).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_torque_clamping() {
+        let mut m1 = Pendulum::new(0.5, 1.0);
+        let mut m2 = Pendulum::new(0.5, 1.0);
+
+        let t1 = m1.step(10.0);
+        let t2 = m2.step(MAX_TORQUE);
+
+        assert_eq!(t1.action, 10.0);
+        assert_eq!(t1.to.state(), t2.to.state());
+        assert_eq!(t1.reward, t2.reward);
+        assert!((t1.reward + (0.25 + 0.1 + 0.001 * MAX_TORQUE * MAX_TORQUE)).abs() < 1e-7);
+
+        let mut m1 = Pendulum::new(0.5, 1.0);
+        let mut m2 = Pendulum::new(0.5, 1.0);
+
+        let t1 = m1.step(-10.0);
+        let t2 = m2.step(-MAX_TORQUE);
+
+        assert_eq!(t1.to.state(), t2.to.state());
+        assert_eq!(t1.reward, t2.reward);
+
+        let mut m1 = Pendulum::new(0.5, 1.0);
+        let mut m2 = Pendulum::new(0.5, 1.0);
+
+        let t1 = m1.step(::std::f64::NAN);
+        let t2 = m2.step(0.0);
+
+        assert_eq!(t1.to.state(), t2.to.state());
+        assert_eq!(t1.reward, t2.reward);
+    }
+}
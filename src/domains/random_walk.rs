@@ -0,0 +1,161 @@
+use crate::geometry::discrete::Ordinal;
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+use super::{Domain, Observation, Transition};
+
+/// Random walk on a chain of states, for evaluating prediction algorithms.
+///
+/// The chain consists of `n` non-terminal states, numbered `1..=n`, flanked by
+/// terminal states `0` and `n + 1`. The walk starts in the centre state and, at
+/// each step, moves left or right with equal probability regardless of the
+/// (single, dummy) action. Terminating on the right yields a reward of 1; all
+/// other transitions yield 0. The true value of state `i` under no discounting
+/// is therefore `i / (n + 1)`.
+///
+/// # References
+/// - Sutton, R. S. and Barto, A. G. (2017). Reinforcement Learning: An
+/// Introduction (2nd ed.). Manuscript in preparation.
+pub struct RandomWalk {
+    n_states: usize,
+    loc: usize,
+
+    rng: StdRng,
+}
+
+impl RandomWalk {
+    pub fn new(n_states: usize) -> RandomWalk {
+        assert!(n_states > 0, "A random walk requires at least one non-terminal state.");
+
+        RandomWalk {
+            n_states,
+            loc: n_states / 2 + 1,
+
+            rng: StdRng::from_rng(thread_rng()).unwrap(),
+        }
+    }
+
+    /// Seed the random number generator used to sample moves.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+
+        self
+    }
+
+    /// Return the true (undiscounted) value of each state, including the two
+    /// terminals.
+    pub fn true_values(&self) -> Vec<f64> {
+        let n = self.n_states + 1;
+
+        (0..=n).map(|i| if i == n { 0.0 } else { i as f64 / n as f64 }).collect()
+    }
+}
+
+impl Default for RandomWalk {
+    fn default() -> RandomWalk { RandomWalk::new(5) }
+}
+
+impl Domain for RandomWalk {
+    type StateSpace = Ordinal;
+    type ActionSpace = Ordinal;
+
+    fn emit(&self) -> Observation<usize> {
+        if self.is_terminal() {
+            Observation::Terminal(self.loc)
+        } else {
+            Observation::Full(self.loc)
+        }
+    }
+
+    fn step(&mut self, action: usize) -> Transition<usize, usize> {
+        let from = self.emit();
+
+        if self.rng.gen_bool(0.5) {
+            self.loc += 1;
+        } else {
+            self.loc -= 1;
+        }
+
+        let to = self.emit();
+        let reward = self.reward(&from, &to);
+
+        Transition {
+            from,
+            action,
+            reward,
+            to,
+            truncated: false,
+        }
+    }
+
+    fn is_terminal(&self) -> bool { self.loc == 0 || self.loc == self.n_states + 1 }
+
+    fn state_index(&self) -> Option<usize> { Some(self.loc) }
+
+    fn reward(&self, _: &Observation<usize>, to: &Observation<usize>) -> f64 {
+        match *to {
+            Observation::Terminal(s) if s > 0 => 1.0,
+            _ => 0.0,
+        }
+    }
+
+    fn state_space(&self) -> Ordinal { Ordinal::new(self.n_states + 2) }
+
+    fn action_space(&self) -> Ordinal { Ordinal::new(1) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{Domain, Observation};
+
+    #[test]
+    fn test_initial_observation() {
+        match RandomWalk::new(5).emit() {
+            Observation::Full(state) => assert_eq!(state, 3),
+            _ => panic!("Should yield a fully observable state."),
+        }
+    }
+
+    #[test]
+    fn test_episodes() {
+        let (mut left, mut right) = (0, 0);
+
+        for seed in 0..100 {
+            let mut m = RandomWalk::new(5).with_seed(seed);
+            let mut s = *m.emit().state();
+
+            loop {
+                let t = m.step(0);
+                let ns = *t.to.state();
+
+                assert_eq!((ns as isize - s as isize).abs(), 1);
+
+                if t.terminated() {
+                    match ns {
+                        0 => { left += 1; assert_eq!(t.reward, 0.0); },
+                        6 => { right += 1; assert_eq!(t.reward, 1.0); },
+                        _ => panic!("State {} should not be terminal.", ns),
+                    }
+
+                    break;
+                }
+
+                assert_eq!(t.reward, 0.0);
+                s = ns;
+            }
+        }
+
+        assert!(left > 0 && right > 0);
+    }
+
+    #[test]
+    fn test_true_values() {
+        let values = RandomWalk::new(5).true_values();
+        let expected = [0.0, 1.0 / 6.0, 2.0 / 6.0, 3.0 / 6.0, 4.0 / 6.0, 5.0 / 6.0, 0.0];
+
+        assert_eq!(values.len(), 7);
+
+        for (v, e) in values.iter().zip(expected.iter()) {
+            assert!((v - e).abs() < 1e-10);
+        }
+    }
+}
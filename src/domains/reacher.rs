@@ -0,0 +1,198 @@
+use crate::geometry::{
+    continuous::Interval,
+    product::LinearSpace,
+    Vector,
+};
+use super::{Clamp, Domain, Observation, Transition};
+
+const DT: f64 = 0.1;
+
+const POS_MIN: f64 = -1.0;
+const POS_MAX: f64 = 1.0;
+
+const MAX_SPEED: f64 = 1.0;
+const MAX_FORCE: f64 = 1.0;
+
+const TOLERANCE: f64 = 0.05;
+
+/// Planar point-mass reaching task with a two-dimensional continuous action.
+///
+/// A unit mass starts at rest at the origin of the square arena `[-1, 1]^2`
+/// and must be driven to within a distance of 0.05 of a target position,
+/// `(0.5, 0.5)` by default. Each action is a force vector whose components are
+/// clamped independently to `[-1, 1]` before being applied; as in the other
+/// continuous domains, the transition reports the action as given.
+///
+/// # Technical details
+/// The **state** is represented by a `Vector` with components:
+///
+/// | Index | Name | Min  | Max |
+/// | ----- | ---- | ---- | --- |
+/// | 0     | x    | -1.0 | 1.0 |
+/// | 1     | y    | -1.0 | 1.0 |
+/// | 2     | dx   | -1.0 | 1.0 |
+/// | 3     | dy   | -1.0 | 1.0 |
+///
+/// The reward is the negative Euclidean distance from the target after each
+/// step, and the episode terminates once the target has been reached.
+pub struct Reacher {
+    position: [f64; 2],
+    velocity: [f64; 2],
+
+    target: [f64; 2],
+}
+
+impl Reacher {
+    fn new(position: [f64; 2], velocity: [f64; 2]) -> Reacher {
+        Reacher {
+            position,
+            velocity,
+
+            target: [0.5, 0.5],
+        }
+    }
+
+    /// Move the target to `(x, y)`, clipped to the arena.
+    pub fn with_target(mut self, x: f64, y: f64) -> Reacher {
+        self.target = [clip!(POS_MIN, x, POS_MAX), clip!(POS_MIN, y, POS_MAX)];
+
+        self
+    }
+
+    fn distance(&self, x: f64, y: f64) -> f64 {
+        (x - self.target[0]).hypot(y - self.target[1])
+    }
+
+    fn update_state(&mut self, force: &Vector<f64>) {
+        for i in 0..2 {
+            self.velocity[i] = clip!(-MAX_SPEED, self.velocity[i] + force[i] * DT, MAX_SPEED);
+            self.position[i] = clip!(POS_MIN, self.position[i] + self.velocity[i] * DT, POS_MAX);
+        }
+    }
+}
+
+impl Default for Reacher {
+    fn default() -> Reacher { Reacher::new([0.0, 0.0], [0.0, 0.0]) }
+}
+
+impl Domain for Reacher {
+    type StateSpace = LinearSpace<Interval>;
+    type ActionSpace = LinearSpace<Interval>;
+
+    fn emit(&self) -> Observation<Vector<f64>> {
+        let s = Vector::from_vec(vec![
+            self.position[0],
+            self.position[1],
+            self.velocity[0],
+            self.velocity[1],
+        ]);
+
+        if self.is_terminal() {
+            Observation::Terminal(s)
+        } else {
+            Observation::Full(s)
+        }
+    }
+
+    fn step(&mut self, action: Vector<f64>) -> Transition<Vector<f64>, Vector<f64>> {
+        let from = self.emit();
+        let force = self.action_space().clamp(action.clone());
+
+        self.update_state(&force);
+        let to = self.emit();
+        let reward = self.reward(&from, &to);
+
+        Transition {
+            from,
+            action,
+            reward,
+            to,
+            truncated: false,
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.distance(self.position[0], self.position[1]) <= TOLERANCE
+    }
+
+    fn reward(&self, _: &Observation<Vector<f64>>, to: &Observation<Vector<f64>>) -> f64 {
+        let s = to.state();
+
+        -self.distance(s[0], s[1])
+    }
+
+    fn state_space(&self) -> Self::StateSpace {
+        LinearSpace::empty()
+            + Interval::bounded(POS_MIN, POS_MAX)
+            + Interval::bounded(POS_MIN, POS_MAX)
+            + Interval::bounded(-MAX_SPEED, MAX_SPEED)
+            + Interval::bounded(-MAX_SPEED, MAX_SPEED)
+    }
+
+    fn action_space(&self) -> Self::ActionSpace {
+        LinearSpace::empty()
+            + Interval::bounded(-MAX_FORCE, MAX_FORCE)
+            + Interval::bounded(-MAX_FORCE, MAX_FORCE)
+    }
+
+    fn reward_range(&self) -> (Option<f64>, Option<f64>) {
+        let span = POS_MAX - POS_MIN;
+
+        (Some(-span.hypot(span)), Some(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{Domain, Observation};
+    use crate::geometry::Space;
+
+    #[test]
+    fn test_initial_observation() {
+        let m = Reacher::default();
+
+        match m.emit() {
+            Observation::Full(ref state) => {
+                assert_eq!(state, &Vector::from_vec(vec![0.0, 0.0, 0.0, 0.0]));
+            },
+            _ => panic!("Should yield a fully observable state."),
+        }
+    }
+
+    #[test]
+    fn test_clamped_action() {
+        let mut m = Reacher::default();
+
+        assert_eq!(m.action_space().dim(), 2);
+
+        let t = m.step(Vector::from_vec(vec![5.0, -0.5]));
+
+        // The action is reported as given, but only the first component, which
+        // lies outside [-1, 1], is clamped when applied:
+        assert_eq!(t.action, Vector::from_vec(vec![5.0, -0.5]));
+        assert_eq!(t.from.state(), &Vector::from_vec(vec![0.0, 0.0, 0.0, 0.0]));
+
+        let s = t.to.state();
+
+        assert!((s[2] - DT).abs() < 1e-10);
+        assert!((s[3] + 0.5 * DT).abs() < 1e-10);
+        assert!((s[0] - DT * DT).abs() < 1e-10);
+        assert!((s[1] + 0.5 * DT * DT).abs() < 1e-10);
+
+        assert!((t.reward + m.distance(s[0], s[1])).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_reach_target() {
+        let m = Reacher::default().with_target(0.0, 0.0);
+
+        assert!(m.is_terminal());
+
+        let mut m = Reacher::default().with_target(DT * DT, -DT * DT);
+        let t = m.step(Vector::from_vec(vec![1.0, -1.0]));
+
+        assert!(t.terminated());
+        assert!(t.reward.abs() < 1e-10);
+    }
+}
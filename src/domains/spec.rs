@@ -0,0 +1,85 @@
+use crate::geometry::{
+    continuous::Interval,
+    discrete::Ordinal,
+    product::LinearSpace,
+    BoundedSpace,
+    Card,
+    Space,
+};
+
+/// Serializable description of a space, in the style of OpenAI Gym.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SpaceSpec {
+    /// A finite set of `n` values, `{0, ..., n - 1}`.
+    Discrete { n: usize },
+
+    /// A product of real intervals, where `None` denotes an unbounded side.
+    Box {
+        low: Vec<Option<f64>>,
+        high: Vec<Option<f64>>,
+    },
+}
+
+/// Serializable description of a domain, as returned by `Domain::spec`.
+///
+/// This exposes the shapes of the state and action spaces, and the range of
+/// rewards, to external tooling without depending on the `spaces` types.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DomainSpec {
+    pub state_space: SpaceSpec,
+    pub action_space: SpaceSpec,
+
+    /// Lower and upper bounds on the reward, where `None` denotes an
+    /// unbounded side.
+    pub reward_range: (Option<f64>, Option<f64>),
+}
+
+/// Trait for spaces that can be described by a `SpaceSpec`.
+pub trait Specify {
+    fn spec(&self) -> SpaceSpec;
+}
+
+impl Specify for Ordinal {
+    fn spec(&self) -> SpaceSpec {
+        match self.card() {
+            Card::Finite(n) => SpaceSpec::Discrete { n },
+            _ => unreachable!("Ordinal spaces are always finite."),
+        }
+    }
+}
+
+impl Specify for Interval {
+    fn spec(&self) -> SpaceSpec {
+        SpaceSpec::Box {
+            low: vec![self.inf()],
+            high: vec![self.sup()],
+        }
+    }
+}
+
+impl Specify for LinearSpace<Interval> {
+    fn spec(&self) -> SpaceSpec {
+        SpaceSpec::Box {
+            low: self.iter().map(|d| d.inf()).collect(),
+            high: self.iter().map(|d| d.sup()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ordinal() {
+        assert_eq!(Ordinal::new(4).spec(), SpaceSpec::Discrete { n: 4 });
+    }
+
+    #[test]
+    fn test_unbounded() {
+        assert_eq!(Interval::left_bounded(1.0).spec(), SpaceSpec::Box {
+            low: vec![Some(1.0)],
+            high: vec![None],
+        });
+    }
+}
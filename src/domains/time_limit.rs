@@ -0,0 +1,142 @@
+use crate::geometry::Space;
+use super::{Domain, Observation, Reset, Transition};
+
+/// Wrapper that imposes a maximum episode length on a domain.
+///
+/// Once `max_steps` transitions have been taken, the wrapper emits terminal
+/// observations regardless of the state of the underlying domain. Transitions
+/// that end an episode in this way are flagged as `truncated`, such that
+/// learners may bootstrap from them rather than treating them as true
+/// terminal states.
+pub struct TimeLimit<D> {
+    domain: D,
+
+    steps: usize,
+    max_steps: usize,
+}
+
+impl<D> TimeLimit<D> {
+    pub fn new(domain: D, max_steps: usize) -> TimeLimit<D> {
+        TimeLimit {
+            domain,
+
+            steps: 0,
+            max_steps,
+        }
+    }
+
+    /// Number of steps taken so far in the current episode.
+    pub fn steps(&self) -> usize { self.steps }
+
+    /// Maximum number of steps per episode.
+    pub fn max_steps(&self) -> usize { self.max_steps }
+
+    /// Returns true if the step budget has been exhausted.
+    pub fn is_truncated(&self) -> bool { self.steps >= self.max_steps }
+
+    /// Return a reference to the wrapped domain.
+    pub fn inner(&self) -> &D { &self.domain }
+
+    /// Consume the wrapper and return the underlying domain.
+    pub fn into_inner(self) -> D { self.domain }
+}
+
+fn into_terminal<S>(obs: Observation<S>) -> Observation<S> {
+    match obs {
+        Observation::Full(s) | Observation::Partial(s) => Observation::Terminal(s),
+        terminal => terminal,
+    }
+}
+
+impl<D: Domain + Reset> Reset for TimeLimit<D> {
+    fn reset_state(&mut self) {
+        self.domain.reset();
+        self.steps = 0;
+    }
+}
+
+impl<D: Domain> Domain for TimeLimit<D> {
+    type StateSpace = D::StateSpace;
+    type ActionSpace = D::ActionSpace;
+
+    fn emit(&self) -> Observation<<Self::StateSpace as Space>::Value> {
+        let obs = self.domain.emit();
+
+        if self.is_truncated() { into_terminal(obs) } else { obs }
+    }
+
+    fn step(
+        &mut self,
+        a: <Self::ActionSpace as Space>::Value,
+    ) -> Transition<<Self::StateSpace as Space>::Value, <Self::ActionSpace as Space>::Value>
+    {
+        let t = self.domain.step(a);
+
+        self.steps += 1;
+
+        if self.is_truncated() { t.into_truncated() } else { t }
+    }
+
+    fn is_terminal(&self) -> bool { self.is_truncated() || self.domain.is_terminal() }
+
+    fn state_index(&self) -> Option<usize> { self.domain.state_index() }
+
+    fn reward(
+        &self,
+        from: &Observation<<Self::StateSpace as Space>::Value>,
+        to: &Observation<<Self::StateSpace as Space>::Value>,
+    ) -> f64
+    {
+        self.domain.reward(from, to)
+    }
+
+    fn state_space(&self) -> Self::StateSpace { self.domain.state_space() }
+
+    fn action_space(&self) -> Self::ActionSpace { self.domain.action_space() }
+
+    fn reward_range(&self) -> (Option<f64>, Option<f64>) { self.domain.reward_range() }
+
+    fn render(&self, ctx: &mut ggez::Context) { self.domain.render(ctx) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{Domain, MountainCar, TwoStateMDP};
+
+    #[test]
+    fn test_truncation() {
+        let mut m = TimeLimit::new(TwoStateMDP::default(), 3);
+
+        for _ in 0..2 {
+            let t = m.step(0);
+
+            assert!(!t.ended());
+            assert!(!t.truncated);
+        }
+
+        let t = m.step(0);
+
+        assert!(t.ended());
+        assert!(t.truncated);
+        assert!(!t.terminated());
+
+        assert!(m.is_terminal());
+        assert!(m.emit().is_terminal());
+        assert!(!m.inner().is_terminal());
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut m = TimeLimit::new(MountainCar::default(), 3);
+
+        for _ in 0..3 {
+            m.step(2);
+        }
+
+        assert!(m.is_terminal());
+        assert!(!m.reset().is_terminal());
+        assert_eq!(m.steps(), 0);
+        assert_eq!(m.emit().state(), MountainCar::default().emit().state());
+    }
+}
@@ -0,0 +1,202 @@
+use crate::geometry::{discrete::Ordinal, Vector};
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+use super::{Domain, Observation, Transition};
+
+const STAY: usize = 0;
+const SWITCH: usize = 1;
+
+/// Continuing two-state MDP with a closed-form value function.
+///
+/// In each state the agent may either stay (`0`) or switch (`1`). The chosen
+/// action takes effect with probability `p`, otherwise the opposite outcome
+/// occurs. Leaving state `s` yields reward `rewards[s]`, regardless of the
+/// action taken or the state reached.
+///
+/// Since both states offer the same choice of next-state distributions, the
+/// optimal policy always heads for the more rewarding state, and the optimal
+/// value function can be computed exactly; see `analytic_values`. The domain
+/// never terminates, so episodes must be truncated by the caller.
+pub struct TwoStateMDP {
+    p: f64,
+    rewards: [f64; 2],
+
+    state: usize,
+    rng: StdRng,
+}
+
+impl TwoStateMDP {
+    pub fn new(p: f64, rewards: [f64; 2]) -> TwoStateMDP {
+        assert!(p >= 0.0 && p <= 1.0, "The success probability must lie in [0, 1].");
+
+        TwoStateMDP {
+            p,
+            rewards,
+
+            state: 0,
+            rng: StdRng::from_rng(thread_rng()).unwrap(),
+        }
+    }
+
+    /// Seed the random number generator used to sample transitions.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+
+        self
+    }
+
+    /// Probability of transitioning to `ns` from `s` after taking action `a`.
+    pub fn transition_probability(&self, s: usize, a: usize, ns: usize) -> f64 {
+        let intended = if a == STAY { s } else { 1 - s };
+
+        if ns == intended { self.p } else { 1.0 - self.p }
+    }
+
+    /// Reward obtained on leaving state `s`.
+    pub fn expected_reward(&self, s: usize) -> f64 { self.rewards[s] }
+
+    /// Return the optimal action in each state.
+    pub fn optimal_policy(&self) -> [usize; 2] {
+        let best = if self.rewards[0] >= self.rewards[1] { 0 } else { 1 };
+        let towards = |s: usize| match (s == best, self.p >= 0.5) {
+            (true, true) | (false, false) => STAY,
+            _ => SWITCH,
+        };
+
+        [towards(0), towards(1)]
+    }
+
+    /// Return the optimal value of each state under discount factor `gamma`.
+    ///
+    /// Under the optimal policy the next state is the more rewarding one with
+    /// probability `q = max(p, 1 - p)`, independently of the current state.
+    /// The expected continuation value, `c`, is thus shared by both states and
+    /// satisfies `c = q r_best + (1 - q) r_worst + gamma c`, giving
+    /// `v(s) = r(s) + gamma c`.
+    pub fn analytic_values(&self, gamma: f64) -> Vector<f64> {
+        assert!(gamma >= 0.0 && gamma < 1.0, "The discount factor must lie in [0, 1).");
+
+        let q = self.p.max(1.0 - self.p);
+        let (r_best, r_worst) = if self.rewards[0] >= self.rewards[1] {
+            (self.rewards[0], self.rewards[1])
+        } else {
+            (self.rewards[1], self.rewards[0])
+        };
+        let c = (q * r_best + (1.0 - q) * r_worst) / (1.0 - gamma);
+
+        Vector::from_vec(vec![self.rewards[0] + gamma * c, self.rewards[1] + gamma * c])
+    }
+}
+
+impl Default for TwoStateMDP {
+    fn default() -> TwoStateMDP { TwoStateMDP::new(0.9, [0.0, 1.0]) }
+}
+
+impl Domain for TwoStateMDP {
+    type StateSpace = Ordinal;
+    type ActionSpace = Ordinal;
+
+    fn emit(&self) -> Observation<usize> { Observation::Full(self.state) }
+
+    fn step(&mut self, action: usize) -> Transition<usize, usize> {
+        let from = self.emit();
+        let intended = if action == STAY { self.state } else { 1 - self.state };
+
+        self.state = if self.rng.gen_bool(self.p) { intended } else { 1 - intended };
+
+        let to = self.emit();
+        let reward = self.reward(&from, &to);
+
+        Transition {
+            from,
+            action,
+            reward,
+            to,
+            truncated: false,
+        }
+    }
+
+    fn is_terminal(&self) -> bool { false }
+
+    fn state_index(&self) -> Option<usize> { Some(self.state) }
+
+    fn reward(&self, from: &Observation<usize>, _: &Observation<usize>) -> f64 {
+        self.rewards[*from.state()]
+    }
+
+    fn state_space(&self) -> Ordinal { Ordinal::new(2) }
+
+    fn action_space(&self) -> Ordinal { Ordinal::new(2) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{Domain, Observation};
+
+    fn value_iteration(m: &TwoStateMDP, gamma: f64) -> ([f64; 2], [usize; 2]) {
+        let mut v = [0.0; 2];
+        let mut pi = [0; 2];
+
+        for _ in 0..2000 {
+            let mut nv = [0.0; 2];
+
+            for s in 0..2 {
+                let qs: Vec<f64> = (0..2).map(|a| {
+                    m.expected_reward(s) + gamma * (0..2)
+                        .map(|ns| m.transition_probability(s, a, ns) * v[ns])
+                        .sum::<f64>()
+                }).collect();
+
+                // Prefer the lower action index on ties, as `optimal_policy` does:
+                pi[s] = if qs[1] > qs[0] + 1e-12 { 1 } else { 0 };
+                nv[s] = qs[pi[s]];
+            }
+
+            v = nv;
+        }
+
+        (v, pi)
+    }
+
+    #[test]
+    fn test_initial_observation() {
+        match TwoStateMDP::default().emit() {
+            Observation::Full(state) => assert_eq!(state, 0),
+            _ => panic!("Should yield a fully observable state."),
+        }
+    }
+
+    #[test]
+    fn test_deterministic_transitions() {
+        let mut m = TwoStateMDP::new(1.0, [1.0, 2.0]).with_seed(0);
+
+        let t = m.step(STAY);
+        assert_eq!((*t.from.state(), *t.to.state(), t.reward), (0, 0, 1.0));
+
+        let t = m.step(SWITCH);
+        assert_eq!((*t.from.state(), *t.to.state(), t.reward), (0, 1, 1.0));
+
+        let t = m.step(STAY);
+        assert_eq!((*t.from.state(), *t.to.state(), t.reward), (1, 1, 2.0));
+
+        assert!(!m.is_terminal());
+    }
+
+    #[test]
+    fn test_analytic_values() {
+        for &(p, rewards, gamma) in [
+            (0.9, [0.0, 1.0], 0.9),
+            (0.7, [2.0, -1.0], 0.5),
+            (0.2, [0.5, 1.5], 0.95),
+            (1.0, [0.0, 1.0], 0.3),
+        ].iter() {
+            let m = TwoStateMDP::new(p, rewards);
+            let (v, pi) = value_iteration(&m, gamma);
+            let expected = m.analytic_values(gamma);
+
+            assert!((v[0] - expected[0]).abs() < 1e-6);
+            assert!((v[1] - expected[1]).abs() < 1e-6);
+            assert_eq!(pi, m.optimal_policy());
+        }
+    }
+}
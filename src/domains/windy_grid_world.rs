@@ -0,0 +1,225 @@
+use crate::core::Matrix;
+use crate::geometry::discrete::Ordinal;
+use super::{
+    grid_world::{GridWorld, Motion},
+    Domain,
+    Observation,
+    Transition,
+};
+
+// A `Motion` moves north by increasing `y`, which here runs from the top of
+// the grid downwards; hence up and down map to south and north, respectively.
+const ALL_ACTIONS: [Motion; 4] = [
+    Motion::South(1),
+    Motion::North(1),
+    Motion::West(1),
+    Motion::East(1),
+];
+
+const REWARD_STEP: f64 = -1.0;
+
+/// Windy grid world domain.
+///
+/// The agent navigates a grid of `width * height` cells using the four
+/// cardinal moves: up (0), down (1), left (2) and right (3). After each move,
+/// the agent is pushed up by the wind strength of the column it departed
+/// from. Any displacement that would take the agent off the grid is truncated
+/// at the boundary. A reward of -1 is given for every step until the goal cell
+/// is reached, at which point the episode terminates.
+///
+/// # Technical details
+/// The **state** is the index of the agent's cell, `y * width + x`, where `(0,
+/// 0)` is the top-left corner of the grid.
+///
+/// # References
+/// - Sutton, R. S. and Barto, A. G. (2017). Reinforcement Learning: An
+/// Introduction (2nd ed.). Manuscript in preparation.
+pub struct WindyGridWorld {
+    gw: GridWorld<u8>,
+    wind: Vec<usize>,
+
+    start: (usize, usize),
+    goal: (usize, usize),
+
+    loc: (usize, usize),
+}
+
+impl WindyGridWorld {
+    /// Construct a grid with one column per entry of `wind`, each giving the
+    /// upward displacement applied in that column.
+    pub fn new(
+        wind: Vec<usize>,
+        height: usize,
+        start: (usize, usize),
+        goal: (usize, usize),
+    ) -> WindyGridWorld
+    {
+        let width = wind.len();
+
+        assert!(start.0 < width && start.1 < height, "Start {:?} is out of bounds.", start);
+        assert!(goal.0 < width && goal.1 < height, "Goal {:?} is out of bounds.", goal);
+
+        WindyGridWorld {
+            // The layout is indexed by `(x, y)` so that each motion is bounded
+            // by the matching extent of the grid.
+            gw: GridWorld::new(Matrix::zeros((width, height))),
+            wind,
+
+            start,
+            goal,
+
+            loc: start,
+        }
+    }
+
+    /// The 10x7 grid of Sutton & Barto, with wind profile
+    /// `[0, 0, 0, 1, 1, 1, 2, 2, 1, 0]`, starting at `(0, 3)` with the goal at
+    /// `(7, 3)`.
+    pub fn standard() -> WindyGridWorld {
+        WindyGridWorld::new(vec![0, 0, 0, 1, 1, 1, 2, 2, 1, 0], 7, (0, 3), (7, 3))
+    }
+
+    pub fn width(&self) -> usize { self.wind.len() }
+
+    // The layout is transposed, so its columns span the height of the grid.
+    pub fn height(&self) -> usize { self.gw.width() }
+
+    pub fn wind(&self) -> &[usize] { &self.wind }
+
+    fn index(&self, cell: (usize, usize)) -> usize { cell.1 * self.width() + cell.0 }
+
+    fn update_state(&mut self, a: usize) {
+        let wind = Motion::South(self.wind[self.loc.0]);
+        let next = self.gw.perform_motion(self.loc, ALL_ACTIONS[a]);
+
+        self.loc = self.gw.perform_motion(next, wind);
+    }
+}
+
+impl Default for WindyGridWorld {
+    fn default() -> WindyGridWorld { WindyGridWorld::standard() }
+}
+
+impl Domain for WindyGridWorld {
+    type StateSpace = Ordinal;
+    type ActionSpace = Ordinal;
+
+    fn emit(&self) -> Observation<usize> {
+        let s = self.index(self.loc);
+
+        if self.is_terminal() {
+            Observation::Terminal(s)
+        } else {
+            Observation::Full(s)
+        }
+    }
+
+    fn step(&mut self, action: usize) -> Transition<usize, usize> {
+        let from = self.emit();
+
+        self.update_state(action);
+        let to = self.emit();
+        let reward = self.reward(&from, &to);
+
+        Transition {
+            from,
+            action,
+            reward,
+            to,
+            truncated: false,
+        }
+    }
+
+    fn is_terminal(&self) -> bool { self.loc == self.goal }
+
+    fn reset(&mut self) -> Observation<usize> {
+        self.loc = self.start;
+
+        self.emit()
+    }
+
+    fn state_index(&self) -> Option<usize> { Some(self.index(self.loc)) }
+
+    fn reward(&self, _: &Observation<usize>, _: &Observation<usize>) -> f64 { REWARD_STEP }
+
+    fn state_space(&self) -> Ordinal { Ordinal::new(self.width() * self.height()) }
+
+    fn action_space(&self) -> Ordinal { Ordinal::new(4) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{Domain, Observation};
+
+    const UP: usize = 0;
+    const DOWN: usize = 1;
+    const LEFT: usize = 2;
+    const RIGHT: usize = 3;
+
+    #[test]
+    fn test_initial_observation() {
+        let m = WindyGridWorld::standard();
+
+        match m.emit() {
+            Observation::Full(state) => assert_eq!(state, 30),
+            _ => panic!("Should yield a fully observable state."),
+        }
+    }
+
+    #[test]
+    fn test_wind_drift() {
+        let mut m = WindyGridWorld::standard();
+
+        let ys: Vec<usize> = (0..9).map(|_| {
+            let t = m.step(RIGHT);
+
+            assert_eq!(t.reward, REWARD_STEP);
+            assert!(!t.terminated());
+
+            *t.to.state() / 10
+        }).collect();
+
+        // Each move is followed by the wind of the column just departed, so the
+        // agent is blown over the goal and pinned against the top edge:
+        assert_eq!(ys, vec![3, 3, 3, 2, 1, 0, 0, 0, 0]);
+        assert_eq!(*m.emit().state(), 9);
+    }
+
+    #[test]
+    fn test_goal() {
+        // With no wind, the goal is reached by moving right seven times:
+        let mut m = WindyGridWorld::new(vec![0; 10], 7, (0, 3), (7, 3));
+
+        for _ in 0..6 {
+            assert!(!m.step(RIGHT).terminated());
+        }
+
+        let t = m.step(RIGHT);
+
+        assert!(t.terminated());
+        assert_eq!(t.reward, REWARD_STEP);
+        assert!(m.is_terminal());
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut m = WindyGridWorld::new(vec![0; 3], 3, (1, 1), (2, 2));
+
+        m.step(RIGHT);
+        m.step(DOWN);
+
+        assert!(m.is_terminal());
+        assert_eq!(*m.reset().state(), 4);
+        assert_eq!(m.width(), 3);
+    }
+
+    #[test]
+    fn test_edges() {
+        let mut m = WindyGridWorld::new(vec![0; 3], 3, (0, 0), (2, 2));
+
+        assert_eq!(*m.step(UP).to.state(), 0);
+        assert_eq!(*m.step(LEFT).to.state(), 0);
+        assert_eq!(*m.step(DOWN).to.state(), 3);
+    }
+}
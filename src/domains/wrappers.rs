@@ -0,0 +1,253 @@
+use crate::geometry::Space;
+use super::{Domain, Observation, Reset, Transition};
+
+type StateOf<D> = <<D as Domain>::StateSpace as Space>::Value;
+type ActionOf<D> = <<D as Domain>::ActionSpace as Space>::Value;
+
+/// Wrapper that applies a closure to every reward emitted by a domain.
+pub struct TransformReward<D, F> {
+    domain: D,
+    transform: F,
+}
+
+impl<D, F: Fn(f64) -> f64> TransformReward<D, F> {
+    pub fn new(domain: D, transform: F) -> TransformReward<D, F> {
+        TransformReward { domain, transform }
+    }
+
+    /// Return a reference to the wrapped domain.
+    pub fn inner(&self) -> &D { &self.domain }
+}
+
+impl<D: Domain + Reset, F> Reset for TransformReward<D, F> {
+    fn reset_state(&mut self) { self.domain.reset(); }
+}
+
+impl<D: Domain, F: Fn(f64) -> f64> Domain for TransformReward<D, F> {
+    type StateSpace = D::StateSpace;
+    type ActionSpace = D::ActionSpace;
+
+    fn emit(&self) -> Observation<StateOf<D>> { self.domain.emit() }
+
+    fn step(&mut self, a: ActionOf<D>) -> Transition<StateOf<D>, ActionOf<D>> {
+        let mut t = self.domain.step(a);
+
+        t.reward = (self.transform)(t.reward);
+
+        t
+    }
+
+    fn is_terminal(&self) -> bool { self.domain.is_terminal() }
+
+    fn state_index(&self) -> Option<usize> { self.domain.state_index() }
+
+    fn reward(&self, from: &Observation<StateOf<D>>, to: &Observation<StateOf<D>>) -> f64 {
+        (self.transform)(self.domain.reward(from, to))
+    }
+
+    fn state_space(&self) -> Self::StateSpace { self.domain.state_space() }
+
+    fn action_space(&self) -> Self::ActionSpace { self.domain.action_space() }
+
+    fn render(&self, ctx: &mut ggez::Context) { self.domain.render(ctx) }
+}
+
+/// Wrapper that clips every reward emitted by a domain to `[lb, ub]`.
+pub struct ClipReward<D> {
+    domain: D,
+
+    lb: f64,
+    ub: f64,
+}
+
+impl<D> ClipReward<D> {
+    pub fn new(domain: D, lb: f64, ub: f64) -> ClipReward<D> {
+        assert!(lb <= ub, "The lower bound must not exceed the upper bound.");
+
+        ClipReward { domain, lb, ub }
+    }
+
+    /// Return a reference to the wrapped domain.
+    pub fn inner(&self) -> &D { &self.domain }
+}
+
+impl<D: Domain + Reset> Reset for ClipReward<D> {
+    fn reset_state(&mut self) { self.domain.reset(); }
+}
+
+impl<D: Domain> Domain for ClipReward<D> {
+    type StateSpace = D::StateSpace;
+    type ActionSpace = D::ActionSpace;
+
+    fn emit(&self) -> Observation<StateOf<D>> { self.domain.emit() }
+
+    fn step(&mut self, a: ActionOf<D>) -> Transition<StateOf<D>, ActionOf<D>> {
+        let mut t = self.domain.step(a);
+
+        t.reward = clip!(self.lb, t.reward, self.ub);
+
+        t
+    }
+
+    fn is_terminal(&self) -> bool { self.domain.is_terminal() }
+
+    fn state_index(&self) -> Option<usize> { self.domain.state_index() }
+
+    fn reward(&self, from: &Observation<StateOf<D>>, to: &Observation<StateOf<D>>) -> f64 {
+        clip!(self.lb, self.domain.reward(from, to), self.ub)
+    }
+
+    fn state_space(&self) -> Self::StateSpace { self.domain.state_space() }
+
+    fn action_space(&self) -> Self::ActionSpace { self.domain.action_space() }
+
+    fn reward_range(&self) -> (Option<f64>, Option<f64>) {
+        let (lb, ub) = self.domain.reward_range();
+
+        (
+            Some(lb.map_or(self.lb, |lb| clip!(self.lb, lb, self.ub))),
+            Some(ub.map_or(self.ub, |ub| clip!(self.lb, ub, self.ub))),
+        )
+    }
+
+    fn render(&self, ctx: &mut ggez::Context) { self.domain.render(ctx) }
+}
+
+/// Wrapper that maps every state emitted by a domain into a new space.
+///
+/// Since the transformation need not be invertible, `reward` cannot be
+/// evaluated on transformed observations. Instead, it returns the reward of the
+/// most recent transition returned by `step`, or zero before the first step.
+pub struct TransformObservation<D, F, SS> {
+    domain: D,
+    transform: F,
+
+    space: SS,
+    last_reward: f64,
+}
+
+impl<D: Domain, F, SS: Space> TransformObservation<D, F, SS>
+where
+    F: Fn(StateOf<D>) -> SS::Value,
+{
+    pub fn new(domain: D, space: SS, transform: F) -> TransformObservation<D, F, SS> {
+        TransformObservation {
+            domain,
+            transform,
+
+            space,
+            last_reward: 0.0,
+        }
+    }
+
+    /// Return a reference to the wrapped domain.
+    pub fn inner(&self) -> &D { &self.domain }
+}
+
+impl<D: Domain + Reset, F, SS> Reset for TransformObservation<D, F, SS> {
+    fn reset_state(&mut self) {
+        self.domain.reset();
+        self.last_reward = 0.0;
+    }
+}
+
+impl<D, F, SS> Domain for TransformObservation<D, F, SS>
+where
+    D: Domain,
+    F: Fn(StateOf<D>) -> SS::Value,
+    SS: Space + Clone,
+{
+    type StateSpace = SS;
+    type ActionSpace = D::ActionSpace;
+
+    fn emit(&self) -> Observation<SS::Value> { self.domain.emit().map(&self.transform) }
+
+    fn step(&mut self, a: ActionOf<D>) -> Transition<SS::Value, ActionOf<D>> {
+        let t = self.domain.step(a);
+
+        self.last_reward = t.reward;
+
+        Transition {
+            from: t.from.map(&self.transform),
+            action: t.action,
+            reward: t.reward,
+            to: t.to.map(&self.transform),
+            truncated: t.truncated,
+        }
+    }
+
+    fn is_terminal(&self) -> bool { self.domain.is_terminal() }
+
+    fn state_index(&self) -> Option<usize> { self.domain.state_index() }
+
+    fn reward(&self, _: &Observation<SS::Value>, _: &Observation<SS::Value>) -> f64 {
+        self.last_reward
+    }
+
+    fn state_space(&self) -> SS { self.space.clone() }
+
+    fn action_space(&self) -> Self::ActionSpace { self.domain.action_space() }
+
+    fn reward_range(&self) -> (Option<f64>, Option<f64>) { self.domain.reward_range() }
+
+    fn render(&self, ctx: &mut ggez::Context) { self.domain.render(ctx) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{CliffWalking, Domain, GridWorld, MountainCar};
+    use crate::geometry::discrete::Ordinal;
+
+    #[test]
+    fn test_transform_reward() {
+        let mut m = TransformReward::new(MountainCar::default(), |r| r * 2.0);
+        let s = m.emit();
+
+        assert_eq!(m.step(1).reward, -2.0);
+        assert_eq!(m.reward(&s, &s), -2.0);
+
+        m.step(2);
+
+        assert_eq!(m.reset().state(), s.state());
+    }
+
+    #[test]
+    fn test_clip_reward() {
+        let mut m = ClipReward::new(MountainCar::default(), -0.5, 0.5);
+
+        assert_eq!(m.step(1).reward, -0.5);
+
+        // Stepping off the start into the cliff costs -100 before clipping:
+        let mut m = ClipReward::new(CliffWalking::new(), -1.0, 1.0);
+        let t = m.step(3);
+
+        assert_eq!(t.reward, -1.0);
+        assert_eq!(m.inner().reward(&t.from, &t.to), -100.0);
+    }
+
+    #[test]
+    fn test_transform_observation() {
+        let mut m = TransformObservation::new(
+            GridWorld::new(3, 1).with_goal((2, 0), 1.0),
+            Ordinal::new(6),
+            |s: usize| 2 * s,
+        );
+
+        assert_eq!(*m.emit().state(), 0);
+
+        let t = m.step(3);
+        assert_eq!((*t.from.state(), *t.to.state()), (0, 2));
+
+        let t = m.step(3);
+        assert!(t.to.is_terminal());
+        assert_eq!(*t.to.state(), 4);
+        assert_eq!(t.reward, 1.0);
+        assert_eq!(m.reward(&t.from, &t.to), 1.0);
+
+        // Stacked reward wrappers query the transformed domain's rewards:
+        let m = ClipReward::new(m, -0.5, 0.5);
+
+        assert_eq!(m.reward(&t.from, &t.to), 0.5);
+    }
+}
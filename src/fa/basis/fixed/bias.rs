@@ -0,0 +1,46 @@
+use super::Constant;
+
+/// Construct a projector yielding a single dense feature of value one for
+/// every input, i.e. `Constant::ones(1)`.
+///
+/// This is intended to be combined with other projectors, e.g. via
+/// [`Stack`](../struct.Stack.html), to provide an explicit intercept term.
+pub fn bias() -> Constant { Constant::ones(1) }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{make_shared, OnlineLearner};
+    use crate::domains::{Observation, Transition};
+    use crate::fa::{Projector, ScalarLFA, LFA};
+    use crate::geometry::{Space, Vector};
+    use crate::prediction::td::TD;
+
+    #[test]
+    fn test_projection() {
+        let p = bias();
+
+        assert_eq!(p.dim(), 1);
+        assert_eq!(p.project(&0usize).expanded(1), Vector::from_vec(vec![1.0]));
+        assert_eq!(p.project(&[1.0, -5.0][..]).expanded(1), Vector::from_vec(vec![1.0]));
+    }
+
+    #[test]
+    fn test_learns_mean_return() {
+        let v_func: ScalarLFA<Constant> = LFA::scalar_output(bias());
+        let v_func = make_shared(v_func);
+        let mut agent = TD::new(v_func.clone(), 0.01, 1.0);
+
+        for i in 0..2000 {
+            agent.handle_transition(&Transition {
+                from: Observation::Full(i % 3),
+                action: (),
+                reward: if i % 2 == 0 { 0.0 } else { 2.0 },
+                to: Observation::Terminal(0usize),
+                truncated: false,
+            });
+        }
+
+        assert!((v_func.borrow().approximator.weights[0] - 1.0).abs() < 0.05);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::geometry::{continuous::Interval, product::LinearSpace};
+use crate::utils::cartesian_product;
+use super::{space_limits, Fourier};
+
+fn filtered_fourier(order: u8, input_space: LinearSpace<Interval>, f: impl Fn(&[f64]) -> bool) -> Fourier {
+    let limits = space_limits(&input_space);
+    let values: Vec<f64> = (0..=order).map(f64::from).collect();
+
+    // The zero frequency vector is omitted, as for `Fourier::new`, since its
+    // feature duplicates the constant term appended to every projection.
+    let mut coefficients: Vec<Vec<f64>> = cartesian_product(&vec![values; limits.len()])
+        .into_iter()
+        .filter(|c| c.iter().any(|&v| v > 0.0) && f(c))
+        .collect();
+
+    coefficients.sort_by(|a, b| b.partial_cmp(a).unwrap());
+
+    Fourier {
+        order,
+        limits,
+        coefficients,
+    }
+}
+
+/// Construct an uncoupled Fourier basis in which each feature varies along at
+/// most one dimension, yielding `1 + d * order` features in `d` dimensions.
+pub fn fourier_independent(order: u8, input_space: LinearSpace<Interval>) -> Fourier {
+    filtered_fourier(order, input_space, |c| c.iter().filter(|&&v| v > 0.0).count() <= 1)
+}
+
+/// Construct a Fourier basis including only those frequency vectors with L1
+/// norm no greater than `max`.
+pub fn fourier_with_max_frequency(order: u8, max: u8, input_space: LinearSpace<Interval>) -> Fourier {
+    filtered_fourier(order, input_space, |c| c.iter().sum::<f64>() <= f64::from(max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fa::basis::Projector;
+    use crate::geometry::Space;
+
+    fn cube() -> LinearSpace<Interval> {
+        LinearSpace::empty()
+            + Interval::bounded(0.0, 1.0)
+            + Interval::bounded(0.0, 1.0)
+            + Interval::bounded(0.0, 1.0)
+    }
+
+    #[test]
+    fn test_dim() {
+        assert_eq!(Fourier::from_space(3, cube()).dim(), 64);
+        assert_eq!(fourier_independent(3, cube()).dim(), 1 + 3 * 3);
+
+        // Non-negative integer vectors in 3D with L1 norm at most 2: C(5, 3).
+        assert_eq!(fourier_with_max_frequency(3, 2, cube()).dim(), 10);
+        assert_eq!(fourier_with_max_frequency(3, 9, cube()).dim(), 64);
+    }
+
+    #[test]
+    fn test_independent_coefficients() {
+        let f = fourier_independent(2, cube());
+
+        for c in f.coefficients.iter() {
+            assert_eq!(c.iter().filter(|&&v| v > 0.0).count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_matches_full_coupling() {
+        // In one dimension every frequency vector is axis-aligned:
+        let space = LinearSpace::empty() + Interval::bounded(-1.0, 1.0);
+
+        let full = Fourier::from_space(3, space.clone());
+        let independent = fourier_independent(3, space);
+
+        for &x in [-1.0, -0.3, 0.0, 0.8].iter() {
+            assert_eq!(
+                full.project_expanded(&vec![x]),
+                independent.project_expanded(&vec![x])
+            );
+        }
+    }
+}
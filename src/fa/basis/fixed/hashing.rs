@@ -0,0 +1,147 @@
+use crate::geometry::{Card, Space, Vector};
+use super::{Projection, Projector};
+
+const SIGN_SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Finalisation step of the 64-bit MurmurHash3 function.
+fn fmix64(mut k: u64) -> u64 {
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    k ^= k >> 33;
+
+    k
+}
+
+/// Feature hashing projector for high-cardinality discrete inputs.
+///
+/// Inputs are sets of active raw feature indices, drawn from an arbitrarily
+/// large (or unbounded) index space. Each raw index is hashed into one of
+/// `table_size` buckets and, by default, a second hash assigns it a sign of
+/// `+1` or `-1`. Colliding features thus cancel in expectation rather than
+/// accumulating, so that inner products between hashed inputs are unbiased.
+/// Memory is bounded by `table_size` regardless of the input cardinality.
+///
+/// # References
+/// - Weinberger, K., Dasgupta, A., Langford, J., Smola, A., & Attenberg, J.
+/// (2009). Feature hashing for large scale multitask learning. In
+/// Proceedings of the 26th International Conference on Machine Learning, pp.
+/// 1113-1120.
+#[derive(Clone, Debug)]
+pub struct HashingProjector {
+    table_size: usize,
+    signed: bool,
+}
+
+impl HashingProjector {
+    pub fn new(table_size: usize) -> Self {
+        assert!(table_size > 0, "Hashing requires a table with at least one entry.");
+
+        HashingProjector {
+            table_size,
+            signed: true,
+        }
+    }
+
+    /// Toggle the sign hash; unsigned hashing simply sums colliding features.
+    pub fn with_signed(mut self, signed: bool) -> Self {
+        self.signed = signed;
+
+        self
+    }
+
+    /// Return the bucket into which the raw feature `index` is hashed.
+    pub fn bucket(&self, index: usize) -> usize {
+        (fmix64(index as u64) % self.table_size as u64) as usize
+    }
+
+    /// Return the sign applied to the raw feature `index`.
+    pub fn sign(&self, index: usize) -> f64 {
+        if !self.signed || fmix64(index as u64 ^ SIGN_SEED) & 1 == 0 { 1.0 } else { -1.0 }
+    }
+}
+
+impl Space for HashingProjector {
+    type Value = Projection;
+
+    fn dim(&self) -> usize { self.table_size }
+
+    fn card(&self) -> Card { Card::Infinite }
+}
+
+impl Projector<[usize]> for HashingProjector {
+    fn project(&self, input: &[usize]) -> Projection {
+        let mut phi = Vector::zeros(self.table_size);
+
+        for &i in input {
+            phi[self.bucket(i)] += self.sign(i);
+        }
+
+        Projection::Dense(phi)
+    }
+}
+
+impl Projector<Vec<usize>> for HashingProjector {
+    fn project(&self, input: &Vec<usize>) -> Projection {
+        Projector::<[usize]>::project(self, input.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dense(p: &HashingProjector, input: &[usize]) -> Vector<f64> {
+        p.project(input).expanded(p.dim())
+    }
+
+    #[test]
+    fn test_bounded_dim() {
+        let p = HashingProjector::new(32);
+        let phi = dense(&p, &[0, 7, 1 << 40, usize::max_value()]);
+
+        assert_eq!(phi.len(), 32);
+        assert!(phi.iter().all(|x| x.abs() <= 4.0));
+    }
+
+    #[test]
+    fn test_no_interference() {
+        let p = HashingProjector::new(64);
+
+        let a = 12345;
+        let b = (0..1000).find(|&b| p.bucket(b) != p.bucket(a)).unwrap();
+
+        let phi_a = dense(&p, &[a]);
+        let phi_b = dense(&p, &[b]);
+
+        assert_eq!(phi_a.iter().filter(|&&x| x != 0.0).count(), 1);
+        assert_eq!(phi_b.iter().filter(|&&x| x != 0.0).count(), 1);
+        assert_eq!(phi_a.dot(&phi_b), 0.0);
+
+        // Projecting both together is the superposition of the two:
+        assert_eq!(dense(&p, &[a, b]), phi_a + phi_b);
+    }
+
+    #[test]
+    fn test_signed_collision_bias() {
+        // Mean inner product between the hashed features of distinct raw
+        // indices, which would be zero without hashing:
+        let mean_overlap = |p: &HashingProjector| {
+            let n = 1000;
+            let phis: Vec<Vector<f64>> = (0..n).map(|i| dense(p, &[i])).collect();
+
+            let total = phis.iter().fold(Vector::zeros(p.dim()), |acc, phi| acc + phi);
+            let self_overlap: f64 = phis.iter().map(|phi| phi.dot(phi)).sum();
+
+            (total.dot(&total) - self_overlap) / (n * (n - 1)) as f64
+        };
+
+        let unsigned = mean_overlap(&HashingProjector::new(16).with_signed(false));
+        let signed = mean_overlap(&HashingProjector::new(16));
+
+        // Unsigned collisions add up to roughly 1 / table_size:
+        assert!(unsigned > 0.05);
+        assert!(signed.abs() < 0.01);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::geometry::{
+    continuous::Interval,
+    product::LinearSpace,
+    Card,
+    Space,
+};
+use rand::Rng;
+use std::cmp::Ordering;
+use super::{space_limits, Projection, Projector};
+
+/// Kanerva coding (sparse distributed memory) basis projector.
+///
+/// A fixed set of prototype points is scattered uniformly at random over the
+/// input space, and each input activates the `k` prototypes nearest to it,
+/// yielding a sparse binary representation. Distances are measured after
+/// rescaling every dimension to the unit interval. Unlike tile coding, the
+/// number of features is independent of the dimensionality of the input.
+///
+/// # References
+/// - Kanerva, P. (1988). Sparse Distributed Memory. MIT Press.
+/// - Sutton, R. S., & Barto, A. G. (2018). Reinforcement learning: An
+/// introduction (2nd ed.), Section 9.5.5. MIT Press.
+#[derive(Clone, Debug)]
+pub struct KanervaCoding {
+    k: usize,
+
+    limits: Vec<(f64, f64)>,
+    prototypes: Vec<Vec<f64>>,
+}
+
+impl KanervaCoding {
+    pub fn new<R: Rng>(
+        n_prototypes: usize,
+        k: usize,
+        input_space: LinearSpace<Interval>,
+        rng: &mut R,
+    ) -> Self
+    {
+        assert!(k > 0, "Kanerva coding requires at least one active prototype.");
+        assert!(k <= n_prototypes, "Cannot activate more prototypes than exist.");
+
+        let limits = space_limits(&input_space);
+        let prototypes = (0..n_prototypes)
+            .map(|_| limits.iter().map(|&(lb, ub)| rng.gen_range(lb, ub)).collect())
+            .collect();
+
+        KanervaCoding {
+            k,
+
+            limits,
+            prototypes,
+        }
+    }
+
+    /// Return the prototype points, in feature order.
+    pub fn prototypes(&self) -> &[Vec<f64>] { &self.prototypes }
+
+    fn distance(&self, prototype: &[f64], input: &[f64]) -> f64 {
+        prototype
+            .iter()
+            .zip(input.iter())
+            .zip(self.limits.iter())
+            .map(|((p, x), &(lb, ub))| ((p - x) / (ub - lb)).powi(2))
+            .sum()
+    }
+}
+
+impl Space for KanervaCoding {
+    type Value = Projection;
+
+    fn dim(&self) -> usize { self.prototypes.len() }
+
+    fn card(&self) -> Card { Card::Infinite }
+}
+
+impl Projector<[f64]> for KanervaCoding {
+    fn project(&self, input: &[f64]) -> Projection {
+        let mut distances: Vec<(usize, f64)> = self
+            .prototypes
+            .iter()
+            .map(|p| self.distance(p, input))
+            .enumerate()
+            .collect();
+
+        distances.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+
+        let indices: Vec<usize> = distances.into_iter().take(self.k).map(|(i, _)| i).collect();
+
+        Projection::Sparse(indices.into_iter().collect())
+    }
+}
+
+impl_array_proxies!(KanervaCoding);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn active(p: &KanervaCoding, input: &[f64]) -> Vec<usize> {
+        match p.project(input) {
+            Projection::Sparse(ref indices) => indices.iter().cloned().collect(),
+            _ => panic!("Kanerva coding should yield a sparse projection."),
+        }
+    }
+
+    fn kanerva(k: usize) -> KanervaCoding {
+        let space = LinearSpace::empty()
+            + Interval::bounded(0.0, 1.0)
+            + Interval::bounded(-10.0, 10.0)
+            + Interval::bounded(0.0, 100.0);
+
+        KanervaCoding::new(50, k, space, &mut StdRng::seed_from_u64(0))
+    }
+
+    #[test]
+    fn test_k_active() {
+        let p = kanerva(5);
+
+        assert_eq!(p.dim(), 50);
+
+        for input in &[[0.0, -10.0, 0.0], [0.5, 0.0, 50.0], [1.0, 10.0, 100.0]] {
+            let indices = active(&p, input);
+
+            assert_eq!(indices.len(), 5);
+            assert!(indices.iter().all(|&i| i < 50));
+
+            let mut unique = indices.clone();
+            unique.sort();
+            unique.dedup();
+
+            assert_eq!(unique.len(), 5);
+        }
+    }
+
+    #[test]
+    fn test_nearby_prototype() {
+        let p = kanerva(3);
+
+        for (i, prototype) in p.prototypes().iter().enumerate() {
+            let input: Vec<f64> = prototype.iter().map(|x| x + 1e-9).collect();
+
+            assert!(active(&p, &input).contains(&i));
+        }
+    }
+}
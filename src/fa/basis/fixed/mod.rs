@@ -0,0 +1,24 @@
+use super::*;
+
+pub use lfa::basis::fixed::*;
+
+mod tile_coding;
+pub use self::tile_coding::AsymmetricTileCoding;
+
+mod rbf;
+pub use self::rbf::RBF;
+
+mod fourier;
+pub use self::fourier::{fourier_independent, fourier_with_max_frequency};
+
+mod bias;
+pub use self::bias::bias;
+
+mod state_aggregation;
+pub use self::state_aggregation::StateAggregation;
+
+mod hashing;
+pub use self::hashing::HashingProjector;
+
+mod kanerva;
+pub use self::kanerva::KanervaCoding;
@@ -0,0 +1,151 @@
+use crate::geometry::{
+    continuous::Interval,
+    product::LinearSpace,
+    Card,
+    Space,
+    Vector,
+};
+use crate::utils::cartesian_product;
+use super::{space_limits, Projection, Projector};
+
+/// Gaussian radial basis function projector.
+///
+/// Centres are placed on a regular grid spanning the input space, with
+/// `n_centres` points along each dimension, and every centre contributes the
+/// activation `exp(-||x - c||^2 / (2 * sigma^2))`. If `normalised` is set, the
+/// activations are rescaled to sum to one.
+#[derive(Clone, Debug)]
+pub struct RBF {
+    centres: Vec<Vec<f64>>,
+    sigma: f64,
+    normalised: bool,
+}
+
+impl RBF {
+    pub fn new(
+        n_centres: usize,
+        sigma: f64,
+        normalised: bool,
+        input_space: LinearSpace<Interval>,
+    ) -> Self
+    {
+        assert!(n_centres > 0, "RBF requires at least one centre per dimension.");
+        assert!(sigma > 0.0, "RBF bandwidth must be positive.");
+
+        let grids: Vec<Vec<f64>> = space_limits(&input_space)
+            .into_iter()
+            .map(|(lb, ub)| if n_centres == 1 {
+                vec![(lb + ub) / 2.0]
+            } else {
+                Vector::linspace(lb, ub, n_centres).to_vec()
+            })
+            .collect();
+
+        RBF {
+            centres: cartesian_product(&grids),
+            sigma,
+            normalised,
+        }
+    }
+
+    /// Create an RBF projector with centres specified explicitly.
+    pub fn from_centres(centres: Vec<Vec<f64>>, sigma: f64, normalised: bool) -> Self {
+        assert!(sigma > 0.0, "RBF bandwidth must be positive.");
+
+        RBF {
+            centres,
+            sigma,
+            normalised,
+        }
+    }
+}
+
+impl Space for RBF {
+    type Value = Projection;
+
+    fn dim(&self) -> usize { self.centres.len() }
+
+    fn card(&self) -> Card { Card::Infinite }
+}
+
+impl Projector<[f64]> for RBF {
+    fn project(&self, input: &[f64]) -> Projection {
+        let denom = 2.0 * self.sigma * self.sigma;
+        let phi = Vector::from_iter(self.centres.iter().map(|c| {
+            let d2 = c.iter().zip(input.iter()).fold(0.0, |acc, (c, x)| acc + (x - c) * (x - c));
+
+            (-d2 / denom).exp()
+        }));
+
+        if self.normalised {
+            let z = phi.sum();
+
+            if z > 0.0 {
+                return Projection::Dense(phi / z);
+            }
+        }
+
+        Projection::Dense(phi)
+    }
+}
+
+impl_array_proxies!(RBF);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activations(rbf: &RBF, input: &[f64]) -> Vector<f64> {
+        match rbf.project(input) {
+            Projection::Dense(phi) => phi,
+            _ => panic!("RBF should yield a dense projection."),
+        }
+    }
+
+    #[test]
+    fn test_dim() {
+        let space = LinearSpace::empty() + Interval::bounded(0.0, 1.0) + Interval::bounded(0.0, 1.0);
+
+        assert_eq!(RBF::new(5, 0.1, false, space.clone()).dim(), 25);
+        assert_eq!(RBF::new(1, 0.1, false, space).dim(), 1);
+    }
+
+    #[test]
+    fn test_peak_at_centre() {
+        let rbf = RBF::from_centres(vec![vec![0.0, 0.0], vec![1.0, 1.0]], 0.5, false);
+
+        let phi = activations(&rbf, &[0.0, 0.0]);
+        assert!((phi[0] - 1.0).abs() < 1e-7);
+        assert!(phi[1] < phi[0]);
+
+        let phi = activations(&rbf, &[1.0, 1.0]);
+        assert!((phi[1] - 1.0).abs() < 1e-7);
+        assert!(phi[0] < phi[1]);
+    }
+
+    #[test]
+    fn test_monotonic_decay() {
+        let rbf = RBF::from_centres(vec![vec![0.0]], 0.5, false);
+
+        let mut prev = activations(&rbf, &[0.0])[0];
+
+        for i in 1..20 {
+            let next = activations(&rbf, &[i as f64 * 0.1])[0];
+
+            assert!(next < prev);
+            prev = next;
+        }
+    }
+
+    #[test]
+    fn test_normalised() {
+        let space = LinearSpace::empty() + Interval::bounded(0.0, 1.0) + Interval::bounded(0.0, 1.0);
+        let rbf = RBF::new(3, 0.25, true, space);
+
+        for input in &[[0.0, 0.0], [0.3, 0.7], [1.0, 0.5]] {
+            let phi = activations(&rbf, input);
+
+            assert!((phi.sum() - 1.0).abs() < 1e-7);
+        }
+    }
+}
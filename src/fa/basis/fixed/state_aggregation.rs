@@ -0,0 +1,123 @@
+use crate::geometry::{
+    continuous::Interval,
+    product::LinearSpace,
+    Card,
+    Space,
+};
+use super::{space_limits, Projection, Projector};
+
+/// State aggregation basis projector.
+///
+/// The input space is divided into a regular grid with `resolution[d]` cells
+/// along dimension `d`, and every input is mapped to the single group index of
+/// the cell containing it. The resulting one-hot sparse projection makes the
+/// linear approximator equivalent to a table over the groups. Inputs outside
+/// the bounds of the space are clipped to the nearest cell.
+///
+/// # References
+/// - Sutton, R. S., & Barto, A. G. (2018). Reinforcement learning: An
+/// introduction (2nd ed.), Section 9.3. MIT Press.
+#[derive(Clone, Debug)]
+pub struct StateAggregation {
+    limits: Vec<(f64, f64)>,
+    resolution: Vec<usize>,
+}
+
+impl StateAggregation {
+    pub fn new(input_space: LinearSpace<Interval>, resolution: Vec<usize>) -> Self {
+        let limits = space_limits(&input_space);
+
+        assert_eq!(
+            limits.len(),
+            resolution.len(),
+            "State aggregation requires a resolution for every dimension."
+        );
+        assert!(
+            resolution.iter().all(|&n| n > 0),
+            "State aggregation requires at least one cell per dimension."
+        );
+
+        StateAggregation {
+            limits,
+            resolution,
+        }
+    }
+
+    /// Return the index of the group containing `input`.
+    pub fn group(&self, input: &[f64]) -> usize {
+        input
+            .iter()
+            .zip(self.limits.iter().zip(self.resolution.iter()))
+            .fold(0, |index, (x, (&(lb, ub), &n))| {
+                let coord = ((clip!(lb, *x, ub) - lb) / (ub - lb) * n as f64).floor() as usize;
+
+                index * n + coord.min(n - 1)
+            })
+    }
+}
+
+impl Space for StateAggregation {
+    type Value = Projection;
+
+    fn dim(&self) -> usize { self.resolution.iter().product() }
+
+    fn card(&self) -> Card { Card::Infinite }
+}
+
+impl Projector<[f64]> for StateAggregation {
+    fn project(&self, input: &[f64]) -> Projection {
+        Projection::Sparse(Some(self.group(input)).into_iter().collect())
+    }
+}
+
+impl_array_proxies!(StateAggregation);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aggregation() -> StateAggregation {
+        let space = LinearSpace::empty() + Interval::bounded(0.0, 1.0) + Interval::bounded(-1.0, 1.0);
+
+        StateAggregation::new(space, vec![4, 2])
+    }
+
+    fn indices(p: &StateAggregation, input: &[f64]) -> Vec<usize> {
+        match p.project(input) {
+            Projection::Sparse(ref indices) => indices.iter().cloned().collect(),
+            _ => panic!("State aggregation should yield a sparse projection."),
+        }
+    }
+
+    #[test]
+    fn test_dim() {
+        assert_eq!(aggregation().dim(), 8);
+    }
+
+    #[test]
+    fn test_same_cell() {
+        let p = aggregation();
+
+        assert_eq!(indices(&p, &[0.05, -0.9]), indices(&p, &[0.2, -0.1]));
+        assert_eq!(indices(&p, &[0.8, 0.5]), indices(&p, &[1.0, 1.0]));
+        assert_eq!(indices(&p, &[0.05, -0.9]).len(), 1);
+    }
+
+    #[test]
+    fn test_different_cells() {
+        let p = aggregation();
+
+        let groups: Vec<usize> = [[0.1, -0.5], [0.1, 0.5], [0.3, -0.5], [0.6, 0.5], [0.9, -0.5]]
+            .iter()
+            .map(|x| p.group(x))
+            .collect();
+
+        for i in 0..groups.len() {
+            assert!(groups[i] < p.dim());
+
+            for j in (i + 1)..groups.len() {
+                assert_ne!(groups[i], groups[j]);
+            }
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use crate::geometry::{
+    continuous::Interval,
+    product::LinearSpace,
+    Card,
+    Space,
+};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use super::{space_limits, Projection, Projector};
+
+/// Tile coding over a bounded input space with asymmetrically offset grids.
+///
+/// Unlike `lfa`'s hashing `TileCoding`, which bins raw inputs, the input space
+/// is covered by `n_tilings` overlapping grids, each of which is divided into
+/// `tiles_per_dim` tiles along every dimension and displaced by a distinct,
+/// asymmetric offset. Any input activates exactly one tile per tiling,
+/// yielding a sparse binary representation.
+///
+/// By default every tile is assigned a unique feature index. Alternatively,
+/// tile coordinates can be hashed into a table of fixed size using
+/// `with_memory_size`, trading collisions for a smaller representation.
+///
+/// # References
+/// - Sutton, R. S., & Barto, A. G. (2018). Reinforcement learning: An
+/// introduction (2nd ed.), Section 9.5.4. MIT Press.
+#[derive(Clone, Debug)]
+pub struct AsymmetricTileCoding {
+    n_tilings: usize,
+    tiles_per_dim: usize,
+
+    limits: Vec<(f64, f64)>,
+    memory_size: Option<usize>,
+}
+
+impl AsymmetricTileCoding {
+    pub fn new(n_tilings: usize, tiles_per_dim: usize, input_space: LinearSpace<Interval>) -> Self {
+        assert!(n_tilings > 0, "Tile coding requires at least one tiling.");
+        assert!(tiles_per_dim > 0, "Tile coding requires at least one tile per dimension.");
+
+        AsymmetricTileCoding {
+            n_tilings,
+            tiles_per_dim,
+
+            limits: space_limits(&input_space),
+            memory_size: None,
+        }
+    }
+
+    /// Hash all tiles into a table with `size` entries.
+    pub fn with_memory_size(mut self, size: usize) -> Self {
+        assert!(size > 0, "Memory size must be positive.");
+
+        self.memory_size = Some(size);
+
+        self
+    }
+
+    /// The number of tiles in each tiling, accounting for the extra tile per
+    /// dimension required to cover the displaced grids.
+    fn tiling_size(&self) -> usize { (self.tiles_per_dim + 1).pow(self.limits.len() as u32) }
+
+    fn full_size(&self) -> usize { self.n_tilings * self.tiling_size() }
+
+    fn tile_index(&self, tiling: usize, input: &[f64]) -> usize {
+        let offset_scale = (2 * tiling + 1) as f64 / (2 * self.n_tilings) as f64;
+
+        let mut index = 0;
+
+        for (d, (x, &(lb, ub))) in input.iter().zip(self.limits.iter()).enumerate() {
+            let width = (ub - lb) / self.tiles_per_dim as f64;
+            let offset = offset_scale * (2 * d + 1) as f64 % 1.0;
+
+            let coord = ((clip!(lb, *x, ub) - lb) / width + offset).floor() as usize;
+            let coord = coord.min(self.tiles_per_dim);
+
+            index = index * (self.tiles_per_dim + 1) + coord;
+        }
+
+        tiling * self.tiling_size() + index
+    }
+}
+
+impl Space for AsymmetricTileCoding {
+    type Value = Projection;
+
+    fn dim(&self) -> usize { self.memory_size.unwrap_or_else(|| self.full_size()) }
+
+    fn card(&self) -> Card { Card::Infinite }
+}
+
+impl Projector<[f64]> for AsymmetricTileCoding {
+    fn project(&self, input: &[f64]) -> Projection {
+        let indices: Vec<usize> = (0..self.n_tilings)
+            .map(|t| {
+                let idx = self.tile_index(t, input);
+
+                match self.memory_size {
+                    Some(size) => {
+                        let mut hasher = DefaultHasher::new();
+                        idx.hash(&mut hasher);
+
+                        (hasher.finish() % size as u64) as usize
+                    },
+                    None => idx,
+                }
+            })
+            .collect();
+
+        Projection::Sparse(indices.into_iter().collect())
+    }
+}
+
+impl_array_proxies!(AsymmetricTileCoding);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn active(t: &AsymmetricTileCoding, input: &[f64]) -> HashSet<usize> {
+        match t.project(input) {
+            Projection::Sparse(ref indices) => indices.iter().cloned().collect(),
+            _ => panic!("Tile coding should yield a sparse projection."),
+        }
+    }
+
+    fn unit_square() -> LinearSpace<Interval> {
+        LinearSpace::empty() + Interval::bounded(0.0, 1.0) + Interval::bounded(0.0, 1.0)
+    }
+
+    #[test]
+    fn test_dim() {
+        let t = AsymmetricTileCoding::new(8, 10, unit_square());
+        assert_eq!(t.dim(), 8 * 11 * 11);
+
+        let t = AsymmetricTileCoding::new(8, 10, unit_square()).with_memory_size(128);
+        assert_eq!(t.dim(), 128);
+    }
+
+    #[test]
+    fn test_one_tile_per_tiling() {
+        let t = AsymmetricTileCoding::new(8, 10, unit_square());
+
+        for input in &[[0.0, 0.0], [0.5, 0.25], [1.0, 1.0]] {
+            let indices = active(&t, input);
+
+            assert_eq!(indices.len(), 8);
+            assert!(indices.iter().all(|&i| i < t.dim()));
+        }
+    }
+
+    #[test]
+    fn test_nearby_inputs() {
+        let t = AsymmetricTileCoding::new(8, 10, unit_square());
+
+        let a = active(&t, &[0.5, 0.5]);
+        let b = active(&t, &[0.51, 0.5]);
+
+        assert!(a.intersection(&b).count() >= 6);
+    }
+
+    #[test]
+    fn test_distant_inputs() {
+        let t = AsymmetricTileCoding::new(8, 10, unit_square());
+
+        let a = active(&t, &[0.1, 0.1]);
+        let b = active(&t, &[0.9, 0.9]);
+
+        assert_eq!(a.intersection(&b).count(), 0);
+    }
+
+    #[test]
+    fn test_hashing() {
+        let t = AsymmetricTileCoding::new(8, 10, unit_square()).with_memory_size(64);
+
+        for input in &[[0.0, 0.0], [0.5, 0.25], [1.0, 1.0]] {
+            let indices = active(&t, input);
+
+            assert!(indices.len() <= 8);
+            assert!(indices.iter().all(|&i| i < 64));
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Basis projection module.
+//!
+//! This module re-exports the projectors provided by the `lfa` crate and
+//! extends them with additional representations.
+use crate::geometry::{
+    continuous::Interval,
+    product::LinearSpace,
+    BoundedSpace,
+    Vector,
+};
+
+pub use lfa::basis::*;
+
+macro_rules! impl_array_proxies {
+    ($type:ty) => {
+        impl Projector<Vec<f64>> for $type {
+            fn project(&self, input: &Vec<f64>) -> Projection {
+                Projector::<[f64]>::project(self, input.as_slice())
+            }
+        }
+
+        impl Projector<$crate::geometry::Vector<f64>> for $type {
+            fn project(&self, input: &$crate::geometry::Vector<f64>) -> Projection {
+                Projector::<[f64]>::project(self, input.as_slice().unwrap())
+            }
+        }
+    };
+}
+
+/// Extract the (lower, upper) bounds of each dimension of a bounded input space.
+pub(self) fn space_limits(space: &LinearSpace<Interval>) -> Vec<(f64, f64)> {
+    space
+        .iter()
+        .map(|d| (
+            d.inf().expect("Projectors require a bounded input space."),
+            d.sup().expect("Projectors require a bounded input space."),
+        ))
+        .collect()
+}
+
+pub mod fixed;
+
+mod scale;
+pub use self::scale::Scale;
+
+mod stack;
+pub use self::stack::Stack;
+
+mod standardize;
+pub use self::standardize::Standardize;
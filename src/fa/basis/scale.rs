@@ -0,0 +1,97 @@
+use crate::geometry::{
+    continuous::Interval,
+    product::LinearSpace,
+    Card,
+    Space,
+    Vector,
+};
+use super::{space_limits, Projection, Projector};
+
+/// Projector wrapper that linearly rescales each input dimension to the unit
+/// interval before delegating to the inner projector.
+///
+/// Values lying outside the bounds of the input space are clipped to the
+/// nearest boundary of `[0, 1]`.
+#[derive(Clone, Debug)]
+pub struct Scale<P> {
+    projector: P,
+    limits: Vec<(f64, f64)>,
+}
+
+impl<P> Scale<P> {
+    pub fn new(projector: P, input_space: LinearSpace<Interval>) -> Self {
+        Scale {
+            projector,
+            limits: space_limits(&input_space),
+        }
+    }
+
+    /// Rescale an input vector to the unit hypercube.
+    pub fn rescale(&self, input: &[f64]) -> Vec<f64> {
+        input
+            .iter()
+            .zip(self.limits.iter())
+            .map(|(x, &(lb, ub))| (clip!(lb, *x, ub) - lb) / (ub - lb))
+            .collect()
+    }
+}
+
+impl<P: Space> Space for Scale<P> {
+    type Value = Projection;
+
+    fn dim(&self) -> usize { self.projector.dim() }
+
+    fn card(&self) -> Card { self.projector.card() }
+}
+
+impl<P: Projector<[f64]>> Projector<[f64]> for Scale<P> {
+    fn project(&self, input: &[f64]) -> Projection {
+        self.projector.project(self.rescale(input).as_slice())
+    }
+}
+
+impl<P: Projector<[f64]>> Projector<Vec<f64>> for Scale<P> {
+    fn project(&self, input: &Vec<f64>) -> Projection {
+        Projector::<[f64]>::project(self, input.as_slice())
+    }
+}
+
+impl<P: Projector<[f64]>> Projector<Vector<f64>> for Scale<P> {
+    fn project(&self, input: &Vector<f64>) -> Projection {
+        Projector::<[f64]>::project(self, input.as_slice().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fa::mocking::Identity;
+
+    fn scale() -> Scale<Identity> {
+        Scale::new(
+            Identity(2),
+            LinearSpace::empty() + Interval::bounded(-1.0, 3.0) + Interval::bounded(10.0, 20.0),
+        )
+    }
+
+    #[test]
+    fn test_dim() {
+        assert_eq!(scale().dim(), 2);
+    }
+
+    #[test]
+    fn test_midpoint() {
+        let s = scale();
+
+        assert_eq!(s.rescale(&[1.0, 15.0]), vec![0.5, 0.5]);
+        assert_eq!(s.project(&vec![1.0, 15.0]).expanded(2), Vector::from_vec(vec![0.5, 0.5]));
+    }
+
+    #[test]
+    fn test_bounds() {
+        let s = scale();
+
+        assert_eq!(s.rescale(&[-1.0, 20.0]), vec![0.0, 1.0]);
+        assert_eq!(s.rescale(&[-10.0, 100.0]), vec![0.0, 1.0]);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::geometry::{Card, Space, Vector};
+use super::{Projection, Projector};
+
+/// Projector that concatenates the features of two inner projectors.
+///
+/// If both inner projections are sparse then the result is also sparse, with
+/// the indices of the second projector offset by the dimensionality of the
+/// first. Otherwise, both projections are expanded and concatenated into a
+/// single dense vector. More than two projectors can be combined by nesting
+/// stacks.
+#[derive(Clone, Debug)]
+pub struct Stack<P1, P2> {
+    p1: P1,
+    p2: P2,
+}
+
+impl<P1, P2> Stack<P1, P2> {
+    pub fn new(p1: P1, p2: P2) -> Self { Stack { p1, p2 } }
+}
+
+impl<P1: Space, P2: Space> Space for Stack<P1, P2> {
+    type Value = Projection;
+
+    fn dim(&self) -> usize { self.p1.dim() + self.p2.dim() }
+
+    fn card(&self) -> Card { Card::Infinite }
+}
+
+impl<I: ?Sized, P1: Projector<I>, P2: Projector<I>> Projector<I> for Stack<P1, P2> {
+    fn project(&self, input: &I) -> Projection {
+        let offset = self.p1.dim();
+
+        match (self.p1.project(input), self.p2.project(input)) {
+            (Projection::Sparse(a), Projection::Sparse(b)) => {
+                let indices: Vec<usize> = a
+                    .iter()
+                    .cloned()
+                    .chain(b.iter().map(|i| i + offset))
+                    .collect();
+
+                Projection::Sparse(indices.into_iter().collect())
+            },
+            (a, b) => {
+                let a = a.expanded(offset);
+                let b = b.expanded(self.p2.dim());
+
+                Projection::Dense(Vector::from_iter(a.iter().chain(b.iter()).cloned()))
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fa::mocking::OneHot;
+
+    struct Ramp(usize);
+
+    impl Space for Ramp {
+        type Value = Projection;
+
+        fn dim(&self) -> usize { self.0 }
+
+        fn card(&self) -> Card { Card::Infinite }
+    }
+
+    impl Projector<usize> for Ramp {
+        fn project(&self, input: &usize) -> Projection {
+            Projection::Dense(Vector::from_iter((0..self.0).map(|i| (i + *input) as f64)))
+        }
+    }
+
+    #[test]
+    fn test_dim() {
+        assert_eq!(Stack::new(OneHot::new(5), Ramp(3)).dim(), 8);
+        assert_eq!(Stack::new(Stack::new(OneHot::new(5), Ramp(3)), OneHot::new(2)).dim(), 10);
+    }
+
+    #[test]
+    fn test_sparse_dense() {
+        let p = Stack::new(OneHot::new(3), Ramp(2));
+        let phi = p.project(&2);
+
+        match phi {
+            Projection::Dense(_) => {},
+            _ => panic!("Stacking a dense projection should yield a dense projection."),
+        }
+
+        assert_eq!(phi.expanded(p.dim()), Vector::from_vec(vec![0.0, 0.0, 1.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    fn test_sparse_offsets() {
+        let p = Stack::new(OneHot::new(3), OneHot::new(3));
+
+        match p.project(&1) {
+            Projection::Sparse(indices) => {
+                let mut indices: Vec<usize> = indices.iter().cloned().collect();
+                indices.sort();
+
+                assert_eq!(indices, vec![1, 4]);
+            },
+            _ => panic!("Stacking sparse projections should yield a sparse projection."),
+        }
+
+        assert_eq!(p.project(&2).expanded(6), Vector::from_vec(vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0]));
+    }
+}
@@ -0,0 +1,161 @@
+use crate::core::RunningStatistics;
+use crate::geometry::{Card, Space, Vector};
+use super::{Projection, Projector};
+
+/// Projector wrapper that standardises the features of an inner projector
+/// using running estimates of their mean and (population) variance.
+///
+/// Each output is given by `(phi - mean) / sqrt(var + epsilon)`. Projection
+/// never alters the statistics: they are updated explicitly through
+/// `observe`, which has no effect outside of training mode (the default is
+/// training); use `set_training(false)` to freeze them for evaluation. Before
+/// any samples have been observed the mean is taken as zero and the variance
+/// as one. The output is always dense.
+#[derive(Clone, Debug)]
+pub struct Standardize<P> {
+    projector: P,
+    stats: Vec<RunningStatistics>,
+
+    epsilon: f64,
+    training: bool,
+}
+
+impl<P: Space> Standardize<P> {
+    pub fn new(projector: P) -> Self {
+        let dim = projector.dim();
+
+        Standardize {
+            projector,
+            stats: vec![RunningStatistics::new(); dim],
+
+            epsilon: 1e-8,
+            training: true,
+        }
+    }
+}
+
+impl<P> Standardize<P> {
+    /// Set the constant added to the variance for numerical stability.
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+
+        self
+    }
+
+    /// Toggle whether calls to `observe` update the feature statistics.
+    pub fn set_training(&mut self, training: bool) { self.training = training; }
+
+    pub fn is_training(&self) -> bool { self.training }
+
+    /// Return the current estimate of the mean of each feature.
+    pub fn mean(&self) -> Vector<f64> { self.stats.iter().map(|s| s.mean()).collect() }
+
+    /// Return the current estimate of the variance of each feature.
+    pub fn variance(&self) -> Vector<f64> {
+        self.stats.iter().map(|s| if s.count() == 0 { 1.0 } else { s.variance() }).collect()
+    }
+}
+
+impl<P: Space> Standardize<P> {
+    /// Update the feature statistics with the projection of `input`, if in
+    /// training mode.
+    pub fn observe<I: ?Sized>(&mut self, input: &I) where P: Projector<I> {
+        if self.training {
+            let phi = self.projector.project(input).expanded(self.projector.dim());
+
+            for (s, &x) in self.stats.iter_mut().zip(phi.iter()) {
+                s.update(x);
+            }
+        }
+    }
+}
+
+impl<P: Space> Space for Standardize<P> {
+    type Value = Projection;
+
+    fn dim(&self) -> usize { self.projector.dim() }
+
+    fn card(&self) -> Card { Card::Infinite }
+}
+
+impl<I: ?Sized, P: Projector<I>> Projector<I> for Standardize<P> {
+    fn project(&self, input: &I) -> Projection {
+        let phi = self.projector.project(input).expanded(self.projector.dim());
+        let scale = self.variance().mapv(|v| (v + self.epsilon).sqrt());
+
+        Projection::Dense((phi - &self.mean()) / scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fa::mocking::Identity;
+
+    fn stream() -> Vec<[f64; 2]> {
+        // Heavily skewed features with a period of 10 samples:
+        (0..1000).map(|i| {
+            let k = (i % 10) as f64;
+
+            [k * k * k, 100.0 + (-k).exp()]
+        }).collect()
+    }
+
+    #[test]
+    fn test_dim() {
+        assert_eq!(Standardize::new(Identity(3)).dim(), 3);
+    }
+
+    #[test]
+    fn test_standardised_moments() {
+        let mut p = Standardize::new(Identity(2));
+
+        for x in stream() {
+            p.observe(&x[..]);
+        }
+
+        p.set_training(false);
+
+        let outputs: Vec<Vector<f64>> = stream()
+            .iter()
+            .map(|x| p.project(&x[..]).expanded(2))
+            .collect();
+        let n = outputs.len() as f64;
+
+        for j in 0..2 {
+            let mean = outputs.iter().map(|o| o[j]).sum::<f64>() / n;
+            let var = outputs.iter().map(|o| (o[j] - mean).powi(2)).sum::<f64>() / n;
+
+            assert!(mean.abs() < 1e-6);
+            assert!((var - 1.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_project_is_pure() {
+        let p = Standardize::new(Identity(2));
+
+        p.project(&[1.0, 2.0][..]);
+
+        assert_eq!(p.mean(), Vector::zeros(2));
+        assert_eq!(p.variance(), Vector::ones(2));
+    }
+
+    #[test]
+    fn test_frozen_statistics() {
+        let mut p = Standardize::new(Identity(2));
+
+        for x in stream().iter().take(100) {
+            p.observe(&x[..]);
+        }
+
+        p.set_training(false);
+
+        let (mean, var) = (p.mean(), p.variance());
+
+        p.observe(&[1e6, -1e6][..]);
+
+        assert_eq!(p.mean(), mean);
+        assert_eq!(p.variance(), var);
+    }
+}
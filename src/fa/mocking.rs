@@ -1,6 +1,6 @@
 use crate::core::{make_shared, Shared};
-use crate::fa::{Approximator, EvaluationResult, QFunction, UpdateResult, VFunction};
-use crate::geometry::Vector;
+use crate::fa::{Approximator, EvaluationResult, Projection, Projector, QFunction, UpdateResult, VFunction};
+use crate::geometry::{Card, Space, Vector};
 use std::marker::PhantomData;
 
 pub struct MockQ {
@@ -42,3 +42,41 @@ impl Approximator<Vector<f64>> for MockQ {
 }
 
 impl QFunction<Vector<f64>> for MockQ {}
+
+/// One-hot projector over the states of a finite chain.
+#[derive(Clone, Debug)]
+pub struct OneHot(usize);
+
+impl OneHot {
+    pub fn new(n_states: usize) -> Self { OneHot(n_states) }
+}
+
+impl Space for OneHot {
+    type Value = Projection;
+
+    fn dim(&self) -> usize { self.0 }
+
+    fn card(&self) -> Card { Card::Infinite }
+}
+
+impl Projector<usize> for OneHot {
+    fn project(&self, input: &usize) -> Projection { Projection::Sparse(Some(*input).into_iter().collect()) }
+}
+
+/// Projector that passes a slice of features through unchanged.
+#[derive(Clone, Debug)]
+pub struct Identity(pub usize);
+
+impl Space for Identity {
+    type Value = Projection;
+
+    fn dim(&self) -> usize { self.0 }
+
+    fn card(&self) -> Card { Card::Infinite }
+}
+
+impl Projector<[f64]> for Identity {
+    fn project(&self, input: &[f64]) -> Projection {
+        Projection::Dense(Vector::from_vec(input.to_vec()))
+    }
+}
@@ -1,15 +1,11 @@
 //! Function approximation and value function representation module.
 use crate::core::Shared;
-use crate::geometry::Vector;
+use crate::geometry::{Matrix, Space, Vector};
+use ndarray::ArrayView2;
 
 extern crate lfa;
 pub use self::lfa::{
     approximators::*,
-    basis::{
-        self,
-        Projector,
-        Projection,
-    },
     core::{
         AdaptResult,
         EvaluationResult,
@@ -19,18 +15,199 @@ pub use self::lfa::{
     LFA,
 };
 
+pub mod basis;
+pub use self::basis::{Projector, Projection};
+
 #[cfg(test)]
 pub(crate) mod mocking;
 
 mod table;
 pub use self::table::Table;
 
+mod nan_guard;
+pub use self::nan_guard::{Guarded, NanGuard, NanPolicy};
+
 pub type ScalarLFA<P> = LFA<P, ScalarFunction>;
 pub type VectorLFA<P> = LFA<P, VectorFunction>;
 
+pub type MatrixView<'a, T> = ArrayView2<'a, T>;
+
+/// Implement `Parameterised`, `WeightsView` and `ParameterisedMut` for a type
+/// by forwarding to a `Shared` function approximator held in one of its fields,
+/// e.g. `delegate_weights!(QLearning<Q, P, G, A, T>, q_func: Q)`.
+macro_rules! delegate_weights {
+    ($type:ident < $($param:ident),* >, $field:ident : $fa:ident) => {
+        impl<$($param),*> $crate::fa::Parameterised for $type<$($param),*>
+        where $fa: $crate::fa::Parameterised
+        {
+            fn weights(&self) -> $crate::geometry::Matrix<f64> {
+                $crate::fa::Parameterised::weights(&*self.$field)
+            }
+        }
+
+        impl<$($param),*> $crate::fa::WeightsView for $type<$($param),*>
+        where $fa: $crate::fa::WeightsView
+        {
+            fn weights_view(&self) -> $crate::fa::MatrixView<f64> {
+                $crate::fa::WeightsView::weights_view(&*self.$field)
+            }
+        }
+
+        impl<$($param),*> $crate::fa::ParameterisedMut for $type<$($param),*>
+        where $fa: $crate::fa::ParameterisedMut
+        {
+            fn set_weights(&mut self, weights: $crate::geometry::Matrix<f64>) {
+                $crate::fa::ParameterisedMut::set_weights(&mut *self.$field.borrow_mut(), weights)
+            }
+        }
+    };
+}
+
+pub(crate) use delegate_weights;
+
 pub type SharedVFunction<S> = Shared<VFunction<S, Value = f64>>;
 pub type SharedQFunction<S> = Shared<QFunction<S, Value = Vector<f64>>>;
 
+fn assert_feature_dim(projector_dim: usize, n_rows: usize) {
+    assert!(
+        projector_dim == n_rows,
+        "Projector produces {} features, but the weights have {} rows; was the basis changed \
+         without rebuilding the approximator?",
+        projector_dim,
+        n_rows,
+    );
+}
+
+/// Construct a `ScalarLFA` from a projector and an existing weight vector.
+///
+/// # Panics
+/// If the length of `weights` differs from `projector.dim()`.
+pub fn scalar_lfa<P: Space>(projector: P, weights: Vector<f64>) -> ScalarLFA<P> {
+    assert_feature_dim(projector.dim(), weights.len());
+
+    let mut lfa = LFA::scalar_output(projector);
+
+    lfa.approximator.weights = weights;
+    lfa
+}
+
+/// Construct a `VectorLFA` from a projector and an existing weight matrix, with
+/// one row per feature and one column per output.
+///
+/// # Panics
+/// If the number of rows of `weights` differs from `projector.dim()`.
+pub fn vector_lfa<P: Space>(projector: P, weights: Matrix<f64>) -> VectorLFA<P> {
+    assert_feature_dim(projector.dim(), weights.rows());
+
+    let mut lfa = LFA::vector_output(projector, weights.cols());
+
+    lfa.approximator.weights = weights;
+    lfa
+}
+
+/// An interface for accessing the weights of a `Parameterised` type without
+/// copying them.
+pub trait WeightsView: Parameterised {
+    /// Return a view of the weight matrix, with one row per feature and one
+    /// column per output.
+    fn weights_view(&self) -> MatrixView<f64>;
+
+    /// Return the dimensions of the weight matrix as (n_features, n_outputs).
+    fn weight_dim(&self) -> (usize, usize) { self.weights_view().dim() }
+}
+
+impl<P> WeightsView for ScalarLFA<P> where ScalarLFA<P>: Parameterised {
+    fn weights_view(&self) -> MatrixView<f64> {
+        let weights = &self.approximator.weights;
+
+        weights.view().into_shape((weights.len(), 1)).unwrap()
+    }
+}
+
+impl<P> WeightsView for VectorLFA<P> where VectorLFA<P>: Parameterised {
+    fn weights_view(&self) -> MatrixView<f64> { self.approximator.weights.view() }
+}
+
+/// An interface for overwriting the weights of a `Parameterised` type, e.g. to
+/// restore a checkpoint or synchronise a target network.
+pub trait ParameterisedMut: Parameterised {
+    /// Replace the weights with `weights`, which must have the same dimensions
+    /// as those returned by `WeightsView::weight_dim`.
+    ///
+    /// # Panics
+    /// If the supplied matrix has the wrong dimensions.
+    fn set_weights(&mut self, weights: Matrix<f64>);
+
+    /// Set every weight to `value` and return the result.
+    ///
+    /// For one-hot (tabular) projections this initialises every output to
+    /// `value`, e.g. for optimistic initialisation.
+    fn with_initial_value(mut self, value: f64) -> Self where Self: Sized {
+        let dim = self.weights().dim();
+
+        self.set_weights(Matrix::from_elem(dim, value));
+
+        self
+    }
+}
+
+impl<P> ParameterisedMut for ScalarLFA<P> where ScalarLFA<P>: Parameterised {
+    fn set_weights(&mut self, weights: Matrix<f64>) {
+        let n = self.approximator.weights.len();
+
+        assert_eq!(weights.dim(), (n, 1), "Weight matrix has incompatible dimensions.");
+
+        self.approximator.weights.assign(&weights.column(0));
+    }
+}
+
+impl<P> ParameterisedMut for VectorLFA<P> where VectorLFA<P>: Parameterised {
+    fn set_weights(&mut self, weights: Matrix<f64>) {
+        assert_eq!(
+            weights.dim(),
+            self.approximator.weights.dim(),
+            "Weight matrix has incompatible dimensions."
+        );
+
+        self.approximator.weights = weights;
+    }
+}
+
+/// An interface for approximators whose outputs are differentiable with
+/// respect to their weights, e.g. for use with external optimisers.
+pub trait Differentiable<S: ?Sized>: WeightsView {
+    /// Return the gradient of the output(s) with respect to the weights,
+    /// evaluated at `input`.
+    ///
+    /// The matrix has the same dimensions as `WeightsView::weights_view`, with
+    /// column `j` holding the gradient of output `j` with respect to the
+    /// weights in column `j`; output `j` does not depend on any other column.
+    fn gradient(&self, input: &S) -> Matrix<f64>;
+}
+
+impl<S: ?Sized, P: Projector<S>> Differentiable<S> for ScalarLFA<P> where ScalarLFA<P>: Parameterised {
+    fn gradient(&self, input: &S) -> Matrix<f64> {
+        let n = self.approximator.weights.len();
+
+        self.projector.project(input).expanded(n).into_shape((n, 1)).unwrap()
+    }
+}
+
+impl<S: ?Sized, P: Projector<S>> Differentiable<S> for VectorLFA<P> where VectorLFA<P>: Parameterised {
+    fn gradient(&self, input: &S) -> Matrix<f64> {
+        let (n, k) = self.approximator.weights.dim();
+        let phi = self.projector.project(input).expanded(n);
+
+        let mut grad = Matrix::zeros((n, k));
+
+        for mut col in grad.gencolumns_mut() {
+            col.assign(&phi);
+        }
+
+        grad
+    }
+}
+
 /// An interface for state-value functions.
 pub trait VFunction<S: ?Sized>: Approximator<S, Value = f64> {
     #[allow(unused_variables)]
@@ -38,9 +215,17 @@ pub trait VFunction<S: ?Sized>: Approximator<S, Value = f64> {
 
     #[allow(unused_variables)]
     fn update_phi(&mut self, phi: &Projection, update: f64) { unimplemented!() }
+
+    /// Return the dense feature vector associated with an input.
+    #[allow(unused_variables)]
+    fn features(&self, input: &S) -> Vector<f64> { unimplemented!() }
 }
 
 impl<S: ?Sized, P: Projector<S>> VFunction<S> for ScalarLFA<P> {
+    fn features(&self, input: &S) -> Vector<f64> {
+        self.projector.project(input).expanded(self.projector.dim())
+    }
+
     fn evaluate_phi(&self, phi: &Projection) -> f64 {
         self.evaluate_primal(phi).unwrap()
     }
@@ -72,6 +257,35 @@ pub trait QFunction<S: ?Sized>: Approximator<S, Value = Vector<f64>> {
     fn update_action_phi(&mut self, phi: &Projection, action: usize, update: f64) {
         unimplemented!()
     }
+
+    /// Apply a batch of single-action updates, one for each projection.
+    ///
+    /// The default implementation calls `update_action_phi` for each sample in
+    /// turn.
+    fn update_batch(&mut self, phis: &[Projection], actions: &[usize], updates: &[f64]) {
+        assert!(
+            phis.len() == actions.len() && actions.len() == updates.len(),
+            "Batch updates require equal numbers of projections, actions and updates."
+        );
+
+        for ((phi, &action), &update) in phis.iter().zip(actions.iter()).zip(updates.iter()) {
+            self.update_action_phi(phi, action, update);
+        }
+    }
+
+    /// Evaluate a batch of states, returning a matrix with one row per state.
+    fn evaluate_batch(&self, states: &[S]) -> Matrix<f64> where S: Sized {
+        let rows: Vec<Vector<f64>> = states.iter().map(|s| self.evaluate(s).unwrap()).collect();
+        let n_outputs = rows.first().map_or(0, |r| r.len());
+
+        let mut values = Matrix::zeros((states.len(), n_outputs));
+
+        for (mut row, qs) in values.genrows_mut().into_iter().zip(rows.iter()) {
+            row.assign(qs);
+        }
+
+        values
+    }
 }
 
 impl<S: ?Sized, P: Projector<S>> QFunction<S> for VectorLFA<P> {
@@ -104,6 +318,22 @@ impl<S: ?Sized, P: Projector<S>> QFunction<S> for VectorLFA<P> {
         let _ = self.approximator.update(phi, updates);
     }
 
+    fn evaluate_batch(&self, states: &[S]) -> Matrix<f64> where S: Sized {
+        let dim = self.projector.dim();
+        let mut phis = Matrix::zeros((states.len(), dim));
+
+        for (mut row, s) in phis.genrows_mut().into_iter().zip(states.iter()) {
+            match self.projector.project(s) {
+                Projection::Dense(dense) => row.assign(&dense),
+                Projection::Sparse(sparse) => for idx in sparse.iter() {
+                    row[*idx] = 1.0
+                },
+            }
+        }
+
+        phis.dot(&self.approximator.weights)
+    }
+
     fn update_action_phi(&mut self, phi: &Projection, action: usize, update: f64) {
         let mut col = self.approximator.weights.column_mut(action);
 
@@ -116,4 +346,263 @@ impl<S: ?Sized, P: Projector<S>> QFunction<S> for VectorLFA<P> {
             },
         }
     }
+
+    /// Accumulate the column updates of the whole batch before writing them to
+    /// the weight matrix in a single pass.
+    fn update_batch(&mut self, phis: &[Projection], actions: &[usize], updates: &[f64]) {
+        assert!(
+            phis.len() == actions.len() && actions.len() == updates.len(),
+            "Batch updates require equal numbers of projections, actions and updates."
+        );
+
+        let mut delta = Matrix::zeros(self.approximator.weights.dim());
+
+        for ((phi, &action), &update) in phis.iter().zip(actions.iter()).zip(updates.iter()) {
+            match *phi {
+                Projection::Dense(ref dense) => delta.column_mut(action).scaled_add(update, dense),
+                Projection::Sparse(ref sparse) => {
+                    for idx in sparse {
+                        delta[[*idx, action]] += update
+                    }
+                },
+            }
+        }
+
+        self.approximator.weights += &delta;
+    }
+}
+
+/// An interface for action-value functions that can be evaluated with an
+/// alternative set of weights, such as a Polyak average of their own.
+pub trait WeightedQFunction<S: ?Sized>: QFunction<S> + Parameterised {
+    /// Evaluate the function using a weight matrix of the same dimensions as
+    /// `Parameterised::weights`.
+    fn evaluate_with_weights(&self, input: &S, weights: &Matrix<f64>) -> Vector<f64>;
+}
+
+impl<S: ?Sized, P: Projector<S>> WeightedQFunction<S> for VectorLFA<P> {
+    fn evaluate_with_weights(&self, input: &S, weights: &Matrix<f64>) -> Vector<f64> {
+        self.projector.project(input).expanded(weights.rows()).dot(weights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{Domain, MountainCar};
+    use crate::fa::basis::fixed::Fourier;
+    use crate::geometry::Space;
+
+    #[test]
+    fn test_evaluate_batch() {
+        let domain = MountainCar::default();
+        let bases = Fourier::from_space(3, domain.state_space());
+        let mut q_func = LFA::vector_output(bases, 3);
+
+        for (i, w) in q_func.approximator.weights.iter_mut().enumerate() {
+            *w = (i as f64).sin();
+        }
+
+        let states: Vec<Vector<f64>> = (0..50).map(|i| {
+            let x = -1.2 + 1.8 * i as f64 / 49.0;
+            let v = -0.07 + 0.14 * ((7 * i) % 50) as f64 / 49.0;
+
+            Vector::from_vec(vec![x, v])
+        }).collect();
+
+        let batch = q_func.evaluate_batch(&states);
+
+        assert_eq!(batch.shape(), &[50, 3]);
+
+        for (row, s) in batch.genrows().into_iter().zip(states.iter()) {
+            for a in 0..3 {
+                assert!((row[a] - q_func.evaluate_action(s, a)).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_update_batch() {
+        let phis = vec![
+            Projection::Dense(vec![0.5, -1.0, 0.0, 2.0].into()),
+            Projection::Sparse(vec![1, 3].into_iter().collect()),
+            Projection::Sparse(vec![1].into_iter().collect()),
+            Projection::Dense(vec![1.0, 0.25, -0.5, 0.0].into()),
+        ];
+        let actions = [0, 1, 1, 0];
+        let updates = [0.1, -0.3, 0.7, 1.5];
+
+        let mut batched = LFA::vector_output(crate::fa::mocking::OneHot::new(4), 2);
+        let mut sequential = LFA::vector_output(crate::fa::mocking::OneHot::new(4), 2);
+
+        for (i, w) in batched.approximator.weights.iter_mut().enumerate() {
+            *w = (i as f64).cos();
+        }
+        sequential.approximator.weights.assign(&batched.approximator.weights);
+
+        QFunction::<usize>::update_batch(&mut batched, &phis, &actions, &updates);
+
+        for i in 0..4 {
+            QFunction::<usize>::update_action_phi(&mut sequential, &phis[i], actions[i], updates[i]);
+        }
+
+        assert!(batched.weights().all_close(&sequential.weights(), 1e-12));
+    }
+
+    #[test]
+    fn test_gradient() {
+        let domain = MountainCar::default();
+        let bases = Fourier::from_space(3, domain.state_space());
+        let mut v_func = LFA::scalar_output(bases.clone());
+
+        for (i, w) in v_func.approximator.weights.iter_mut().enumerate() {
+            *w = (i as f64).sin();
+        }
+
+        let s = Vector::from_vec(vec![-0.5, 0.01]);
+        let grad = v_func.gradient(&s);
+
+        assert_eq!(grad.dim(), v_func.weight_dim());
+        assert!(grad.column(0).all_close(&bases.project(&s).expanded(grad.rows()), 1e-12));
+
+        // A manual gradient step coincides with the approximator's own update,
+        // which normalises dense updates by the L1 norm of the features:
+        let (alpha, error) = (0.1, 2.5);
+        let l1 = grad.iter().map(|g| g.abs()).sum::<f64>();
+        let expected = v_func.weights() + &(alpha * error / l1 * &grad);
+
+        let _ = v_func.update(&s, alpha * error);
+
+        assert!(v_func.weights().all_close(&expected, 1e-12));
+    }
+
+    #[test]
+    fn test_initial_value() {
+        use crate::fa::mocking::OneHot;
+
+        let mut q_func = LFA::vector_output(OneHot::new(4), 2).with_initial_value(10.0);
+
+        for s in 0..4 {
+            assert!(q_func.evaluate(&s).unwrap().all_close(&Vector::from_elem(2, 10.0), 1e-12));
+        }
+
+        q_func.update_action(&2, 1, -3.0);
+
+        assert_eq!(q_func.evaluate_action(&2, 1), 7.0);
+        assert_eq!(q_func.evaluate_action(&2, 0), 10.0);
+
+        let v_func = LFA::scalar_output(OneHot::new(4)).with_initial_value(-1.0);
+
+        assert_eq!(v_func.evaluate(&0).unwrap(), -1.0);
+    }
+
+    #[test]
+    fn test_lfa_from_weights() {
+        use crate::fa::mocking::OneHot;
+
+        let q_func = vector_lfa(OneHot::new(3), Matrix::from_elem((3, 2), 1.5));
+
+        assert_eq!(q_func.evaluate_action(&2, 1), 1.5);
+
+        let v_func = scalar_lfa(OneHot::new(3), Vector::from_vec(vec![0.0, 1.0, 2.0]));
+
+        assert_eq!(v_func.evaluate(&2).unwrap(), 2.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Projector produces 4 features, but the weights have 3 rows")]
+    fn test_lfa_dimension_mismatch() {
+        let domain = MountainCar::default();
+
+        // A Fourier basis of order 1 over two dimensions has 4 features:
+        let bases = Fourier::from_space(1, domain.state_space());
+
+        vector_lfa(bases, Matrix::zeros((3, 3)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Projector produces 3 features, but the weights have 2 rows")]
+    fn test_scalar_lfa_dimension_mismatch() {
+        scalar_lfa(crate::fa::mocking::OneHot::new(3), Vector::zeros(2));
+    }
+
+    #[test]
+    fn test_weights_view() {
+        use crate::control::td::SARSA;
+        use crate::core::make_shared;
+        use crate::fa::mocking::OneHot;
+        use crate::policies::fixed::Random;
+
+        let q_func = make_shared(LFA::vector_output(OneHot::new(4), 2));
+
+        for (i, w) in q_func.borrow_mut().approximator.weights.iter_mut().enumerate() {
+            *w = i as f64 - 3.5;
+        }
+
+        assert_eq!(q_func.weight_dim(), (4, 2));
+        assert_eq!(q_func.weights_view(), q_func.weights().view());
+
+        let agent = SARSA::new(q_func.clone(), make_shared(Random::new(2)), 0.1, 0.9);
+
+        assert_eq!(agent.weight_dim(), (4, 2));
+        assert_eq!(agent.weights_view(), agent.weights().view());
+
+        let mut v_func = LFA::scalar_output(OneHot::new(3));
+
+        v_func.approximator.weights[1] = 2.0;
+
+        assert_eq!(v_func.weight_dim(), (3, 1));
+        assert!(v_func.weights_view().iter().eq(v_func.weights().iter()));
+    }
+
+    #[test]
+    fn test_set_weights() {
+        use crate::control::td::SARSA;
+        use crate::core::{make_shared, ActionValuePredictor};
+        use crate::fa::mocking::OneHot;
+        use crate::policies::fixed::Random;
+
+        let q_func = make_shared(LFA::vector_output(OneHot::new(4), 2));
+
+        for (i, w) in q_func.borrow_mut().approximator.weights.iter_mut().enumerate() {
+            *w = (i as f64).cos();
+        }
+
+        let mut agent = SARSA::new(q_func, make_shared(Random::new(2)), 0.1, 0.9);
+        let mut fresh = LFA::vector_output(OneHot::new(4), 2);
+
+        fresh.set_weights(agent.weights());
+
+        for s in 0..4 {
+            assert_eq!(fresh.evaluate(&s).unwrap(), agent.predict_qs(&s));
+        }
+
+        let mut other = SARSA::new(
+            make_shared(LFA::vector_output(OneHot::new(4), 2)),
+            make_shared(Random::new(2)),
+            0.1,
+            0.9,
+        );
+
+        other.set_weights(agent.weights());
+
+        assert_eq!(other.weights(), agent.weights());
+
+        let mut v_func = LFA::scalar_output(OneHot::new(3));
+        let mut weights = Matrix::zeros((3, 1));
+
+        weights[[2, 0]] = 5.0;
+        v_func.set_weights(weights);
+
+        assert_eq!(v_func.evaluate(&2).unwrap(), 5.0);
+        assert_eq!(v_func.evaluate(&0).unwrap(), 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_set_weights_dimensions() {
+        use crate::fa::mocking::OneHot;
+
+        LFA::vector_output(OneHot::new(4), 2).set_weights(Matrix::zeros((4, 3)));
+    }
 }
@@ -0,0 +1,220 @@
+use crate::geometry::{Matrix, Vector};
+use super::{
+    Approximator,
+    EvaluationResult,
+    MatrixView,
+    Parameterised,
+    ParameterisedMut,
+    Projection,
+    QFunction,
+    UpdateResult,
+    VFunction,
+    WeightedQFunction,
+    WeightsView,
+};
+
+/// Action taken by a `NanGuard` on encountering a non-finite update.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NanPolicy {
+    /// Panic, reporting the offending update.
+    Panic,
+
+    /// Discard the update entirely.
+    Skip,
+
+    /// Replace each non-finite component of the update with zero.
+    Clamp,
+}
+
+impl Default for NanPolicy {
+    fn default() -> NanPolicy { NanPolicy::Panic }
+}
+
+/// Update values that can be checked by a `NanGuard`.
+pub trait Guarded: Sized {
+    fn guard(self, policy: NanPolicy) -> Option<Self>;
+}
+
+impl Guarded for f64 {
+    fn guard(self, policy: NanPolicy) -> Option<f64> {
+        if self.is_finite() {
+            return Some(self);
+        }
+
+        match policy {
+            NanPolicy::Panic => panic!("Encountered non-finite update value: {}.", self),
+            NanPolicy::Skip => None,
+            NanPolicy::Clamp => Some(0.0),
+        }
+    }
+}
+
+impl Guarded for Vector<f64> {
+    fn guard(self, policy: NanPolicy) -> Option<Vector<f64>> {
+        if self.iter().all(|x| x.is_finite()) {
+            return Some(self);
+        }
+
+        match policy {
+            NanPolicy::Panic => panic!("Encountered non-finite update values: {}.", self),
+            NanPolicy::Skip => None,
+            NanPolicy::Clamp => Some(self.mapv(|x| if x.is_finite() { x } else { 0.0 })),
+        }
+    }
+}
+
+/// Wrapper that checks every update applied to a function approximator for
+/// `NaN` or infinite values before they can corrupt the weights.
+///
+/// How offending updates are handled is set by the `NanPolicy`, which
+/// defaults to `Panic`.
+pub struct NanGuard<F> {
+    pub approximator: F,
+    pub policy: NanPolicy,
+}
+
+impl<F> NanGuard<F> {
+    pub fn new(approximator: F) -> Self { NanGuard::with_policy(approximator, NanPolicy::default()) }
+
+    pub fn with_policy(approximator: F, policy: NanPolicy) -> Self {
+        NanGuard { approximator, policy }
+    }
+}
+
+impl<S: ?Sized, F> Approximator<S> for NanGuard<F>
+where
+    F: Approximator<S>,
+    F::Value: Guarded,
+{
+    type Value = F::Value;
+
+    fn n_outputs(&self) -> usize { self.approximator.n_outputs() }
+
+    fn evaluate(&self, input: &S) -> EvaluationResult<F::Value> { self.approximator.evaluate(input) }
+
+    fn update(&mut self, input: &S, update: F::Value) -> UpdateResult<()> {
+        match update.guard(self.policy) {
+            Some(update) => self.approximator.update(input, update),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<S: ?Sized, F: VFunction<S>> VFunction<S> for NanGuard<F> {
+    fn evaluate_phi(&self, phi: &Projection) -> f64 { self.approximator.evaluate_phi(phi) }
+
+    fn update_phi(&mut self, phi: &Projection, update: f64) {
+        if let Some(update) = update.guard(self.policy) {
+            self.approximator.update_phi(phi, update)
+        }
+    }
+
+    fn features(&self, input: &S) -> Vector<f64> { self.approximator.features(input) }
+}
+
+impl<S: ?Sized, F: QFunction<S>> QFunction<S> for NanGuard<F> {
+    fn evaluate_action(&self, input: &S, action: usize) -> f64 {
+        self.approximator.evaluate_action(input, action)
+    }
+
+    fn update_action(&mut self, input: &S, action: usize, update: f64) {
+        if let Some(update) = update.guard(self.policy) {
+            self.approximator.update_action(input, action, update)
+        }
+    }
+
+    fn evaluate_phi(&self, phi: &Projection) -> Vector<f64> { self.approximator.evaluate_phi(phi) }
+
+    fn evaluate_action_phi(&self, phi: &Projection, action: usize) -> f64 {
+        self.approximator.evaluate_action_phi(phi, action)
+    }
+
+    fn update_phi(&mut self, phi: &Projection, updates: Vector<f64>) {
+        if let Some(updates) = updates.guard(self.policy) {
+            self.approximator.update_phi(phi, updates)
+        }
+    }
+
+    fn update_action_phi(&mut self, phi: &Projection, action: usize, update: f64) {
+        if let Some(update) = update.guard(self.policy) {
+            self.approximator.update_action_phi(phi, action, update)
+        }
+    }
+
+    fn update_batch(&mut self, phis: &[Projection], actions: &[usize], updates: &[f64]) {
+        let updates: Vec<f64> = updates
+            .iter()
+            .map(|u| u.guard(self.policy).unwrap_or(0.0))
+            .collect();
+
+        self.approximator.update_batch(phis, actions, &updates)
+    }
+
+    fn evaluate_batch(&self, states: &[S]) -> Matrix<f64> where S: Sized {
+        self.approximator.evaluate_batch(states)
+    }
+}
+
+impl<S: ?Sized, F: WeightedQFunction<S>> WeightedQFunction<S> for NanGuard<F> {
+    fn evaluate_with_weights(&self, input: &S, weights: &Matrix<f64>) -> Vector<f64> {
+        self.approximator.evaluate_with_weights(input, weights)
+    }
+}
+
+impl<F: Parameterised> Parameterised for NanGuard<F> {
+    fn weights(&self) -> Matrix<f64> { self.approximator.weights() }
+}
+
+impl<F: WeightsView> WeightsView for NanGuard<F> {
+    fn weights_view(&self) -> MatrixView<f64> { self.approximator.weights_view() }
+}
+
+impl<F: ParameterisedMut> ParameterisedMut for NanGuard<F> {
+    fn set_weights(&mut self, weights: Matrix<f64>) {
+        assert!(weights.iter().all(|w| w.is_finite()), "Weights must be finite.");
+
+        self.approximator.set_weights(weights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fa::{mocking::OneHot, VectorLFA, LFA};
+
+    fn guarded(policy: NanPolicy) -> NanGuard<VectorLFA<OneHot>> {
+        NanGuard::with_policy(LFA::vector_output(OneHot::new(2), 2), policy)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_panic() {
+        guarded(NanPolicy::Panic).update_action(&0usize, 1, ::std::f64::NAN);
+    }
+
+    #[test]
+    fn test_skip() {
+        let mut q_func = guarded(NanPolicy::Skip);
+
+        q_func.update_action(&0usize, 1, ::std::f64::NAN);
+        QFunction::<usize>::update_phi(&mut q_func, &Projection::Sparse(vec![1].into_iter().collect()), vec![::std::f64::INFINITY, 1.0].into());
+
+        assert!(q_func.weights().iter().all(|&w| w == 0.0));
+
+        q_func.update_action(&0usize, 1, 0.5);
+
+        assert_eq!(q_func.evaluate_action(&0usize, 1), 0.5);
+    }
+
+    #[test]
+    fn test_clamp() {
+        let mut q_func = guarded(NanPolicy::Clamp);
+
+        q_func.update_action(&0usize, 1, ::std::f64::NAN);
+        QFunction::<usize>::update_phi(&mut q_func, &Projection::Sparse(vec![1].into_iter().collect()), vec![::std::f64::NAN, 1.0].into());
+
+        assert_eq!(q_func.evaluate_action(&0usize, 1), 0.0);
+        assert_eq!(q_func.evaluate_action(&1usize, 0), 0.0);
+        assert_eq!(q_func.evaluate_action(&1usize, 1), 1.0);
+    }
+}
@@ -1,7 +1,7 @@
-use super::{Approximator, EvaluationResult, UpdateResult};
+use crate::geometry::Vector;
+use super::{Approximator, EvaluationResult, QFunction, UpdateResult, VFunction};
 use std::collections::HashMap;
 use std::hash::Hash;
-use std::ops::AddAssign;
 
 /// Generic tabular function representation.
 ///
@@ -21,39 +21,164 @@ use std::ops::AddAssign;
 ///
 /// assert_eq!(f.evaluate(&(0, 1)).unwrap(), 1.0);
 /// ```
+///
+/// No enumeration of the state space is required up front: an entry is
+/// created the first time a state is updated, and entries that have never
+/// been updated evaluate to the table's initial value. This is `V::default()`
+/// unless the table was constructed with `Table::filled` or, for tables of
+/// action values, `Table::vector_output`.
 #[derive(Default, Clone, Serialize, Deserialize)]
-pub struct Table<K: Hash + Eq, V>(pub HashMap<K, V>);
+pub struct Table<K: Hash + Eq, V>(pub HashMap<K, V>, V);
+
+impl<K: Hash + Eq, V: Default> Table<K, V> {
+    pub fn new() -> Self { Table(HashMap::new(), V::default()) }
+}
 
 impl<K: Hash + Eq, V> Table<K, V> {
-    pub fn new() -> Self { Table(HashMap::new()) }
+    /// Construct an empty table in which every entry initially takes `value`.
+    pub fn filled(value: V) -> Self { Table(HashMap::new(), value) }
+}
+
+impl<K: Hash + Eq, V> Table<K, V> {
+    /// Return the number of states with an entry in the table.
+    pub fn len(&self) -> usize { self.0.len() }
+
+    pub fn is_empty(&self) -> bool { self.0.is_empty() }
+
+    /// Returns true if the table holds an entry for `state`.
+    pub fn contains(&self, state: &K) -> bool { self.0.contains_key(state) }
+}
+
+impl<K: Hash + Eq> Table<K, Vector<f64>> {
+    /// Construct an empty table of `n_outputs` values per state, suitable for
+    /// action-value functions.
+    pub fn vector_output(n_outputs: usize) -> Self { Table::filled(Vector::zeros(n_outputs)) }
 }
 
-// TODO: Have to deal with attempts to evaluate when no value is present.
-//       Really we need a map with defaults.
-//       The issue arises when we try to consider what the default value may be
-//       for the generic type O.
-impl<I, V> Approximator<I> for Table<I, V>
-where
-    I: Hash + Eq + Copy,
-    V: Copy + Default + AddAssign,
-{
-    type Value = V;
+impl<I: Hash + Eq + Clone> Approximator<I> for Table<I, f64> {
+    type Value = f64;
+
+    fn n_outputs(&self) -> usize { 1 }
 
-    fn n_outputs(&self) -> usize {
-        unimplemented!()
+    fn evaluate(&self, input: &I) -> EvaluationResult<f64> {
+        Ok(self.0.get(input).cloned().unwrap_or(self.1))
     }
 
-    fn evaluate(&self, input: &I) -> EvaluationResult<V> {
-        if self.0.contains_key(input) {
-            Ok(self.0[input])
-        } else {
-            Ok(V::default())
-        }
+    fn update(&mut self, input: &I, error: f64) -> UpdateResult<()> {
+        let init = self.1;
+
+        *self.0.entry(input.clone()).or_insert(init) += error;
+
+        Ok(())
+    }
+}
+
+impl<I: Hash + Eq + Clone> VFunction<I> for Table<I, f64> {}
+
+impl<I: Hash + Eq + Clone> Approximator<I> for Table<I, Vector<f64>> {
+    type Value = Vector<f64>;
+
+    fn n_outputs(&self) -> usize { self.1.len() }
+
+    fn evaluate(&self, input: &I) -> EvaluationResult<Vector<f64>> {
+        Ok(self.0.get(input).unwrap_or(&self.1).clone())
     }
 
-    fn update(&mut self, input: &I, error: V) -> UpdateResult<()> {
-        *self.0.entry(*input).or_insert_with(V::default) += error;
+    fn update(&mut self, input: &I, errors: Vector<f64>) -> UpdateResult<()> {
+        let init = &self.1;
+
+        *self.0.entry(input.clone()).or_insert_with(|| init.clone()) += &errors;
 
         Ok(())
     }
 }
+
+impl<I: Hash + Eq + Clone> QFunction<I> for Table<I, Vector<f64>> {
+    fn evaluate_action(&self, input: &I, action: usize) -> f64 {
+        self.0.get(input).unwrap_or(&self.1)[action]
+    }
+
+    fn update_action(&mut self, input: &I, action: usize, update: f64) {
+        let init = &self.1;
+
+        self.0.entry(input.clone()).or_insert_with(|| init.clone())[action] += update;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::td::QLearning;
+    use crate::core::{make_shared, Controller, SerialExperiment, run};
+    use crate::domains::GridWorld;
+    use crate::policies::fixed::Random;
+
+    #[test]
+    fn test_filled() {
+        let mut t = Table::<u32, f64>::filled(5.0);
+
+        for s in 0..10 {
+            assert_eq!(t.evaluate(&s).unwrap(), 5.0);
+        }
+
+        t.update(&3, -1.0).unwrap();
+        t.update(&3, -0.5).unwrap();
+
+        assert_eq!(t.evaluate(&3).unwrap(), 3.5);
+        assert_eq!(t.evaluate(&4).unwrap(), 5.0);
+    }
+
+    #[test]
+    fn test_scalar() {
+        let mut v_func = Table::<_, f64>::new();
+
+        assert_eq!(v_func.evaluate(&(1, 2)).unwrap(), 0.0);
+        assert!(v_func.is_empty());
+
+        v_func.update(&(1, 2), 0.5).unwrap();
+        v_func.update(&(1, 2), 0.25).unwrap();
+
+        assert_eq!(v_func.len(), 1);
+        assert_eq!(v_func.evaluate(&(1, 2)).unwrap(), 0.75);
+        assert_eq!(v_func.evaluate(&(2, 1)).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_lazy_insertion() {
+        let mut q_func = Table::vector_output(3);
+
+        assert_eq!(q_func.n_outputs(), 3);
+        assert_eq!(q_func.evaluate(&7).unwrap(), Vector::zeros(3));
+        assert!(!q_func.contains(&7));
+
+        q_func.update_action(&7, 2, 1.5);
+
+        assert!(q_func.contains(&7));
+        assert_eq!(q_func.evaluate(&7).unwrap(), Vector::from_vec(vec![0.0, 0.0, 1.5]));
+
+        q_func.update(&7, Vector::from_vec(vec![1.0, 1.0, 1.0])).unwrap();
+
+        assert_eq!(q_func.evaluate_action(&7, 0), 1.0);
+        assert_eq!(q_func.evaluate_action(&7, 2), 2.5);
+        assert_eq!(q_func.len(), 1);
+    }
+
+    #[test]
+    fn test_q_learning() {
+        let q_func = make_shared(Table::vector_output(4));
+        let mut agent = QLearning::new(q_func.clone(), make_shared(Random::new(4)), 0.5, 0.9);
+
+        {
+            let domain_factory = Box::new(|| GridWorld::new(4, 1).with_goal((3, 0), 1.0));
+
+            run(SerialExperiment::new(&mut agent, domain_factory, 100), 200, None);
+        }
+
+        // Only the non-terminal states visited are ever inserted:
+        assert_eq!(q_func.len(), 3);
+
+        for s in 0..3 {
+            assert_eq!(agent.sample_target(&s), 3);
+        }
+    }
+}
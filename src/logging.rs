@@ -3,8 +3,11 @@
 use slog::*;
 use slog_async;
 use slog_term;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
 use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
 
 pub fn stdout() -> Fuse<slog_async::Async> {
     let decorator = slog_term::TermDecorator::new().build();
@@ -43,3 +46,301 @@ where D: SendSyncUnwindSafeDrain<Err = Never, Ok = ()>
         + SendSyncRefUnwindSafeDrain<Ok = (), Err = Never> {
     Logger::root(drain, o!())
 }
+
+/// Drain that writes the key-value pairs of each record as a row of a CSV
+/// file.
+///
+/// The header is inferred from the keys of the first record that has any;
+/// later records are written in the same column order, with missing keys left
+/// empty and unknown keys dropped. Output is buffered and flushed when the
+/// drain is dropped.
+pub struct CsvDrain<W: Write> {
+    state: Mutex<CsvState<W>>,
+}
+
+struct CsvState<W: Write> {
+    writer: BufWriter<W>,
+    header: Option<Vec<String>>,
+}
+
+impl<W: Write> CsvDrain<W> {
+    pub fn new(writer: W) -> CsvDrain<W> {
+        CsvDrain {
+            state: Mutex::new(CsvState {
+                writer: BufWriter::new(writer),
+                header: None,
+            }),
+        }
+    }
+}
+
+struct FieldCollector(Vec<(String, String)>);
+
+impl Serializer for FieldCollector {
+    fn emit_arguments(&mut self, key: Key, val: &fmt::Arguments) -> Result {
+        self.0.push((key.to_string(), format!("{}", val)));
+
+        Ok(())
+    }
+}
+
+fn escape(field: &str) -> String {
+    if field.contains(|c: char| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+fn write_row<W: Write>(writer: &mut W, fields: &[String]) -> io::Result<()> {
+    let row: Vec<String> = fields.iter().map(|f| escape(f)).collect();
+
+    writeln!(writer, "{}", row.join(","))
+}
+
+impl<W: Write> Drain for CsvDrain<W> {
+    type Ok = ();
+    type Err = io::Error;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> io::Result<()> {
+        let to_io = |e: Error| io::Error::new(io::ErrorKind::Other, format!("{}", e));
+
+        let mut fields = FieldCollector(vec![]);
+
+        record.kv().serialize(record, &mut fields).map_err(to_io)?;
+        values.serialize(record, &mut fields).map_err(to_io)?;
+
+        if fields.0.is_empty() {
+            return Ok(());
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+
+        if state.header.is_none() {
+            let header: Vec<String> = fields.0.iter().map(|&(ref k, _)| k.clone()).collect();
+
+            write_row(&mut state.writer, &header)?;
+            state.header = Some(header);
+        }
+
+        let row: Vec<String> = state.header.as_ref().unwrap().iter().map(|key| {
+            fields.0.iter()
+                .find(|&&(ref k, _)| k == key)
+                .map(|&(_, ref v)| v.clone())
+                .unwrap_or_default()
+        }).collect();
+
+        write_row(&mut state.writer, &row)
+    }
+}
+
+impl<W: Write> Drop for CsvDrain<W> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            let _ = state.writer.flush();
+        }
+    }
+}
+
+pub fn csv<P: AsRef<Path>>(path: P) -> io::Result<Fuse<CsvDrain<File>>> {
+    Ok(CsvDrain::new(File::create(path)?).fuse())
+}
+
+/// Drain that writes each record as a single-line JSON object.
+///
+/// Every object contains the record's message under the key `"msg"`,
+/// followed by its key-value pairs. Integers, floats and booleans are written
+/// as JSON numbers and booleans, with non-finite floats mapped to `null`; all
+/// other values are written as strings. Output is buffered and flushed when
+/// the drain is dropped.
+pub struct JsonlDrain<W: Write> {
+    writer: Mutex<BufWriter<W>>,
+}
+
+impl<W: Write> JsonlDrain<W> {
+    pub fn new(writer: W) -> JsonlDrain<W> {
+        JsonlDrain {
+            writer: Mutex::new(BufWriter::new(writer)),
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+
+    out
+}
+
+struct JsonCollector(Vec<(String, String)>);
+
+impl JsonCollector {
+    fn push(&mut self, key: Key, value: String) -> Result {
+        self.0.push((key.to_string(), value));
+
+        Ok(())
+    }
+}
+
+macro_rules! impl_emit_number {
+    ($($name:ident: $type:ty),*) => {
+        $(fn $name(&mut self, key: Key, val: $type) -> Result { self.push(key, val.to_string()) })*
+    };
+}
+
+impl Serializer for JsonCollector {
+    impl_emit_number!(
+        emit_usize: usize, emit_isize: isize,
+        emit_u8: u8, emit_i8: i8, emit_u16: u16, emit_i16: i16,
+        emit_u32: u32, emit_i32: i32, emit_u64: u64, emit_i64: i64
+    );
+
+    fn emit_f32(&mut self, key: Key, val: f32) -> Result { self.emit_f64(key, val as f64) }
+
+    fn emit_f64(&mut self, key: Key, val: f64) -> Result {
+        if val.is_finite() {
+            self.push(key, format!("{:?}", val))
+        } else {
+            self.push(key, "null".to_owned())
+        }
+    }
+
+    fn emit_bool(&mut self, key: Key, val: bool) -> Result { self.push(key, val.to_string()) }
+
+    fn emit_unit(&mut self, key: Key) -> Result { self.push(key, "null".to_owned()) }
+
+    fn emit_none(&mut self, key: Key) -> Result { self.push(key, "null".to_owned()) }
+
+    fn emit_str(&mut self, key: Key, val: &str) -> Result { self.push(key, json_string(val)) }
+
+    fn emit_arguments(&mut self, key: Key, val: &fmt::Arguments) -> Result {
+        self.push(key, json_string(&format!("{}", val)))
+    }
+}
+
+impl<W: Write> Drain for JsonlDrain<W> {
+    type Ok = ();
+    type Err = io::Error;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> io::Result<()> {
+        let to_io = |e: Error| io::Error::new(io::ErrorKind::Other, format!("{}", e));
+
+        let msg = json_string(&format!("{}", record.msg()));
+        let mut fields = JsonCollector(vec![("msg".to_owned(), msg)]);
+
+        record.kv().serialize(record, &mut fields).map_err(to_io)?;
+        values.serialize(record, &mut fields).map_err(to_io)?;
+
+        let members: Vec<String> = fields.0
+            .iter()
+            .map(|&(ref k, ref v)| format!("{}:{}", json_string(k), v))
+            .collect();
+
+        writeln!(self.writer.lock().unwrap(), "{{{}}}", members.join(","))
+    }
+}
+
+impl<W: Write> Drop for JsonlDrain<W> {
+    fn drop(&mut self) {
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writer.flush();
+        }
+    }
+}
+
+pub fn jsonl<P: AsRef<Path>>(path: P) -> io::Result<Fuse<JsonlDrain<File>>> {
+    Ok(JsonlDrain::new(File::create(path)?).fuse())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Episode;
+    use std::fs;
+
+    #[test]
+    fn test_csv() {
+        let path = std::env::temp_dir().join(format!("rsrl_test_csv_{}.csv", std::process::id()));
+
+        {
+            let logger = root(csv(&path).unwrap());
+
+            info!(logger, "starting");
+
+            for i in 0..3 {
+                let e = Episode {
+                    steps: 10 * (i + 1),
+                    reward: -(i as f64) - 0.5,
+                    discounted_reward: None,
+                };
+
+                info!(logger, "episode {}", i; e);
+            }
+        }
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let rows: Vec<Vec<&str>> = contents.lines().map(|l| l.split(',').collect()).collect();
+
+        fs::remove_file(&path).ok();
+
+        assert_eq!(rows, vec![
+            vec!["steps", "reward"],
+            vec!["10", "-0.5"],
+            vec!["20", "-1.5"],
+            vec!["30", "-2.5"],
+        ]);
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("plain"), "plain");
+        assert_eq!(escape("a,b"), "\"a,b\"");
+        assert_eq!(escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_jsonl() {
+        let path = std::env::temp_dir().join(format!("rsrl_test_jsonl_{}.jsonl", std::process::id()));
+
+        {
+            let logger = root(jsonl(&path).unwrap());
+
+            info!(logger, "episode {}", 1; Episode { steps: 12, reward: -3.5, discounted_reward: None });
+            info!(logger, "solution"; "solved" => true, "label" => "a \"b\"");
+        }
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+
+        fs::remove_file(&path).ok();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], r#"{"msg":"episode 1","steps":12,"reward":-3.5}"#);
+        assert!(lines[1].starts_with(r#"{"msg":"solution","#));
+        assert!(lines[1].contains(r#""solved":true"#));
+        assert!(lines[1].contains(r#""label":"a \"b\"""#));
+        assert!(lines[1].ends_with("}"));
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("plain"), r#""plain""#);
+        assert_eq!(json_string("a\"b\\c\nd"), r#""a\"b\\c\nd""#);
+    }
+}
@@ -5,34 +5,44 @@ use crate::{
     policies::{sample_probs_with_rng, FinitePolicy, Policy},
     utils::argmax_choose,
 };
-use rand::{rngs::ThreadRng, thread_rng};
+use rand::{rngs::ThreadRng, thread_rng, Rng};
 use std::f64;
 
-pub struct Boltzmann<Q> {
+pub struct Boltzmann<Q, R = ThreadRng> {
     q_func: Shared<Q>,
 
     tau: Parameter,
-    rng: ThreadRng,
+    rng: R,
 }
 
 impl<Q> Boltzmann<Q> {
     pub fn new<T: Into<Parameter>>(q_func: Shared<Q>, tau: T) -> Self {
+        Boltzmann::with_rng(q_func, tau, thread_rng())
+    }
+}
+
+impl<Q, R> Boltzmann<Q, R> {
+    pub fn with_rng<T: Into<Parameter>>(q_func: Shared<Q>, tau: T, rng: R) -> Self {
         Boltzmann {
             q_func,
 
             tau: tau.into(),
-            rng: thread_rng(),
+            rng,
         }
     }
 }
 
-impl<Q> Algorithm for Boltzmann<Q> {
+impl<Q, R> Algorithm for Boltzmann<Q, R> {
     fn handle_terminal(&mut self) {
         self.tau = self.tau.step();
     }
+
+    fn handle_episode(&mut self, episode: usize) {
+        self.tau = self.tau.at(episode as u32);
+    }
 }
 
-impl<S, Q: QFunction<S>> Policy<S> for Boltzmann<Q> {
+impl<S, Q: QFunction<S>, R: Rng> Policy<S> for Boltzmann<Q, R> {
     type Action = usize;
 
     fn sample(&mut self, s: &S) -> usize {
@@ -50,22 +60,23 @@ impl<S, Q: QFunction<S>> Policy<S> for Boltzmann<Q> {
     fn probability(&mut self, s: &S, a: usize) -> f64 { self.probabilities(s)[a] }
 }
 
-impl<S, Q: QFunction<S>> FinitePolicy<S> for Boltzmann<Q> {
+impl<S, Q: QFunction<S>, R: Rng> FinitePolicy<S> for Boltzmann<Q, R> {
     fn n_actions(&self) -> usize {
         self.q_func.n_outputs()
     }
 
     fn probabilities(&mut self, s: &S) -> Vector<f64> {
         let tau = self.tau.value();
+        let qs = self.q_func.evaluate(s).unwrap();
+
+        // Shift by the maximum value to avoid overflow when exponentiating:
+        let max_q = qs.fold(f64::MIN, |acc, &q| acc.max(q));
 
         let mut z = 0.0;
-        let ws: Vec<f64> = self
-            .q_func
-            .evaluate(s)
-            .unwrap()
+        let ws: Vec<f64> = qs
             .into_iter()
             .map(|v| {
-                let v = (v / tau).exp();
+                let v = ((v - max_q) / tau).exp();
                 z += v;
 
                 v
@@ -82,6 +93,7 @@ mod tests {
     use crate::domains::{Domain, MountainCar};
     use crate::fa::mocking::MockQ;
     use crate::geometry::Vector;
+    use crate::policies::tests::assert_normalised;
     use std::f64::consts::E;
 
     #[test]
@@ -130,6 +142,40 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_overflow() {
+        let mut p = Boltzmann::new(MockQ::new_shared(None), 1.0);
+
+        assert!(p.probabilities(&vec![1000.0, 1001.0].into()).all_close(
+            &Vector::from_vec(vec![1.0 / (1.0 + E), E / (1.0 + E)]),
+            1e-6,
+        ));
+    }
+
+    #[test]
+    fn test_low_temperature() {
+        let mut p = Boltzmann::new(MockQ::new_shared(None), 1e-3);
+
+        assert!(p.probabilities(&vec![0.0, 1.0, 0.5].into()).all_close(
+            &Vector::from_vec(vec![0.0, 1.0, 0.0]),
+            1e-6,
+        ));
+
+        for _ in 0..100 {
+            assert_eq!(p.sample(&vec![0.0, 1.0, 0.5].into()), 1);
+        }
+    }
+
+    #[test]
+    fn test_high_temperature() {
+        let mut p = Boltzmann::new(MockQ::new_shared(None), 1e6);
+
+        assert!(p.probabilities(&vec![0.0, 1.0, 0.5].into()).all_close(
+            &Vector::from_vec(vec![1.0 / 3.0; 3]),
+            1e-5,
+        ));
+    }
+
     #[test]
     fn test_terminal() {
         let mut tau = Parameter::exponential(100.0, 1.0, 0.9);
@@ -142,4 +188,26 @@ mod tests {
             assert_eq!(tau.value(), p.tau.value());
         }
     }
+
+    #[test]
+    fn test_episode_schedule() {
+        let tau = Parameter::polynomial(10.0, 0.1, 2.0);
+        let mut p = Boltzmann::new(MockQ::new_shared(None), tau);
+
+        for &k in [3, 0, 10, 250].iter() {
+            p.handle_episode(k);
+
+            let expected = (0..k).fold(tau, |t, _| t.step());
+
+            assert_eq!(p.tau.value(), expected.value());
+            assert_eq!(p.tau.value(), f64::max(10.0 / (k as f64 + 1.0).powf(2.0), 0.1));
+        }
+    }
+
+    #[test]
+    fn test_probabilities_sum() {
+        let mut p = Boltzmann::new(MockQ::new_shared(None), 0.5);
+
+        assert_normalised(&mut p);
+    }
 }
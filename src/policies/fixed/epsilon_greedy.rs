@@ -1,25 +1,36 @@
 use crate::core::*;
 use crate::domains::Transition;
 use crate::fa::QFunction;
-use rand::{rngs::ThreadRng, thread_rng, Rng};
-use super::{FinitePolicy, Greedy, Policy, Random};
+use rand::{rngs::ThreadRng, thread_rng, Rng, SeedableRng};
+use super::{FinitePolicy, Greedy, Policy, Random, TieBreak};
+
+/// Hook at which the exploration schedule of an `EpsilonGreedy` policy is
+/// advanced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Annealing {
+    /// Step `epsilon` once per episode, in `handle_terminal`.
+    PerEpisode,
+
+    /// Step `epsilon` after every call to `sample`.
+    PerStep,
+}
+
+impl Default for Annealing {
+    fn default() -> Annealing { Annealing::PerEpisode }
+}
 
-pub struct EpsilonGreedy<Q> {
-    greedy: Greedy<Q>,
-    random: Random,
+pub struct EpsilonGreedy<Q, R = ThreadRng> {
+    greedy: Greedy<Q, R>,
+    random: Random<R>,
 
     epsilon: Parameter,
-    rng: ThreadRng,
+    annealing: Annealing,
+    rng: R,
 }
 
 impl<Q> EpsilonGreedy<Q> {
     pub fn new<T: Into<Parameter>>(greedy: Greedy<Q>, random: Random, epsilon: T) -> Self {
-        EpsilonGreedy {
-            greedy, random,
-
-            epsilon: epsilon.into(),
-            rng: thread_rng(),
-        }
+        EpsilonGreedy::with_rng(greedy, random, epsilon, thread_rng())
     }
 
     #[allow(non_snake_case)]
@@ -33,24 +44,80 @@ impl<Q> EpsilonGreedy<Q> {
     }
 }
 
-impl<Q> Algorithm for EpsilonGreedy<Q> {
+impl<Q, R> EpsilonGreedy<Q, R> {
+    pub fn with_rng<T: Into<Parameter>>(
+        greedy: Greedy<Q, R>,
+        random: Random<R>,
+        epsilon: T,
+        rng: R,
+    ) -> Self
+    {
+        EpsilonGreedy {
+            greedy, random,
+
+            epsilon: epsilon.into(),
+            annealing: Annealing::default(),
+            rng,
+        }
+    }
+
+    /// Set the hook at which the `epsilon` schedule is advanced.
+    pub fn with_annealing(mut self, annealing: Annealing) -> Self {
+        self.annealing = annealing;
+
+        self
+    }
+
+    #[allow(non_snake_case)]
+    pub fn from_Q_with_rng<S, T: Into<Parameter>>(q_func: Shared<Q>, epsilon: T, mut rng: R) -> Self
+        where Q: QFunction<S>, R: Rng + SeedableRng,
+    {
+        let greedy = Greedy::with_rng(
+            q_func,
+            TieBreak::default(),
+            R::from_rng(&mut rng).expect("Failed to seed greedy policy."),
+        );
+        let random = Random::with_rng(
+            greedy.n_actions(),
+            R::from_rng(&mut rng).expect("Failed to seed random policy."),
+        );
+
+        EpsilonGreedy::with_rng(greedy, random, epsilon, rng)
+    }
+}
+
+impl<Q, R> Algorithm for EpsilonGreedy<Q, R> {
     fn handle_terminal(&mut self) {
-        self.epsilon = self.epsilon.step();
+        if self.annealing == Annealing::PerEpisode {
+            self.epsilon = self.epsilon.step();
+        }
 
         self.greedy.handle_terminal();
         self.random.handle_terminal();
     }
+
+    fn handle_episode(&mut self, episode: usize) {
+        if self.annealing == Annealing::PerEpisode {
+            self.epsilon = self.epsilon.at(episode as u32);
+        }
+    }
 }
 
-impl<S, Q: QFunction<S>> Policy<S> for EpsilonGreedy<Q> {
+impl<S, Q: QFunction<S>, R: Rng> Policy<S> for EpsilonGreedy<Q, R> {
     type Action = usize;
 
     fn sample(&mut self, s: &S) -> usize {
-        if self.rng.gen_bool(self.epsilon.value()) {
+        let a = if self.rng.gen_bool(self.epsilon.value()) {
             self.random.sample(s)
         } else {
             self.greedy.sample(s)
+        };
+
+        if self.annealing == Annealing::PerStep {
+            self.epsilon = self.epsilon.step();
         }
+
+        a
     }
 
     fn mpa(&mut self, s: &S) -> usize {
@@ -60,7 +127,7 @@ impl<S, Q: QFunction<S>> Policy<S> for EpsilonGreedy<Q> {
     fn probability(&mut self, s: &S, a: usize) -> f64 { self.probabilities(s)[a] }
 }
 
-impl<S, Q: QFunction<S>> FinitePolicy<S> for EpsilonGreedy<Q> {
+impl<S, Q: QFunction<S>, R: Rng> FinitePolicy<S> for EpsilonGreedy<Q, R> {
     fn n_actions(&self) -> usize {
         self.greedy.n_actions()
     }
@@ -75,10 +142,12 @@ impl<S, Q: QFunction<S>> FinitePolicy<S> for EpsilonGreedy<Q> {
 
 #[cfg(test)]
 mod tests {
-    use super::{Algorithm, EpsilonGreedy, FinitePolicy, Parameter, Policy};
+    use super::{Algorithm, Annealing, EpsilonGreedy, FinitePolicy, Parameter, Policy};
     use crate::domains::{Domain, MountainCar};
     use crate::fa::mocking::MockQ;
     use crate::geometry::Vector;
+    use crate::policies::tests::assert_normalised;
+    use rand::{rngs::StdRng, SeedableRng};
 
     #[test]
     fn test_sampling() {
@@ -137,4 +206,50 @@ mod tests {
             assert_eq!(epsilon.value(), p.epsilon.value());
         }
     }
+
+    #[test]
+    fn test_annealing() {
+        let epsilon = Parameter::exponential(1.0, 0.01, 0.9);
+        let qs: Vector<f64> = vec![0.0, 1.0].into();
+
+        for &(annealing, n_steps) in [(Annealing::PerEpisode, 1), (Annealing::PerStep, 10)].iter() {
+            let q = MockQ::new_shared(Some(qs.clone()));
+            let mut p = EpsilonGreedy::from_Q(q.clone(), epsilon).with_annealing(annealing);
+
+            q.borrow_mut().clear_output();
+
+            for _ in 0..10 {
+                p.sample(&qs);
+            }
+            p.handle_terminal();
+
+            let expected = (0..n_steps).fold(epsilon, |e, _| e.step());
+
+            assert_eq!(p.epsilon.value(), expected.value());
+        }
+    }
+
+    #[test]
+    fn test_seeded_ties() {
+        let qs: Vector<f64> = vec![1.0, 1.0, 1.0, 1.0].into();
+        let actions = |seed: u64| -> Vec<usize> {
+            let q = MockQ::new_shared(Some(qs.clone()));
+            let mut p = EpsilonGreedy::from_Q_with_rng(q.clone(), 0.0, StdRng::seed_from_u64(seed));
+
+            q.borrow_mut().clear_output();
+
+            (0..100).map(|_| p.sample(&qs)).collect()
+        };
+
+        // Greedy tie-breaking draws from the policy's own generator:
+        assert_eq!(actions(0), actions(0));
+        assert!(actions(0).iter().any(|&a| a != actions(0)[0]));
+    }
+
+    #[test]
+    fn test_probabilities_sum() {
+        let mut p = EpsilonGreedy::from_Q(MockQ::new_shared(None), 0.3);
+
+        assert_normalised(&mut p);
+    }
 }
@@ -2,29 +2,71 @@ use crate::core::*;
 use crate::fa::QFunction;
 use crate::policies::{FinitePolicy, Policy};
 use crate::utils::{argmaxima, argmax_choose};
-use rand::thread_rng;
+use rand::{rngs::ThreadRng, thread_rng, Rng};
 
-pub struct Greedy<Q>(Shared<Q>);
+/// Strategy for choosing between actions with equal maximal value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Choose the maximal action with the lowest index.
+    First,
+
+    /// Choose the maximal action with the highest index.
+    Last,
+
+    /// Choose uniformly at random among the maximal actions.
+    Random,
+}
+
+impl Default for TieBreak {
+    fn default() -> TieBreak { TieBreak::Random }
+}
+
+pub struct Greedy<Q, R = ThreadRng>(Shared<Q>, TieBreak, R);
 
 impl<Q> Greedy<Q> {
-    pub fn new(q_func: Shared<Q>) -> Self { Greedy(q_func) }
+    pub fn new(q_func: Shared<Q>) -> Self { Greedy::with_tie_break(q_func, TieBreak::default()) }
+
+    pub fn with_tie_break(q_func: Shared<Q>, tie_break: TieBreak) -> Self {
+        Greedy::with_rng(q_func, tie_break, thread_rng())
+    }
 }
 
-impl<Q> Algorithm for Greedy<Q> {}
+impl<Q, R> Greedy<Q, R> {
+    pub fn with_rng(q_func: Shared<Q>, tie_break: TieBreak, rng: R) -> Self {
+        Greedy(q_func, tie_break, rng)
+    }
+}
 
-impl<S, Q: QFunction<S>> Policy<S> for Greedy<Q> {
+impl<Q, R: Rng> Greedy<Q, R> {
+    /// Return the greedy action for a precomputed vector of action values,
+    /// breaking ties according to the policy's `TieBreak` strategy.
+    ///
+    /// This allows callers that have already evaluated the Q-function to avoid
+    /// a second evaluation in `sample` or `mpa`.
+    pub fn argmax_qs(&mut self, qs: &Vector<f64>) -> usize {
+        match self.1 {
+            TieBreak::First => argmaxima(qs.as_slice().unwrap()).1[0],
+            TieBreak::Last => *argmaxima(qs.as_slice().unwrap()).1.last().unwrap(),
+            TieBreak::Random => argmax_choose(&mut self.2, qs.as_slice().unwrap()).1,
+        }
+    }
+}
+
+impl<Q, R> Algorithm for Greedy<Q, R> {}
+
+impl<S, Q: QFunction<S>, R: Rng> Policy<S> for Greedy<Q, R> {
     type Action = usize;
 
     fn mpa(&mut self, s: &S) -> usize {
         let qs = self.0.evaluate(s).unwrap();
 
-        argmax_choose(&mut thread_rng(), qs.as_slice().unwrap()).1
+        self.argmax_qs(&qs)
     }
 
     fn probability(&mut self, s: &S, a: usize) -> f64 { self.probabilities(s)[a] }
 }
 
-impl<S, Q: QFunction<S>> FinitePolicy<S> for Greedy<Q> {
+impl<S, Q: QFunction<S>, R: Rng> FinitePolicy<S> for Greedy<Q, R> {
     fn n_actions(&self) -> usize {
         self.0.n_outputs()
     }
@@ -35,9 +77,15 @@ impl<S, Q: QFunction<S>> FinitePolicy<S> for Greedy<Q> {
 
         let (_, maxima) = argmaxima(qs.as_slice().unwrap());
 
-        let p = 1.0 / maxima.len() as f64;
-        for i in maxima {
-            ps[i] = p;
+        match self.1 {
+            TieBreak::First => ps[maxima[0]] = 1.0,
+            TieBreak::Last => ps[*maxima.last().unwrap()] = 1.0,
+            TieBreak::Random => {
+                let p = 1.0 / maxima.len() as f64;
+                for i in maxima {
+                    ps[i] = p;
+                }
+            },
         }
 
         ps.into()
@@ -46,9 +94,10 @@ impl<S, Q: QFunction<S>> FinitePolicy<S> for Greedy<Q> {
 
 #[cfg(test)]
 mod tests {
-    use super::{FinitePolicy, Greedy, Policy};
+    use super::{FinitePolicy, Greedy, Policy, TieBreak};
     use crate::fa::mocking::MockQ;
     use crate::geometry::Vector;
+    use crate::policies::tests::assert_normalised;
 
     #[test]
     #[should_panic]
@@ -120,4 +169,63 @@ mod tests {
             Vector::from_vec(vec![0.25, 0.25, 0.25, 0.25])
         );
     }
+
+    #[test]
+    fn test_probabilities_sum() {
+        let mut p = Greedy::new(MockQ::new_shared(None));
+
+        assert_normalised(&mut p);
+    }
+
+    #[test]
+    fn test_tie_break_deterministic() {
+        let qs: Vector<f64> = vec![1.0, 3.0, 0.0, 3.0, 3.0].into();
+
+        let mut p = Greedy::with_tie_break(MockQ::new_shared(None), TieBreak::First);
+
+        assert_eq!(p.sample(&qs), 1);
+        assert_eq!(p.probabilities(&qs), Vector::from_vec(vec![0.0, 1.0, 0.0, 0.0, 0.0]));
+
+        let mut p = Greedy::with_tie_break(MockQ::new_shared(None), TieBreak::Last);
+
+        assert_eq!(p.sample(&qs), 4);
+        assert_eq!(p.probabilities(&qs), Vector::from_vec(vec![0.0, 0.0, 0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn test_tie_break_random() {
+        let qs: Vector<f64> = vec![1.0, 3.0, 0.0, 3.0, 3.0].into();
+        let mut p = Greedy::with_tie_break(MockQ::new_shared(None), TieBreak::Random);
+
+        let mut counts = [0.0f64; 5];
+        for _ in 0..30000 {
+            counts[p.sample(&qs)] += 1.0;
+        }
+
+        assert_eq!(counts[0], 0.0);
+        assert_eq!(counts[2], 0.0);
+
+        for &a in [1, 3, 4].iter() {
+            assert!((counts[a] / 30000.0 - 1.0 / 3.0).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn test_argmax_qs() {
+        let q = MockQ::new_shared(None);
+
+        for &tie_break in [TieBreak::First, TieBreak::Last].iter() {
+            let mut p = Greedy::with_tie_break(q.clone(), tie_break);
+
+            for qs in vec![
+                vec![0.0, 1.0, 2.0, 3.0],
+                vec![-1.0, 5.0, 5.0, 0.5],
+                vec![1.0, 1.0, 1.0, 1.0],
+            ] {
+                let qs: Vector<f64> = qs.into();
+
+                assert_eq!(p.argmax_qs(&qs), p.sample(&qs));
+            }
+        }
+    }
 }
@@ -0,0 +1,118 @@
+use crate::core::*;
+use rand::{rngs::ThreadRng, thread_rng, Rng};
+use super::{FinitePolicy, Policy};
+
+/// Weighted mixture of two policies.
+///
+/// With probability `weight` actions are sampled from the first policy, and
+/// otherwise from the second, such that the action distribution is given by
+/// `w * pi_a + (1 - w) * pi_b`. `EpsilonGreedy`, for example, is equivalent to
+/// a mixture of `Random` and `Greedy` with weight `epsilon`.
+pub struct Mixture<A, B, R = ThreadRng> {
+    a: A,
+    b: B,
+
+    weight: Parameter,
+    rng: R,
+}
+
+impl<A, B> Mixture<A, B> {
+    pub fn new<T: Into<Parameter>>(a: A, b: B, weight: T) -> Self {
+        Mixture::with_rng(a, b, weight, thread_rng())
+    }
+}
+
+impl<A, B, R> Mixture<A, B, R> {
+    pub fn with_rng<T: Into<Parameter>>(a: A, b: B, weight: T, rng: R) -> Self {
+        Mixture {
+            a, b,
+
+            weight: weight.into(),
+            rng,
+        }
+    }
+
+    /// Return the current probability of sampling from the first policy.
+    pub fn weight(&self) -> f64 { self.weight.value() }
+}
+
+impl<A: Algorithm, B: Algorithm, R> Algorithm for Mixture<A, B, R> {
+    fn handle_terminal(&mut self) {
+        self.weight = self.weight.step();
+
+        self.a.handle_terminal();
+        self.b.handle_terminal();
+    }
+}
+
+impl<S, A, B, R> Policy<S> for Mixture<A, B, R>
+where
+    A: Policy<S>,
+    A::Action: Clone,
+    B: Policy<S, Action = A::Action>,
+    R: Rng,
+{
+    type Action = A::Action;
+
+    fn sample(&mut self, s: &S) -> A::Action {
+        if self.rng.gen_bool(self.weight.value()) {
+            self.a.sample(s)
+        } else {
+            self.b.sample(s)
+        }
+    }
+
+    fn probability(&mut self, s: &S, a: A::Action) -> f64 {
+        let w = self.weight.value();
+
+        w * self.a.probability(s, a.clone()) + (1.0 - w) * self.b.probability(s, a)
+    }
+}
+
+impl<S, A, B, R> FinitePolicy<S> for Mixture<A, B, R>
+where
+    A: FinitePolicy<S>,
+    B: FinitePolicy<S>,
+    R: Rng,
+{
+    fn n_actions(&self) -> usize { self.a.n_actions() }
+
+    fn probabilities(&mut self, s: &S) -> Vector<f64> {
+        let w = self.weight.value();
+
+        self.a.probabilities(s) * w + self.b.probabilities(s) * (1.0 - w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FinitePolicy, Mixture, Policy};
+    use crate::fa::mocking::MockQ;
+    use crate::geometry::Vector;
+    use crate::policies::fixed::{Greedy, Random};
+
+    #[test]
+    fn test_unit_weight() {
+        let mut greedy = Greedy::new(MockQ::new_shared(None));
+        let mut p = Mixture::new(Greedy::new(MockQ::new_shared(None)), Random::new(4), 1.0);
+
+        for qs in vec![
+            vec![0.0, 1.0, 2.0, 3.0],
+            vec![-1.0, 5.0, 2.0, 0.5],
+        ] {
+            let qs: Vector<f64> = qs.into();
+
+            assert_eq!(p.sample(&qs), greedy.sample(&qs));
+            assert_eq!(p.probabilities(&qs), greedy.probabilities(&qs));
+        }
+    }
+
+    #[test]
+    fn test_probabilities() {
+        let mut p = Mixture::new(Greedy::new(MockQ::new_shared(None)), Random::new(4), 0.5);
+        let qs: Vector<f64> = vec![0.0, 1.0, 3.0, 2.0].into();
+
+        assert!(p.probabilities(&qs).all_close(&vec![0.125, 0.125, 0.625, 0.125].into(), 1e-7));
+        assert!((p.probability(&qs, 2) - 0.625).abs() < 1e-7);
+    }
+}
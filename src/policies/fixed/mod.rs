@@ -4,10 +4,19 @@ mod random;
 pub use self::random::Random;
 
 mod greedy;
-pub use self::greedy::Greedy;
+pub use self::greedy::{Greedy, TieBreak};
 
 mod epsilon_greedy;
-pub use self::epsilon_greedy::EpsilonGreedy;
+pub use self::epsilon_greedy::{Annealing, EpsilonGreedy};
+
+mod mixture;
+pub use self::mixture::Mixture;
+
+mod ucb;
+pub use self::ucb::{StateIndex, UCB};
+
+mod thompson_sampling;
+pub use self::thompson_sampling::ThompsonSampling;
 
 mod boltzmann;
 pub use self::boltzmann::Boltzmann;
@@ -5,13 +5,14 @@ use rand::{
     distributions::{Distribution, Uniform},
     rngs::ThreadRng,
     thread_rng,
+    Rng,
 };
 
 // TODO: Generalise the random policy to work on any `Space`. This won't be hard at all, just use
 // T: Into<Space>. Just make sure that you add all the relevant From implementations for the
 // different spaces in the `spaces` crate; i.e. From<usize> for Ordinal etc etc...
 
-pub struct Random(usize, ThreadRng);
+pub struct Random<R = ThreadRng>(usize, R);
 
 impl Random {
     pub fn new(n_actions: usize) -> Self { Random(n_actions, thread_rng()) }
@@ -19,9 +20,13 @@ impl Random {
     pub fn from_space<S: Space>(space: S) -> Self { Self::new(space.dim()) }
 }
 
-impl Algorithm for Random {}
+impl<R> Random<R> {
+    pub fn with_rng(n_actions: usize, rng: R) -> Self { Random(n_actions, rng) }
+}
+
+impl<R> Algorithm for Random<R> {}
 
-impl<S> Policy<S> for Random {
+impl<S, R: Rng> Policy<S> for Random<R> {
     type Action = usize;
 
     fn sample(&mut self, _: &S) -> usize { Uniform::new(0, self.0).sample(&mut self.1) }
@@ -29,7 +34,7 @@ impl<S> Policy<S> for Random {
     fn probability(&mut self, _: &S, _: usize) -> f64 { 1.0 / self.0 as f64 }
 }
 
-impl<S> FinitePolicy<S> for Random {
+impl<S, R: Rng> FinitePolicy<S> for Random<R> {
     fn n_actions(&self) -> usize { self.0 }
 
     fn probabilities(&mut self, _: &S) -> Vector<f64> {
@@ -41,6 +46,8 @@ impl<S> FinitePolicy<S> for Random {
 mod tests {
     use super::{FinitePolicy, Policy, Random};
     use crate::geometry::Vector;
+    use crate::policies::tests::assert_normalised;
+    use rand::{rngs::StdRng, SeedableRng};
 
     #[test]
     fn test_sampling() {
@@ -79,4 +86,21 @@ mod tests {
             .probabilities(&[0.0, 0.0, 0.0, 0.0, 1.0])
             .all_close(&Vector::from_vec(vec![0.2; 5]), 1e-6));
     }
+
+    #[test]
+    fn test_probabilities_sum() {
+        let mut p = Random::new(4);
+
+        assert_normalised(&mut p);
+    }
+
+    #[test]
+    fn test_seeded() {
+        let mut p1 = Random::with_rng(10, StdRng::seed_from_u64(0));
+        let mut p2 = Random::with_rng(10, StdRng::seed_from_u64(0));
+
+        for _ in 0..100 {
+            assert_eq!(p1.sample(&()), p2.sample(&()));
+        }
+    }
 }
@@ -0,0 +1,159 @@
+use crate::core::*;
+use crate::policies::{FinitePolicy, Policy};
+use rand::{
+    distributions::{Distribution, Normal},
+    rngs::ThreadRng,
+    thread_rng,
+    Rng,
+};
+
+const N_PROBABILITY_DRAWS: usize = 1000;
+
+/// Thompson sampling over independent Gaussian action-value posteriors.
+///
+/// Each action's value is modelled by a Gaussian with known observation noise,
+/// `noise_var`, under a conjugate Gaussian prior with zero mean and variance
+/// `prior_var`. Observed rewards are incorporated through `handle`, and actions
+/// are chosen by drawing one value from each posterior and taking the argmax.
+/// The posteriors are shared across all states, as in a multi-armed bandit.
+///
+/// # References
+/// - Thompson, W. R. (1933). On the likelihood that one unknown probability
+/// exceeds another in view of the evidence of two samples. Biometrika, 25(3/4),
+/// 285-294.
+pub struct ThompsonSampling<R = ThreadRng> {
+    means: Vec<f64>,
+    variances: Vec<f64>,
+
+    pub noise_var: f64,
+    rng: R,
+}
+
+impl ThompsonSampling {
+    pub fn new(n_actions: usize, prior_var: f64) -> Self {
+        ThompsonSampling::with_rng(n_actions, prior_var, thread_rng())
+    }
+}
+
+impl<R> ThompsonSampling<R> {
+    pub fn with_rng(n_actions: usize, prior_var: f64, rng: R) -> Self {
+        assert!(prior_var > 0.0, "The prior variance must be positive.");
+
+        ThompsonSampling {
+            means: vec![0.0; n_actions],
+            variances: vec![prior_var; n_actions],
+
+            noise_var: 1.0,
+            rng,
+        }
+    }
+
+    /// Set the (known) variance of the observed rewards.
+    pub fn with_noise_var(mut self, noise_var: f64) -> Self {
+        self.noise_var = noise_var;
+
+        self
+    }
+
+    /// Update the posterior of action `a` given an observed reward.
+    pub fn handle(&mut self, a: usize, reward: f64) {
+        let precision = 1.0 / self.variances[a] + 1.0 / self.noise_var;
+        let mean = (self.means[a] / self.variances[a] + reward / self.noise_var) / precision;
+
+        self.means[a] = mean;
+        self.variances[a] = 1.0 / precision;
+    }
+
+    /// Return the posterior mean and variance of action `a`.
+    pub fn posterior(&self, a: usize) -> (f64, f64) { (self.means[a], self.variances[a]) }
+}
+
+impl<R: Rng> ThompsonSampling<R> {
+    fn draw(&mut self) -> usize {
+        let mut best = (0, ::std::f64::NEG_INFINITY);
+
+        for a in 0..self.means.len() {
+            let v = Normal::new(self.means[a], self.variances[a].sqrt()).sample(&mut self.rng);
+
+            if v > best.1 {
+                best = (a, v);
+            }
+        }
+
+        best.0
+    }
+}
+
+impl<R> Algorithm for ThompsonSampling<R> {}
+
+impl<S, R: Rng> Policy<S> for ThompsonSampling<R> {
+    type Action = usize;
+
+    fn sample(&mut self, _: &S) -> usize { self.draw() }
+
+    fn mpa(&mut self, _: &S) -> usize {
+        self.means.iter().enumerate().fold((0, ::std::f64::NEG_INFINITY), |acc, (a, &m)| {
+            if m > acc.1 { (a, m) } else { acc }
+        }).0
+    }
+
+    fn probability(&mut self, s: &S, a: usize) -> f64 { self.probabilities(s)[a] }
+}
+
+impl<S, R: Rng> FinitePolicy<S> for ThompsonSampling<R> {
+    fn n_actions(&self) -> usize { self.means.len() }
+
+    /// Estimate the probability of each action being sampled from a fixed
+    /// number of posterior draws; no closed form exists in general.
+    fn probabilities(&mut self, _: &S) -> Vector<f64> {
+        let mut ps = vec![0.0; self.means.len()];
+
+        for _ in 0..N_PROBABILITY_DRAWS {
+            ps[self.draw()] += 1.0 / N_PROBABILITY_DRAWS as f64;
+        }
+
+        ps.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Policy, ThompsonSampling};
+    use rand::{distributions::{Distribution, Normal}, rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_posterior_update() {
+        let mut p = ThompsonSampling::new(2, 1.0);
+
+        p.handle(0, 2.0);
+
+        assert_eq!(p.posterior(0), (1.0, 0.5));
+        assert_eq!(p.posterior(1), (0.0, 1.0));
+    }
+
+    #[test]
+    fn test_bandit() {
+        let means = [0.0, 0.5, 1.0];
+
+        let mut p = ThompsonSampling::with_rng(3, 10.0, StdRng::seed_from_u64(0));
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let mut counts = [0; 3];
+        for _ in 0..2000 {
+            let a = p.sample(&());
+            let r = Normal::new(means[a], 1.0).sample(&mut rng);
+
+            p.handle(a, r);
+            counts[a] += 1;
+        }
+
+        assert!(counts[2] > counts[0] && counts[2] > counts[1]);
+        assert!(counts[2] > 1000);
+
+        let (mean, var) = p.posterior(2);
+
+        assert!((mean - 1.0).abs() < 0.2);
+        assert!(var < p.posterior(0).1 && var < p.posterior(1).1);
+        assert!(var < 0.01);
+    }
+}
@@ -5,37 +5,43 @@ use crate::{
     policies::{sample_probs_with_rng, FinitePolicy, Policy},
     utils::argmax_choose,
 };
-use rand::{rngs::ThreadRng, thread_rng};
+use rand::{rngs::ThreadRng, thread_rng, Rng};
 use std::f64;
 
 #[inline(always)]
 fn kappa(c: f64, x: f64) -> f64 { c / (1.0 + (-x).exp()) }
 
-pub struct TruncatedBoltzmann<Q> {
+pub struct TruncatedBoltzmann<Q, R = ThreadRng> {
     q_func: Shared<Q>,
 
     c: Parameter,
-    rng: ThreadRng,
+    rng: R,
 }
 
 impl<Q> TruncatedBoltzmann<Q> {
     pub fn new<T: Into<Parameter>>(q_func: Shared<Q>, c: T) -> Self {
+        TruncatedBoltzmann::with_rng(q_func, c, thread_rng())
+    }
+}
+
+impl<Q, R> TruncatedBoltzmann<Q, R> {
+    pub fn with_rng<T: Into<Parameter>>(q_func: Shared<Q>, c: T, rng: R) -> Self {
         TruncatedBoltzmann {
             q_func,
 
             c: c.into(),
-            rng: thread_rng(),
+            rng,
         }
     }
 }
 
-impl<Q> Algorithm for TruncatedBoltzmann<Q> {
+impl<Q, R> Algorithm for TruncatedBoltzmann<Q, R> {
     fn handle_terminal(&mut self) {
         self.c = self.c.step();
     }
 }
 
-impl<S, Q: QFunction<S>> Policy<S> for TruncatedBoltzmann<Q> {
+impl<S, Q: QFunction<S>, R: Rng> Policy<S> for TruncatedBoltzmann<Q, R> {
     type Action = usize;
 
     fn sample(&mut self, s: &S) -> usize {
@@ -53,7 +59,7 @@ impl<S, Q: QFunction<S>> Policy<S> for TruncatedBoltzmann<Q> {
     fn probability(&mut self, s: &S, a: usize) -> f64 { self.probabilities(s)[a] }
 }
 
-impl<S, Q: QFunction<S>> FinitePolicy<S> for TruncatedBoltzmann<Q> {
+impl<S, Q: QFunction<S>, R: Rng> FinitePolicy<S> for TruncatedBoltzmann<Q, R> {
     fn n_actions(&self) -> usize {
         self.q_func.n_outputs()
     }
@@ -78,3 +84,18 @@ impl<S, Q: QFunction<S>> FinitePolicy<S> for TruncatedBoltzmann<Q> {
         ws.iter().map(|w| w / z).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{FinitePolicy, Policy, TruncatedBoltzmann};
+    use crate::fa::mocking::MockQ;
+    use crate::geometry::Vector;
+    use crate::policies::tests::assert_normalised;
+
+    #[test]
+    fn test_probabilities_sum() {
+        let mut p = TruncatedBoltzmann::new(MockQ::new_shared(None), 2.0);
+
+        assert_normalised(&mut p);
+    }
+}
@@ -0,0 +1,176 @@
+use crate::core::*;
+use crate::fa::QFunction;
+use crate::policies::{FinitePolicy, Policy};
+use std::collections::HashMap;
+
+/// Mapping from states to discrete identifiers under which `UCB` keeps
+/// separate visit counts.
+pub trait StateIndex<S> {
+    fn index(&self, s: &S) -> usize;
+}
+
+/// All states share a single set of counts, as in a multi-armed bandit.
+impl<S> StateIndex<S> for () {
+    fn index(&self, _: &S) -> usize { 0 }
+}
+
+impl<S, F: Fn(&S) -> usize> StateIndex<S> for F {
+    fn index(&self, s: &S) -> usize { (self)(s) }
+}
+
+/// Upper confidence bound (UCB1) action selection.
+///
+/// Actions are chosen greedily with respect to `Q(s, a) + c * sqrt(ln(N) /
+/// n_a)`, where `n_a` is the number of times `a` has been sampled and `N` the
+/// total number of samples. Actions that have never been sampled are always
+/// preferred. By default the counts are shared across all states; see
+/// `with_state_index` to keep them per discrete state.
+///
+/// # References
+/// - Auer, P., Cesa-Bianchi, N., & Fischer, P. (2002). Finite-time analysis of
+/// the multiarmed bandit problem. Machine Learning, 47(2-3), 235-256.
+pub struct UCB<Q, I = ()> {
+    q_func: Shared<Q>,
+    index: I,
+
+    pub c: f64,
+    counts: HashMap<usize, Vec<u64>>,
+}
+
+impl<Q> UCB<Q> {
+    pub fn new(q_func: Shared<Q>, c: f64) -> Self {
+        UCB {
+            q_func,
+            index: (),
+
+            c,
+            counts: HashMap::new(),
+        }
+    }
+}
+
+impl<Q, I> UCB<Q, I> {
+    /// Keep separate visit counts for each state, as identified by `f`.
+    pub fn with_state_index<S, F>(self, f: F) -> UCB<Q, F>
+    where
+        F: Fn(&S) -> usize,
+    {
+        UCB {
+            q_func: self.q_func,
+            index: f,
+
+            c: self.c,
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Return the number of times each action has been sampled under the state
+    /// identifier `key`, if any.
+    pub fn counts(&self, key: usize) -> Option<&[u64]> { self.counts.get(&key).map(|c| c.as_slice()) }
+
+    fn select(&self, key: usize, qs: &Vector<f64>) -> usize {
+        let counts = match self.counts.get(&key) {
+            Some(counts) => counts,
+            None => return 0,
+        };
+
+        if let Some(a) = counts.iter().position(|&n| n == 0) {
+            return a;
+        }
+
+        let ln_n = (counts.iter().sum::<u64>() as f64).ln();
+
+        qs.iter().zip(counts.iter()).enumerate().fold((0, ::std::f64::NEG_INFINITY), |acc, (a, (q, &n))| {
+            let u = q + self.c * (ln_n / n as f64).sqrt();
+
+            if u > acc.1 { (a, u) } else { acc }
+        }).0
+    }
+}
+
+impl<Q, I> Algorithm for UCB<Q, I> {}
+
+impl<S, Q: QFunction<S>, I: StateIndex<S>> Policy<S> for UCB<Q, I> {
+    type Action = usize;
+
+    fn sample(&mut self, s: &S) -> usize {
+        let key = self.index.index(s);
+        let a = self.mpa(s);
+        let n_actions = self.q_func.n_outputs();
+
+        self.counts.entry(key).or_insert_with(|| vec![0; n_actions])[a] += 1;
+
+        a
+    }
+
+    fn mpa(&mut self, s: &S) -> usize {
+        let qs = self.q_func.evaluate(s).unwrap();
+
+        self.select(self.index.index(s), &qs)
+    }
+
+    fn probability(&mut self, s: &S, a: usize) -> f64 {
+        if self.mpa(s) == a { 1.0 } else { 0.0 }
+    }
+}
+
+impl<S, Q: QFunction<S>, I: StateIndex<S>> FinitePolicy<S> for UCB<Q, I> {
+    fn n_actions(&self) -> usize { self.q_func.n_outputs() }
+
+    fn probabilities(&mut self, s: &S) -> Vector<f64> {
+        let mut ps = vec![0.0; self.n_actions()];
+
+        ps[self.mpa(s)] = 1.0;
+
+        ps.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Policy, UCB};
+    use crate::core::make_shared;
+    use crate::fa::{mocking::OneHot, Approximator, QFunction, LFA};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn test_tries_every_action() {
+        let q_func = make_shared(LFA::vector_output(OneHot::new(2), 3));
+        let mut p = UCB::new(q_func, 1.0).with_state_index(|s: &usize| *s);
+
+        for s in 0..2 {
+            let mut actions: Vec<usize> = (0..3).map(|_| p.sample(&s)).collect();
+            actions.sort();
+
+            assert_eq!(actions, vec![0, 1, 2]);
+            assert_eq!(p.counts(s), Some(&[1, 1, 1][..]));
+        }
+    }
+
+    #[test]
+    fn test_bandit() {
+        let means = [0.2, 0.5, 0.8];
+
+        let q_func = make_shared(LFA::vector_output(OneHot::new(1), 3));
+        let mut p = UCB::new(q_func.clone(), 1.0);
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let mut n_optimal = vec![];
+        for _ in 0..5000 {
+            let a = p.sample(&0usize);
+            let r = if rng.gen_bool(means[a]) { 1.0 } else { 0.0 };
+            let n = p.counts(0).unwrap()[a] as f64;
+            let q = q_func.evaluate_action(&0usize, a);
+
+            q_func.borrow_mut().update_action(&0usize, a, (r - q) / n);
+            n_optimal.push(if a == 2 { 1.0 } else { 0.0 });
+        }
+
+        let early = n_optimal[..500].iter().sum::<f64>() / 500.0;
+        let late = n_optimal[2500..].iter().sum::<f64>() / 2500.0;
+
+        assert!(late > early);
+        assert!(late > 0.8);
+        assert_eq!(q_func.n_outputs(), 3);
+    }
+}
@@ -16,13 +16,7 @@ use crate::{
     domains::Transition,
     fa::Parameterised,
 };
-use rand::{thread_rng, Rng, seq::SliceRandom};
-
-#[allow(dead_code)]
-#[inline]
-pub(self) fn sample_probs(probabilities: &[f64]) -> usize {
-    sample_probs_with_rng(&mut thread_rng(), probabilities)
-}
+use rand::{Rng, seq::SliceRandom};
 
 #[inline]
 pub(self) fn sample_probs_with_rng(rng: &mut impl Rng, probabilities: &[f64]) -> usize {
@@ -82,4 +76,29 @@ pub trait ParameterisedPolicy<S>: Policy<S> + Parameterised {
 pub mod fixed;
 pub mod parameterised;
 
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::FinitePolicy;
+    use crate::geometry::Vector;
+
+    /// Assert that a policy over four actions yields normalised probabilities that are consistent
+    /// with `Policy::probability`.
+    pub fn assert_normalised<P: FinitePolicy<Vector<f64>>>(p: &mut P) {
+        for qs in vec![
+            vec![0.0, 1.0, 2.0, 3.0],
+            vec![-1.0, 5.0, 5.0, 0.5],
+            vec![1.0, 1.0, 1.0, 1.0],
+        ] {
+            let qs: Vector<f64> = qs.into();
+            let ps = p.probabilities(&qs);
+
+            assert!((ps.sum() - 1.0).abs() < 1e-7);
+
+            for a in 0..4 {
+                assert!((p.probability(&qs, a) - ps[a]).abs() < 1e-7);
+            }
+        }
+    }
+}
+
 import_all!(perturbation);
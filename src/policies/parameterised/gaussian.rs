@@ -7,6 +7,7 @@ use rand::{
     distributions::{Distribution, Normal as NormalDist},
     rngs::ThreadRng,
     thread_rng,
+    Rng,
 };
 use std::ops::AddAssign;
 use super::pdfs::normal_pdf;
@@ -37,20 +38,26 @@ impl<M> Mean<ScalarLFA<M>> {
     }
 }
 
-pub struct Gaussian1d<F> {
+pub struct Gaussian1d<F, R = ThreadRng> {
     pub mean: Mean<F>,
     pub std: Parameter,
 
-    rng: ThreadRng,
+    rng: R,
 }
 
 impl<F> Gaussian1d<F> {
     pub fn new<T: Into<Parameter>>(fa_mean: F, std: T) -> Self {
+        Gaussian1d::with_rng(fa_mean, std, thread_rng())
+    }
+}
+
+impl<F, R> Gaussian1d<F, R> {
+    pub fn with_rng<T: Into<Parameter>>(fa_mean: F, std: T, rng: R) -> Self {
         Gaussian1d {
             mean: Mean { fa: fa_mean, },
             std: std.into(),
 
-            rng: thread_rng(),
+            rng,
         }
     }
 
@@ -74,13 +81,13 @@ impl<F> Gaussian1d<F> {
     }
 }
 
-impl<F> Algorithm for Gaussian1d<F> {
+impl<F, R> Algorithm for Gaussian1d<F, R> {
     fn handle_terminal(&mut self) {
         self.std = self.std.step();
     }
 }
 
-impl<S, F: VFunction<S>> Policy<S> for Gaussian1d<F> {
+impl<S, F: VFunction<S>, R: Rng> Policy<S> for Gaussian1d<F, R> {
     type Action = f64;
 
     fn sample(&mut self, input: &S) -> f64 {
@@ -100,19 +107,19 @@ impl<S, F: VFunction<S>> Policy<S> for Gaussian1d<F> {
     }
 }
 
-impl<S, M: Projector<S>> DifferentiablePolicy<S> for Gaussian1d<ScalarLFA<M>> {
+impl<S, M: Projector<S>, R: Rng> DifferentiablePolicy<S> for Gaussian1d<ScalarLFA<M>, R> {
     fn grad_log(&self, input: &S, a: f64) -> Matrix<f64> {
         self.mean.grad_log(input, a, self.std()).insert_axis(Axis(1))
     }
 }
 
-impl<F: Parameterised> Parameterised for Gaussian1d<F> {
+impl<F: Parameterised, R> Parameterised for Gaussian1d<F, R> {
     fn weights(&self) -> Matrix<f64> {
         self.mean.fa.weights()
     }
 }
 
-impl<S, M: Projector<S>> ParameterisedPolicy<S> for Gaussian1d<ScalarLFA<M>> {
+impl<S, M: Projector<S>, R: Rng> ParameterisedPolicy<S> for Gaussian1d<ScalarLFA<M>, R> {
     fn update(&mut self, input: &S, a: f64, error: f64) {
         let grad_log = self.grad_log(input, a);
 
@@ -13,10 +13,10 @@ use crate::{
 use rand::{rngs::ThreadRng, thread_rng, Rng};
 use std::{f64, ops::AddAssign};
 
-pub struct Gibbs<F> {
+pub struct Gibbs<F, R = ThreadRng> {
     pub fa: F,
 
-    rng: ThreadRng,
+    rng: R,
 }
 
 fn probabilities_from_values(values: &[f64]) -> Vector<f64> {
@@ -35,18 +35,22 @@ fn probabilities_from_values(values: &[f64]) -> Vector<f64> {
 }
 
 impl<F> Gibbs<F> {
-    pub fn new(fa: F) -> Self {
+    pub fn new(fa: F) -> Self { Gibbs::with_rng(fa, thread_rng()) }
+}
+
+impl<F, R> Gibbs<F, R> {
+    pub fn with_rng(fa: F, rng: R) -> Self {
         Gibbs {
             fa,
 
-            rng: thread_rng(),
+            rng,
         }
     }
 }
 
-impl<F> Algorithm for Gibbs<F> {}
+impl<F, R> Algorithm for Gibbs<F, R> {}
 
-impl<S, M: Projector<S>> Policy<S> for Gibbs<VectorLFA<M>> {
+impl<S, M: Projector<S>, R: Rng> Policy<S> for Gibbs<VectorLFA<M>, R> {
     type Action = usize;
 
     fn sample(&mut self, s: &S) -> usize {
@@ -64,7 +68,7 @@ impl<S, M: Projector<S>> Policy<S> for Gibbs<VectorLFA<M>> {
     fn probability(&mut self, input: &S, a: usize) -> f64 { self.probabilities(input)[a] }
 }
 
-impl<S, M: Projector<S>> FinitePolicy<S> for Gibbs<VectorLFA<M>> {
+impl<S, M: Projector<S>, R: Rng> FinitePolicy<S> for Gibbs<VectorLFA<M>, R> {
     fn n_actions(&self) -> usize {
         self.fa.n_outputs()
     }
@@ -76,7 +80,7 @@ impl<S, M: Projector<S>> FinitePolicy<S> for Gibbs<VectorLFA<M>> {
     }
 }
 
-impl<S, M: Projector<S>> DifferentiablePolicy<S> for Gibbs<VectorLFA<M>> {
+impl<S, M: Projector<S>, R: Rng> DifferentiablePolicy<S> for Gibbs<VectorLFA<M>, R> {
     fn grad_log(&self, input: &S, a: usize) -> Matrix<f64> {
         let phi = self.fa.projector.project(input);
 
@@ -100,11 +104,11 @@ impl<S, M: Projector<S>> DifferentiablePolicy<S> for Gibbs<VectorLFA<M>> {
     }
 }
 
-impl<F: Parameterised> Parameterised for Gibbs<F> {
+impl<F: Parameterised, R> Parameterised for Gibbs<F, R> {
     fn weights(&self) -> Matrix<f64> { self.fa.weights() }
 }
 
-impl<S, M: Projector<S>> ParameterisedPolicy<S> for Gibbs<VectorLFA<M>> {
+impl<S, M: Projector<S>, R: Rng> ParameterisedPolicy<S> for Gibbs<VectorLFA<M>, R> {
     fn update(&mut self, input: &S, a: usize, error: f64) {
         let grad_log = self.grad_log(input, a);
 
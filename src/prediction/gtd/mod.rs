@@ -8,3 +8,141 @@ import_all!(gtd2);
 // True online GTD(lambda) - http://citeseerx.ist.psu.edu/viewdoc/download?doi=10.1.1.487.2451&rep=rep1&type=pdf
 // GTD2(lambda)-MP - https://arxiv.org/pdf/1602.08771.pdf
 // TDC(lambda)-MP - https://arxiv.org/pdf/1602.08771.pdf
+
+#[cfg(test)]
+mod tests {
+    use crate::core::*;
+    use crate::domains::{Observation, Transition};
+    use crate::fa::{LFA, Projection, Projector};
+    use crate::geometry::{Card, Space};
+    use crate::policies::Policy;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use super::{GTD2, TDC};
+
+    const DASHED: usize = 0;
+    const SOLID: usize = 1;
+
+    /// Feature representation of Baird's counterexample.
+    struct BairdFeatures;
+
+    impl Space for BairdFeatures {
+        type Value = Projection;
+
+        fn dim(&self) -> usize { 8 }
+
+        fn card(&self) -> Card { Card::Infinite }
+    }
+
+    impl Projector<usize> for BairdFeatures {
+        fn project(&self, input: &usize) -> Projection {
+            let mut phi = vec![0.0; 8];
+
+            if *input < 6 {
+                phi[*input] = 2.0;
+                phi[7] = 1.0;
+            } else {
+                phi[6] = 1.0;
+                phi[7] = 2.0;
+            }
+
+            Projection::Dense(phi.into())
+        }
+    }
+
+    struct Behaviour;
+
+    impl Algorithm for Behaviour {}
+
+    impl Policy<usize> for Behaviour {
+        type Action = usize;
+
+        fn probability(&mut self, _: &usize, a: usize) -> f64 {
+            if a == DASHED { 6.0 / 7.0 } else { 1.0 / 7.0 }
+        }
+    }
+
+    struct Target;
+
+    impl Algorithm for Target {}
+
+    impl Policy<usize> for Target {
+        type Action = usize;
+
+        fn probability(&mut self, _: &usize, a: usize) -> f64 {
+            if a == SOLID { 1.0 } else { 0.0 }
+        }
+    }
+
+    fn initial_weights() -> Vector<f64> { vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 10.0, 1.0].into() }
+
+    fn transitions(n: usize) -> Vec<Transition<usize, usize>> {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut s = rng.gen_range(0, 7);
+
+        (0..n).map(|_| {
+            let (a, ns) = if rng.gen_bool(6.0 / 7.0) {
+                (DASHED, rng.gen_range(0, 6))
+            } else {
+                (SOLID, 6)
+            };
+
+            let t = Transition {
+                from: Observation::Full(s),
+                action: a,
+                reward: 0.0,
+                to: Observation::Full(ns),
+                truncated: false,
+            };
+
+            s = ns;
+
+            t
+        }).collect()
+    }
+
+    fn norm(w: &Vector<f64>) -> f64 { w.dot(w).sqrt() }
+
+    #[test]
+    fn test_bairds_counterexample() {
+        let ts = transitions(2000);
+
+        // Off-policy TD(0) with importance sampling diverges:
+        let mut theta = initial_weights();
+
+        for t in ts.iter() {
+            let (phi_s, phi_ns) = t.map_states(|s| BairdFeatures.project(s).expanded(8));
+            let rho = Target.probability(t.from.state(), t.action)
+                / Behaviour.probability(t.from.state(), t.action);
+            let td_error = t.reward + 0.99 * theta.dot(&phi_ns) - theta.dot(&phi_s);
+
+            theta.scaled_add(0.005 * rho * td_error, &phi_s);
+        }
+
+        assert!(norm(&theta) > 100.0);
+
+        // Whereas the gradient-TD methods remain stable:
+        let fa_theta = make_shared(LFA::scalar_output(BairdFeatures));
+        let fa_w = make_shared(LFA::scalar_output(BairdFeatures));
+        fa_theta.borrow_mut().approximator.weights.assign(&initial_weights());
+
+        let mut gtd2 = GTD2::new(fa_theta.clone(), fa_w, 0.005, 0.05, 0.99);
+
+        for t in ts.iter() {
+            gtd2.handle_off_policy(t, &mut Target, &mut Behaviour);
+
+            assert!(norm(&fa_theta.approximator.weights) < 15.0);
+        }
+
+        let fa_theta = make_shared(LFA::scalar_output(BairdFeatures));
+        let fa_w = make_shared(LFA::scalar_output(BairdFeatures));
+        fa_theta.borrow_mut().approximator.weights.assign(&initial_weights());
+
+        let mut tdc = TDC::new(fa_theta.clone(), fa_w, 0.005, 0.05, 0.99);
+
+        for t in ts.iter() {
+            tdc.handle_off_policy(t, &mut Target, &mut Behaviour);
+
+            assert!(norm(&fa_theta.approximator.weights) < 15.0);
+        }
+    }
+}
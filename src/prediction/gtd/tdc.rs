@@ -2,6 +2,7 @@ use crate::core::*;
 use crate::domains::Transition;
 use crate::fa::{Approximator, Parameterised, Projection, Projector, ScalarLFA, VFunction};
 use crate::geometry::Space;
+use crate::policies::Policy;
 
 pub struct TDC<M> {
     pub fa_theta: Shared<ScalarLFA<M>>,
@@ -43,13 +44,34 @@ impl<M: Space> TDC<M> {
 impl<M> Algorithm for TDC<M> {
     fn handle_terminal(&mut self) {
         self.alpha = self.alpha.step();
-        self.beta = self.alpha.step();
+        self.beta = self.beta.step();
         self.gamma = self.gamma.step();
     }
 }
 
-impl<S, A, M: Projector<S>> OnlineLearner<S, A> for TDC<M> {
-    fn handle_transition(&mut self, t: &Transition<S, A>) {
+impl<M> TDC<M> {
+    /// Handle a transition sampled from a behaviour policy, correcting for the
+    /// discrepancy with the target policy using the importance sampling ratio.
+    pub fn handle_off_policy<S, A, PT, PB>(
+        &mut self,
+        t: &Transition<S, A>,
+        target: &mut PT,
+        behaviour: &mut PB,
+    )
+    where
+        M: Projector<S>,
+        A: Clone,
+        PT: Policy<S, Action = A>,
+        PB: Policy<S, Action = A>,
+    {
+        let s = t.from.state();
+        let rho = target.probability(s, t.action.clone())
+            / behaviour.probability(s, t.action.clone());
+
+        self.update(t, rho);
+    }
+
+    fn update<S, A>(&mut self, t: &Transition<S, A>, rho: f64) where M: Projector<S> {
         let (phi_s, phi_ns) = t.map_states(|s| self.fa_theta.projector.project(s));
 
         let v = self.fa_theta.evaluate_phi(&phi_s);
@@ -61,14 +83,23 @@ impl<S, A, M: Projector<S>> OnlineLearner<S, A> for TDC<M> {
             t.reward + self.gamma * self.fa_theta.evaluate_phi(&phi_ns) - v
         };
 
-        self.fa_w.borrow_mut().update_phi(&phi_s, self.beta * (td_error - td_estimate));
+        self.fa_w.borrow_mut().update_phi(&phi_s, self.beta * (rho * td_error - td_estimate));
 
         let dim = self.fa_theta.projector.dim();
-        let phi =
+        let phi = if t.terminated() {
+            td_error * phi_s.expanded(dim)
+        } else {
             td_error * phi_s.expanded(dim) -
-            td_estimate * self.gamma.value() * phi_ns.expanded(dim);
+                td_estimate * self.gamma.value() * phi_ns.expanded(dim)
+        };
 
-        self.fa_theta.borrow_mut().update_phi(&Projection::Dense(phi), self.alpha.value());
+        self.fa_theta.borrow_mut().update_phi(&Projection::Dense(phi), self.alpha * rho);
+    }
+}
+
+impl<S, A, M: Projector<S>> OnlineLearner<S, A> for TDC<M> {
+    fn handle_transition(&mut self, t: &Transition<S, A>) {
+        self.update(t, 1.0);
     }
 }
 
@@ -14,6 +14,8 @@ pub struct LSTDLambda<M> {
 
     a: Matrix<f64>,
     b: Vector<f64>,
+
+    epsilon: f64,
 }
 
 impl<M: Space> LSTDLambda<M> {
@@ -30,11 +32,21 @@ impl<M: Space> LSTDLambda<M> {
 
             a: Matrix::zeros((n_features, n_features)),
             b: Vector::zeros((n_features,)),
+
+            epsilon: 0.0,
         }
     }
 }
 
 impl<M> LSTDLambda<M> {
+    /// Regularise the solution by adding the ridge term `epsilon * I` to the A
+    /// matrix.
+    pub fn with_regularisation(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+
+        self
+    }
+
     #[inline]
     fn update_trace(&mut self, phi: &Vector<f64>) -> Vector<f64> {
         let decay_rate = self.trace.lambda.value() * self.gamma.value();
@@ -46,12 +58,18 @@ impl<M> LSTDLambda<M> {
     }
 
     pub fn solve(&mut self) {
+        let a = if self.epsilon > 0.0 {
+            &self.a + &(Matrix::eye(self.a.rows()) * self.epsilon)
+        } else {
+            self.a.clone()
+        };
+
         // First try the clean approach:
-        if let Ok(theta) = self.a.solve(&self.b) {
+        if let Ok(theta) = a.solve(&self.b) {
             self.fa_theta.borrow_mut().approximator.weights.assign(&theta);
 
         // Otherwise solve via SVD:
-        } else if let Ok(ainv) = pinv(&self.a) {
+        } else if let Ok(ainv) = pinv(&a) {
             let theta = ainv.dot(&self.b);
 
             self.fa_theta.borrow_mut().approximator.weights.assign(&theta);
@@ -76,8 +94,6 @@ impl<S, A, M: Projector<S>> BatchLearner<S, A> for LSTDLambda<M> {
             self.b.scaled_add(t.reward, &z);
 
             let pd = if t.terminated() {
-                self.trace.decay(0.0);
-
                 phi_s
             } else {
                 let phi_ns = self.fa_theta.projector
@@ -88,6 +104,12 @@ impl<S, A, M: Projector<S>> BatchLearner<S, A> for LSTDLambda<M> {
             }.insert_axis(Axis(0));
 
             self.a += &z.insert_axis(Axis(1)).dot(&pd);
+
+            // Truncated episodes still bootstrap, but must not share a trace
+            // with the next episode in the batch:
+            if t.ended() {
+                self.trace.decay(0.0);
+            }
         });
 
         self.solve();
@@ -116,3 +138,89 @@ where
         self.fa_theta.weights()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::Observation;
+    use crate::fa::LFA;
+    use crate::geometry::Card;
+
+    const N_STATES: usize = 13;
+
+    /// Linearly interpolated features of the Boyan chain.
+    struct BoyanFeatures;
+
+    impl Space for BoyanFeatures {
+        type Value = Projection;
+
+        fn dim(&self) -> usize { 4 }
+
+        fn card(&self) -> Card { Card::Infinite }
+    }
+
+    impl Projector<usize> for BoyanFeatures {
+        fn project(&self, input: &usize) -> Projection {
+            let mut phi = vec![0.0; 4];
+            let (i, r) = ((N_STATES - 1 - input) / 4, (N_STATES - 1 - input) % 4);
+
+            if r == 0 {
+                phi[i] = 1.0;
+            } else {
+                phi[i] = 1.0 - r as f64 / 4.0;
+                phi[i + 1] = r as f64 / 4.0;
+            }
+
+            Projection::Dense(phi.into())
+        }
+    }
+
+    fn boyan_transitions() -> Vec<Transition<usize, ()>> {
+        let transition = |from: usize, to: usize| Transition {
+            from: Observation::Full(from),
+            action: (),
+            reward: if from == 1 { -2.0 } else { -3.0 },
+            to: if to == 0 { Observation::Terminal(to) } else { Observation::Full(to) },
+            truncated: false,
+        };
+
+        let mut ts = vec![transition(1, 0)];
+
+        for s in 2..N_STATES {
+            ts.push(transition(s, s - 1));
+            ts.push(transition(s, s - 2));
+        }
+
+        ts
+    }
+
+    #[test]
+    fn test_boyan_chain() {
+        let mut agent = LSTDLambda::new(
+            make_shared(LFA::scalar_output(BoyanFeatures)),
+            Trace::accumulating(0.0, 4),
+            1.0,
+        );
+
+        agent.handle_batch(&boyan_transitions());
+
+        for s in 1..N_STATES {
+            assert!((agent.predict_v(&s) + 2.0 * s as f64).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_regularisation() {
+        let mut agent = LSTDLambda::new(
+            make_shared(LFA::scalar_output(BoyanFeatures)),
+            Trace::accumulating(0.0, 4),
+            1.0,
+        ).with_regularisation(1e-6);
+
+        agent.handle_batch(&boyan_transitions());
+
+        for s in 1..N_STATES {
+            assert!((agent.predict_v(&s) + 2.0 * s as f64).abs() < 1e-3);
+        }
+    }
+}
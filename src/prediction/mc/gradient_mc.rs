@@ -1,12 +1,30 @@
 use crate::core::*;
 use crate::domains::Transition;
-use crate::fa::{Parameterised, VFunction};
+use crate::fa::{Parameterised, VFunction, delegate_weights};
+
+/// Which occurrences of a state within an episode contribute an update.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VisitKind {
+    /// Only the first occurrence of each state is updated.
+    First,
+
+    /// Every occurrence of each state is updated.
+    Every,
+}
+
+impl Default for VisitKind {
+    fn default() -> VisitKind { VisitKind::Every }
+}
 
 pub struct GradientMC<V> {
     pub v_func: Shared<V>,
 
     pub alpha: Parameter,
     pub gamma: Parameter,
+
+    step_size: Option<AdaptiveStepSize>,
+
+    pub visits: VisitKind,
 }
 
 impl<V> GradientMC<V> {
@@ -20,8 +38,27 @@ impl<V> GradientMC<V> {
 
             alpha: alpha.into(),
             gamma: gamma.into(),
+
+            step_size: None,
+
+            visits: VisitKind::default(),
         }
     }
+
+    /// Set the rule used to derive per-feature step sizes; see
+    /// `AdaptiveStepSize::configure`.
+    pub fn with_step_size(mut self, step_size: StepSize) -> Self {
+        self.step_size = AdaptiveStepSize::configure(step_size, &mut self.alpha);
+
+        self
+    }
+
+    /// Select between first-visit and every-visit updates.
+    pub fn with_visits(mut self, visits: VisitKind) -> Self {
+        self.visits = visits;
+
+        self
+    }
 }
 
 impl<V> Algorithm for GradientMC<V> {
@@ -31,16 +68,44 @@ impl<V> Algorithm for GradientMC<V> {
     }
 }
 
-impl<S, A, V: VFunction<S>> BatchLearner<S, A> for GradientMC<V> {
+impl<S: PartialEq, A, V: VFunction<S>> BatchLearner<S, A> for GradientMC<V> {
     fn handle_batch(&mut self, batch: &[Transition<S, A>]) {
-        let mut sum = 0.0;
+        for episode in split_episodes(batch) {
+            self.update_episode(episode);
+        }
+    }
+}
+
+impl<V> GradientMC<V> {
+    fn update_episode<S: PartialEq, A>(&mut self, batch: &[Transition<S, A>]) where V: VFunction<S> {
+        let rewards: Vec<f64> = batch.iter().map(|t| t.reward).collect();
+        let returns = discounted_returns(&rewards, self.gamma.value());
 
-        batch.into_iter().rev().for_each(|ref t| {
-            sum = t.reward + self.gamma * sum;
+        let visits = self.visits;
+        let is_first = |i: usize| {
+            let s = batch[i].from.state();
+
+            !batch[..i].iter().any(|t| t.from.state() == s)
+        };
+
+        batch.iter().zip(returns.into_iter()).enumerate().rev().for_each(|(i, (t, ret))| {
+            if visits == VisitKind::First && !is_first(i) {
+                return;
+            }
 
             let s = t.from.state();
             let v_est = self.v_func.evaluate(s).unwrap();
-            let _ = self.v_func.borrow_mut().update(s, self.alpha * (sum - v_est));
+
+            match self.step_size {
+                Some(ref mut step_size) => {
+                    let phi = self.v_func.features(s);
+
+                    step_size.apply(&mut *self.v_func.borrow_mut(), &phi, ret - v_est);
+                },
+                None => {
+                    let _ = self.v_func.borrow_mut().update(s, self.alpha * (ret - v_est));
+                },
+            }
         })
     }
 }
@@ -53,8 +118,59 @@ impl<S, V: VFunction<S>> ValuePredictor<S> for GradientMC<V> {
 
 impl<S, A, V: VFunction<S>> ActionValuePredictor<S, A> for GradientMC<V> {}
 
-impl<V: Parameterised> Parameterised for GradientMC<V> {
-    fn weights(&self) -> Matrix<f64> {
-        self.v_func.weights()
+delegate_weights!(GradientMC<V>, v_func: V);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::Observation;
+    use crate::fa::{mocking::OneHot, ScalarLFA, LFA};
+
+    /// Episode that revisits state 0 before terminating: 0 -> 1 -> 0 -> 2.
+    fn looping_episode() -> Vec<Transition<usize, ()>> {
+        vec![
+            Transition { from: Observation::Full(0), action: (), reward: 0.0, to: Observation::Full(1), truncated: false },
+            Transition { from: Observation::Full(1), action: (), reward: 0.0, to: Observation::Full(0), truncated: false },
+            Transition { from: Observation::Full(0), action: (), reward: 1.0, to: Observation::Terminal(2), truncated: false },
+        ]
+    }
+
+    fn agent(visits: VisitKind) -> GradientMC<ScalarLFA<OneHot>> {
+        GradientMC::new(make_shared(LFA::scalar_output(OneHot::new(3))), 0.5, 1.0)
+            .with_visits(visits)
+    }
+
+    #[test]
+    fn test_every_visit() {
+        let mut agent = agent(VisitKind::Every);
+
+        agent.handle_batch(&looping_episode());
+
+        // Two updates towards a return of 1: 0.5, then 0.75.
+        assert!((agent.predict_v(&0) - 0.75).abs() < 1e-10);
+        assert!((agent.predict_v(&1) - 0.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_first_visit() {
+        let mut agent = agent(VisitKind::First);
+
+        agent.handle_batch(&looping_episode());
+
+        assert!((agent.predict_v(&0) - 0.5).abs() < 1e-10);
+        assert!((agent.predict_v(&1) - 0.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_adagrad() {
+        let mut agent = GradientMC::new(make_shared(LFA::scalar_output(OneHot::new(3))), 0.5, 1.0)
+            .with_step_size(StepSize::AdaGrad(0.5));
+
+        agent.handle_batch(&looping_episode());
+
+        // The first AdaGrad step for each feature has magnitude alpha, while
+        // the second update to state 0 (error 0.5) is scaled by 1 / sqrt(1.25):
+        assert!((agent.predict_v(&0) - (0.5 + 0.25 / 1.25f64.sqrt())).abs() < 1e-6);
+        assert!((agent.predict_v(&1) - 0.5).abs() < 1e-6);
     }
 }
@@ -0,0 +1,259 @@
+use crate::core::*;
+use crate::domains::Transition;
+use crate::fa::{Parameterised, VFunction, delegate_weights};
+use crate::policies::Policy;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Flag selecting weighted, rather than ordinary, importance sampling.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WeightedIS(pub bool);
+
+/// Off-policy every-visit Monte-Carlo prediction with importance sampling.
+///
+/// The return from each time step is corrected by the product of the ratios
+/// `pi(a|s) / b(a|s)` over the remainder of the trajectory. With ordinary
+/// importance sampling the value of each state is the sample average of the
+/// corrected returns; with weighted importance sampling it is the average of
+/// the returns weighted by their ratios. Running totals are kept per state,
+/// so this estimator is intended for discrete state spaces.
+///
+/// # References
+/// - Sutton, R. S. and Barto, A. G. (2017). Reinforcement Learning: An
+/// Introduction (2nd ed.). Manuscript in preparation.
+pub struct ImportanceSampledMC<S, V, T, B> {
+    pub v_func: Shared<V>,
+
+    pub target_policy: Shared<T>,
+    pub behaviour_policy: Shared<B>,
+
+    pub gamma: Parameter,
+    pub weighted: WeightedIS,
+
+    totals: HashMap<S, f64>,
+}
+
+impl<S: Hash + Eq, V, T, B> ImportanceSampledMC<S, V, T, B> {
+    pub fn new<T1: Into<Parameter>>(
+        v_func: Shared<V>,
+        target_policy: Shared<T>,
+        behaviour_policy: Shared<B>,
+        gamma: T1,
+    ) -> Self
+    {
+        ImportanceSampledMC {
+            v_func,
+
+            target_policy,
+            behaviour_policy,
+
+            gamma: gamma.into(),
+            weighted: WeightedIS::default(),
+
+            totals: HashMap::new(),
+        }
+    }
+
+    /// Select between ordinary and weighted importance sampling.
+    pub fn with_weighting(mut self, weighted: WeightedIS) -> Self {
+        self.weighted = weighted;
+
+        self
+    }
+}
+
+impl<S, V, T: Algorithm, B: Algorithm> Algorithm for ImportanceSampledMC<S, V, T, B> {
+    fn handle_terminal(&mut self) {
+        self.gamma = self.gamma.step();
+
+        self.target_policy.borrow_mut().handle_terminal();
+        self.behaviour_policy.borrow_mut().handle_terminal();
+    }
+}
+
+impl<S, V, T, B> BatchLearner<S, T::Action> for ImportanceSampledMC<S, V, T, B>
+where
+    S: Hash + Eq + Clone,
+    V: VFunction<S>,
+    T: Policy<S>,
+    T::Action: Clone,
+    B: Policy<S, Action = T::Action>,
+{
+    fn handle_batch(&mut self, batch: &[Transition<S, T::Action>]) {
+        for episode in split_episodes(batch) {
+            self.update_episode(episode);
+        }
+    }
+}
+
+impl<S, V, T, B> ImportanceSampledMC<S, V, T, B> {
+    fn update_episode(&mut self, batch: &[Transition<S, T::Action>])
+    where
+        S: Hash + Eq + Clone,
+        V: VFunction<S>,
+        T: Policy<S>,
+        T::Action: Clone,
+        B: Policy<S, Action = T::Action>,
+    {
+        let gamma = self.gamma.value();
+
+        let mut ret = 0.0;
+        let mut rho = 1.0;
+
+        for t in batch.iter().rev() {
+            let s = t.from.state();
+
+            ret = t.reward + gamma * ret;
+            rho *= self.target_policy.borrow_mut().probability(s, t.action.clone())
+                / self.behaviour_policy.borrow_mut().probability(s, t.action.clone());
+
+            // Under weighted importance sampling, no earlier step of the
+            // episode can contribute once the ratio vanishes:
+            if self.weighted.0 && rho == 0.0 {
+                break;
+            }
+
+            let (weight, target) = if self.weighted.0 { (rho, ret) } else { (1.0, rho * ret) };
+            let total = self.totals.entry(s.clone()).or_insert(0.0);
+
+            *total += weight;
+
+            if *total > 0.0 {
+                let v_est = self.v_func.evaluate(s).unwrap();
+                let _ = self.v_func.borrow_mut().update(s, weight / *total * (target - v_est));
+            }
+        }
+    }
+}
+
+impl<S, V: VFunction<S>, T, B> ValuePredictor<S> for ImportanceSampledMC<S, V, T, B> {
+    fn predict_v(&mut self, s: &S) -> f64 {
+        self.v_func.evaluate(s).unwrap()
+    }
+}
+
+impl<S, A, V: VFunction<S>, T, B> ActionValuePredictor<S, A> for ImportanceSampledMC<S, V, T, B> {}
+
+delegate_weights!(ImportanceSampledMC<S, V, T, B>, v_func: V);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::Observation;
+    use crate::fa::{mocking::OneHot, LFA};
+    use crate::policies::fixed::Random;
+
+    /// Deterministic target policy that always selects the first action.
+    struct FirstAction;
+
+    impl Algorithm for FirstAction {}
+
+    impl Policy<usize> for FirstAction {
+        type Action = usize;
+
+        fn sample(&mut self, _: &usize) -> usize { 0 }
+
+        fn probability(&mut self, _: &usize, a: usize) -> f64 { if a == 0 { 1.0 } else { 0.0 } }
+    }
+
+    /// Two-step chain, 0 -> 1 -> 2, rewarding the first action on the last step.
+    fn episode(a0: usize, a1: usize) -> Vec<Transition<usize, usize>> {
+        vec![
+            Transition {
+                from: Observation::Full(0),
+                action: a0,
+                reward: 0.0,
+                to: Observation::Full(1),
+                truncated: false,
+            },
+            Transition {
+                from: Observation::Full(1),
+                action: a1,
+                reward: if a1 == 0 { 1.0 } else { 0.0 },
+                to: Observation::Terminal(2),
+                truncated: false,
+            },
+        ]
+    }
+
+    fn estimate(weighted: bool) -> (f64, f64) {
+        let mut agent = ImportanceSampledMC::new(
+            make_shared(LFA::scalar_output(OneHot::new(3))),
+            make_shared(FirstAction),
+            make_shared(Random::new(2)),
+            1.0,
+        ).with_weighting(WeightedIS(weighted));
+
+        // Each behaviour trajectory is observed once, in proportion to its
+        // probability under the uniform behaviour policy:
+        for &(a0, a1) in [(0, 0), (0, 1), (1, 0), (1, 1)].iter() {
+            agent.handle_batch(&episode(a0, a1));
+        }
+
+        (agent.predict_v(&0), agent.predict_v(&1))
+    }
+
+    #[test]
+    fn test_ordinary() {
+        // The target policy always earns a return of 1:
+        let (v0, v1) = estimate(false);
+
+        assert!((v0 - 1.0).abs() < 1e-10);
+        assert!((v1 - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_weighted() {
+        let (v0, v1) = estimate(true);
+
+        assert!((v0 - 1.0).abs() < 1e-10);
+        assert!((v1 - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_weighted_single_episode() {
+        // Weighted IS is exact after a single trajectory with non-zero ratio,
+        // whereas ordinary IS scales the return by the ratio itself:
+        let mut weighted = ImportanceSampledMC::new(
+            make_shared(LFA::scalar_output(OneHot::new(3))),
+            make_shared(FirstAction),
+            make_shared(Random::new(2)),
+            1.0,
+        ).with_weighting(WeightedIS(true));
+        let mut ordinary = ImportanceSampledMC::new(
+            make_shared(LFA::scalar_output(OneHot::new(3))),
+            make_shared(FirstAction),
+            make_shared(Random::new(2)),
+            1.0,
+        );
+
+        weighted.handle_batch(&episode(0, 0));
+        ordinary.handle_batch(&episode(0, 0));
+
+        assert!((weighted.predict_v(&0) - 1.0).abs() < 1e-10);
+        assert!((ordinary.predict_v(&0) - 4.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_episode_boundaries() {
+        // Handling all four trajectories in one batch must not carry returns
+        // or ratios across episodes:
+        for &weighted in [false, true].iter() {
+            let mut agent = ImportanceSampledMC::new(
+                make_shared(LFA::scalar_output(OneHot::new(3))),
+                make_shared(FirstAction),
+                make_shared(Random::new(2)),
+                1.0,
+            ).with_weighting(WeightedIS(weighted));
+
+            let batch: Vec<_> = [(0, 0), (0, 1), (1, 0), (1, 1)].iter()
+                .flat_map(|&(a0, a1)| episode(a0, a1))
+                .collect();
+
+            agent.handle_batch(&batch);
+
+            assert!((agent.predict_v(&0) - 1.0).abs() < 1e-10);
+            assert!((agent.predict_v(&1) - 1.0).abs() < 1e-10);
+        }
+    }
+}
@@ -1 +1,2 @@
 import_all!(gradient_mc);
+import_all!(importance_sampled_mc);
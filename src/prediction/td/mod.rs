@@ -1,12 +1,12 @@
 import_all!(td);
 import_all!(td_lambda);
+import_all!(true_online_td);
 
 // TODO:
 // n-step TD - Sutton & Barto
 // ETD(lambda) - https://arxiv.org/pdf/1503.04269.pdf
 // HTD(lambda) - https://arxiv.org/pdf/1602.08771.pdf
 // PTD(lambda) - http://proceedings.mlr.press/v32/sutton14.pdf
-// True online TD(lambda) - http://proceedings.mlr.press/v32/seijen14.pdf
 // True online ETD(lambda) - https://arxiv.org/pdf/1602.08771.pdf
 // True online ETD(beta, lambda) - https://arxiv.org/pdf/1602.08771.pdf
 // True online HTD(lambda) - https://arxiv.org/pdf/1602.08771.pdf
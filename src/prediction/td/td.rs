@@ -1,6 +1,6 @@
 use crate::core::*;
 use crate::domains::Transition;
-use crate::fa::{Parameterised, VFunction};
+use crate::fa::{Parameterised, VFunction, delegate_weights};
 use crate::geometry::Matrix;
 
 pub struct TD<V> {
@@ -8,6 +8,8 @@ pub struct TD<V> {
 
     pub alpha: Parameter,
     pub gamma: Parameter,
+
+    step_size: Option<AdaptiveStepSize>,
 }
 
 impl<V> TD<V> {
@@ -21,8 +23,18 @@ impl<V> TD<V> {
 
             alpha: alpha.into(),
             gamma: gamma.into(),
+
+            step_size: None,
         }
     }
+
+    /// Set the rule used to derive per-feature step sizes; see
+    /// `AdaptiveStepSize::configure`.
+    pub fn with_step_size(mut self, step_size: StepSize) -> Self {
+        self.step_size = AdaptiveStepSize::configure(step_size, &mut self.alpha);
+
+        self
+    }
 }
 
 impl<V> Algorithm for TD<V> {
@@ -43,7 +55,16 @@ impl<S, A, V: VFunction<S>> OnlineLearner<S, A> for TD<V> {
             t.reward + self.gamma * self.predict_v(t.to.state()) - v
         };
 
-        self.v_func.borrow_mut().update(s, self.alpha * td_error).ok();
+        match self.step_size {
+            Some(ref mut step_size) => {
+                let phi = self.v_func.features(s);
+
+                step_size.apply(&mut *self.v_func.borrow_mut(), &phi, td_error);
+            },
+            None => {
+                self.v_func.borrow_mut().update(s, self.alpha * td_error).ok();
+            },
+        }
     }
 }
 
@@ -53,8 +74,8 @@ impl<S, V: VFunction<S>> ValuePredictor<S> for TD<V> {
 
 impl<S, A, V: VFunction<S>> ActionValuePredictor<S, A> for TD<V> {}
 
-impl<V: Parameterised> Parameterised for TD<V> {
-    fn weights(&self) -> Matrix<f64> {
-        self.v_func.weights()
-    }
+impl<S, V: VFunction<S>> ContinuousActionValuePredictor<S> for TD<V> {
+    fn predict_qsa_continuous(&mut self, s: &S, _: f64) -> f64 { self.predict_v(s) }
 }
+
+delegate_weights!(TD<V>, v_func: V);
@@ -38,6 +38,8 @@ impl<M> Algorithm for TDLambda<M> {
     fn handle_terminal(&mut self) {
         self.alpha = self.alpha.step();
         self.gamma = self.gamma.step();
+
+        self.trace.clear();
     }
 }
 
@@ -0,0 +1,246 @@
+use crate::core::*;
+use crate::domains::Transition;
+use crate::fa::{Approximator, Parameterised, Projection, Projector, ScalarLFA, VFunction};
+use crate::geometry::Matrix;
+
+/// True online TD(lambda).
+///
+/// Unlike conventional TD(lambda), the forward view of the lambda-return is
+/// matched exactly at every time step by using a dutch trace together with a
+/// correction term on the weight update.
+///
+/// # References
+/// - van Seijen, H., & Sutton, R. S. (2014). True online TD(lambda). In
+/// Proceedings of the 31st International Conference on Machine Learning, pp.
+/// 692–700.
+pub struct TrueOnlineTD<M> {
+    pub fa_theta: Shared<ScalarLFA<M>>,
+
+    pub alpha: Parameter,
+    pub gamma: Parameter,
+
+    trace: Trace,
+    v_old: f64,
+}
+
+impl<M> TrueOnlineTD<M> {
+    pub fn new<T1, T2>(
+        fa_theta: Shared<ScalarLFA<M>>,
+        trace: Trace,
+        alpha: T1,
+        gamma: T2,
+    ) -> Self
+    where
+        T1: Into<Parameter>,
+        T2: Into<Parameter>,
+    {
+        TrueOnlineTD {
+            fa_theta,
+
+            alpha: alpha.into(),
+            gamma: gamma.into(),
+
+            trace,
+            v_old: 0.0,
+        }
+    }
+}
+
+impl<M> Algorithm for TrueOnlineTD<M> {
+    fn handle_terminal(&mut self) {
+        self.alpha = self.alpha.step();
+        self.gamma = self.gamma.step();
+
+        // Episodes truncated by a time limit end without a terminal transition:
+        self.trace.clear();
+        self.v_old = 0.0;
+    }
+}
+
+impl<S, A, M: Projector<S>> OnlineLearner<S, A> for TrueOnlineTD<M> {
+    fn handle_transition(&mut self, t: &Transition<S, A>) {
+        let alpha = self.alpha.value();
+        let gamma = self.gamma.value();
+        let decay_rate = self.trace.lambda.value() * gamma;
+
+        let phi_s = self.fa_theta.projector.project(t.from.state());
+        let phi_s = phi_s.expanded(self.fa_theta.projector.dim());
+
+        let v = self.fa_theta.evaluate_phi(&Projection::Dense(phi_s.clone()));
+        let nv = if t.terminated() { 0.0 } else { self.predict_v(t.to.state()) };
+
+        let td_error = t.reward + gamma * nv - v;
+
+        // Dutch trace update:
+        let e_phi = self.trace.eligibility.dot(&phi_s);
+
+        self.trace.decay(decay_rate);
+        self.trace.eligibility.scaled_add(1.0 - alpha * decay_rate * e_phi, &phi_s);
+
+        // The weights are adjusted directly, as dense updates through the
+        // approximator are normalised by the L1 norm of the projection:
+        let weights = &mut self.fa_theta.borrow_mut().approximator.weights;
+
+        weights.scaled_add(alpha * (td_error + v - self.v_old), &self.trace.get());
+        weights.scaled_add(-alpha * (v - self.v_old), &phi_s);
+
+        if t.terminated() {
+            self.trace.decay(0.0);
+            self.v_old = 0.0;
+        } else {
+            self.v_old = nv;
+        }
+    }
+}
+
+impl<S, M> ValuePredictor<S> for TrueOnlineTD<M>
+where
+    ScalarLFA<M>: VFunction<S>,
+{
+    fn predict_v(&mut self, s: &S) -> f64 {
+        self.fa_theta.evaluate(s).unwrap()
+    }
+}
+
+impl<S, A, M> ActionValuePredictor<S, A> for TrueOnlineTD<M>
+where
+    ScalarLFA<M>: VFunction<S>,
+{}
+
+impl<M> Parameterised for TrueOnlineTD<M>
+where
+    ScalarLFA<M>: Parameterised
+{
+    fn weights(&self) -> Matrix<f64> {
+        self.fa_theta.weights()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::Observation;
+    use crate::fa::{mocking::OneHot, LFA};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const N_STATES: usize = 5;
+
+    fn random_walk(rng: &mut StdRng) -> Vec<Transition<usize, ()>> {
+        let mut s = N_STATES / 2;
+        let mut transitions = vec![];
+
+        loop {
+            let (reward, to) = if rng.gen_bool(0.5) {
+                if s + 1 == N_STATES {
+                    (1.0, Observation::Terminal(s))
+                } else {
+                    (0.0, Observation::Full(s + 1))
+                }
+            } else {
+                if s == 0 {
+                    (0.0, Observation::Terminal(s))
+                } else {
+                    (0.0, Observation::Full(s - 1))
+                }
+            };
+
+            let ns = match to {
+                Observation::Terminal(_) => None,
+                _ => Some(*to.state()),
+            };
+
+            transitions.push(Transition {
+                from: Observation::Full(s),
+                action: (),
+                reward,
+                to,
+                truncated: false,
+            });
+
+            match ns {
+                Some(ns) => s = ns,
+                None => return transitions,
+            }
+        }
+    }
+
+    fn learn<L>(agent: &mut L, episodes: &[Vec<Transition<usize, ()>>]) -> Vec<f64>
+    where L: OnlineLearner<usize, ()> + ValuePredictor<usize> {
+        for episode in episodes {
+            for t in episode {
+                agent.handle_transition(t);
+            }
+
+            agent.handle_terminal();
+        }
+
+        (0..N_STATES).map(|s| agent.predict_v(&s)).collect()
+    }
+
+    /// Tabular TD(lambda) with accumulating traces and unnormalised updates.
+    fn learn_td_lambda(episodes: &[Vec<Transition<usize, ()>>], alpha: f64, lambda: f64) -> Vec<f64> {
+        let mut v = vec![0.0; N_STATES];
+
+        for episode in episodes {
+            let mut e = vec![0.0; N_STATES];
+
+            for t in episode {
+                let s = *t.from.state();
+                let nv = if t.terminated() { 0.0 } else { v[*t.to.state()] };
+                let td_error = t.reward + nv - v[s];
+
+                e.iter_mut().for_each(|z| *z *= lambda);
+                e[s] += 1.0;
+
+                v.iter_mut().zip(e.iter()).for_each(|(v, z)| *v += alpha * td_error * z);
+            }
+        }
+
+        v
+    }
+
+    fn mean_and_variance(estimates: &[Vec<f64>]) -> (Vec<f64>, f64) {
+        let n = estimates.len() as f64;
+        let means: Vec<f64> = (0..N_STATES)
+            .map(|i| estimates.iter().map(|v| v[i]).sum::<f64>() / n)
+            .collect();
+        let variance = (0..N_STATES)
+            .map(|i| estimates.iter().map(|v| (v[i] - means[i]).powi(2)).sum::<f64>() / n)
+            .sum::<f64>() / N_STATES as f64;
+
+        (means, variance)
+    }
+
+    #[test]
+    fn test_random_walk() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let mut tol_estimates = vec![];
+        let mut tdl_estimates = vec![];
+
+        for _ in 0..20 {
+            let episodes: Vec<_> = (0..100).map(|_| random_walk(&mut rng)).collect();
+
+            let mut tol = TrueOnlineTD::new(
+                make_shared(LFA::scalar_output(OneHot::new(N_STATES))),
+                Trace::accumulating(0.9, N_STATES),
+                0.3,
+                1.0,
+            );
+
+            tol_estimates.push(learn(&mut tol, &episodes));
+            tdl_estimates.push(learn_td_lambda(&episodes, 0.3, 0.9));
+        }
+
+        let (tol_means, tol_var) = mean_and_variance(&tol_estimates);
+        let (_, tdl_var) = mean_and_variance(&tdl_estimates);
+
+        for (i, v) in tol_means.into_iter().enumerate() {
+            let v_true = (i + 1) as f64 / (N_STATES + 1) as f64;
+
+            assert!((v - v_true).abs() < 0.2);
+        }
+
+        assert!(tol_var < tdl_var);
+    }
+}